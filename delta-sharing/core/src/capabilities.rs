@@ -8,7 +8,8 @@ use http::header::HeaderMap;
 
 use crate::Error;
 
-const DELTA_SHARING_CAPABILITIES: &str = "delta-sharing-capabilities";
+/// Name of the header used to communicate capabilities.
+pub const DELTA_SHARING_CAPABILITIES: &str = "delta-sharing-capabilities";
 
 /// The format of the response that the client can accept.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,6 +32,15 @@ impl FromStr for ResponseFormat {
     }
 }
 
+impl std::fmt::Display for ResponseFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parquet => f.write_str("parquet"),
+            Self::Delta => f.write_str("delta"),
+        }
+    }
+}
+
 /// Capabilities of the client.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Capabilities {
@@ -91,6 +101,64 @@ impl Capabilities {
     pub fn reader_features(&self) -> &[String] {
         self.reader_features.as_slice()
     }
+
+    /// Negotiate the capabilities used to respond to the client.
+    ///
+    /// The response format is the first format requested by the client that is also supported
+    /// by the server, falling back to [`ResponseFormat::Parquet`]. Reader features are limited
+    /// to those supported by both sides.
+    ///
+    /// # Example
+    /// ```
+    /// use delta_sharing_core::capabilities::{Capabilities, ResponseFormat};
+    ///
+    /// let client = Capabilities::new(vec![ResponseFormat::Delta, ResponseFormat::Parquet], vec![]);
+    /// let server = Capabilities::new(vec![ResponseFormat::Parquet], vec![]);
+    /// let negotiated = client.negotiate(&server);
+    /// assert_eq!(negotiated.response_formats(), &[ResponseFormat::Parquet]);
+    /// ```
+    pub fn negotiate(&self, supported: &Capabilities) -> Capabilities {
+        let response_format = self
+            .response_formats
+            .iter()
+            .find(|format| supported.response_formats.contains(format))
+            .copied()
+            .unwrap_or(ResponseFormat::Parquet);
+        let reader_features = self
+            .reader_features
+            .iter()
+            .filter(|feature| supported.reader_features.contains(feature))
+            .cloned()
+            .collect();
+        Capabilities {
+            response_formats: vec![response_format],
+            reader_features,
+        }
+    }
+
+    /// Returns the value of the `delta-sharing-capabilities` header for these capabilities.
+    ///
+    /// # Example
+    /// ```
+    /// use delta_sharing_core::capabilities::{Capabilities, ResponseFormat};
+    ///
+    /// let capabilities = Capabilities::new(vec![ResponseFormat::Delta], vec![]);
+    /// assert_eq!(capabilities.header_value(), "responseformat=delta");
+    /// ```
+    pub fn header_value(&self) -> String {
+        let response_formats = self
+            .response_formats
+            .iter()
+            .map(|format| format.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut value = format!("responseformat={}", response_formats);
+        if !self.reader_features.is_empty() {
+            value.push_str(";readerfeatures=");
+            value.push_str(&self.reader_features.join(","));
+        }
+        value
+    }
 }
 
 impl Default for Capabilities {
@@ -112,10 +180,11 @@ impl TryFrom<&HeaderMap> for Capabilities {
                 Error::Generic(format!("Failed to parse capabilities header: {}", e))
             })?;
             for capability in capability_header.split(';') {
-                let (capability_key, capability_value) = capability.split_once('=').ok_or(
-                    Error::Generic(format!("Failed to parse capability: {}", capability)),
-                )?;
-                match capability_key {
+                let Some((capability_key, capability_value)) = capability.split_once('=') else {
+                    tracing::warn!(capability, "encountered malformed capability");
+                    continue;
+                };
+                match capability_key.trim().to_lowercase().as_str() {
                     "responseformat" => {
                         capabilities.response_formats = capability_value
                             .split(',')
@@ -128,7 +197,7 @@ impl TryFrom<&HeaderMap> for Capabilities {
                             .map(|s| s.trim().to_lowercase())
                             .collect();
                     }
-                    _ => {
+                    capability_key => {
                         tracing::warn!(
                             capability = capability_key,
                             "encountered unrecognized capability"
@@ -176,4 +245,42 @@ mod test {
             vec!["feature1".to_string(), "feature2".to_string()]
         );
     }
+
+    #[test]
+    fn test_capabilities_ignore_unknown() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            DELTA_SHARING_CAPABILITIES,
+            "unknown=value;garbage;responseformat=delta"
+                .parse()
+                .unwrap(),
+        );
+
+        let capabilities = Capabilities::try_from(&headers).unwrap();
+        assert_eq!(capabilities.response_formats(), vec![ResponseFormat::Delta]);
+        assert_eq!(capabilities.reader_features(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_negotiate_capabilities() {
+        let client = Capabilities::new(
+            vec![ResponseFormat::Delta, ResponseFormat::Parquet],
+            vec!["deletionVectors".to_string(), "columnMapping".to_string()],
+        );
+        let server = Capabilities::new(
+            vec![ResponseFormat::Parquet, ResponseFormat::Delta],
+            vec!["deletionVectors".to_string()],
+        );
+
+        let negotiated = client.negotiate(&server);
+        assert_eq!(negotiated.response_formats(), vec![ResponseFormat::Delta]);
+        assert_eq!(negotiated.reader_features(), vec!["deletionvectors"]);
+        assert_eq!(
+            negotiated.header_value(),
+            "responseformat=delta;readerfeatures=deletionvectors"
+        );
+
+        let negotiated = Capabilities::new(vec![], vec![]).negotiate(&server);
+        assert_eq!(negotiated.response_formats(), vec![ResponseFormat::Parquet]);
+    }
 }
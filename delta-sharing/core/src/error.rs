@@ -8,8 +8,8 @@ pub enum Error {
     #[error("kernel error: {0}")]
     Kernel(#[from] delta_kernel::Error),
 
-    #[error("Entity not found.")]
-    NotFound,
+    #[error("Entity not found: {0}")]
+    NotFound(String),
 
     #[error("No or invalid token provided.")]
     Unauthenticated,
@@ -17,13 +17,57 @@ pub enum Error {
     #[error("Recipient is not allowed to read the entity.")]
     NotAllowed,
 
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Too many requests, please retry later.")]
+    RateLimited,
+
     #[error("Invalid table location: {0}")]
     InvalidTableLocation(String),
 
+    #[error("Malformed pagination: {0}")]
+    MalformedPagination(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Invalid configuration: {message}")]
+    InvalidConfig {
+        message: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     #[error("Generic error: {0}")]
     Generic(String),
 }
 
+impl Error {
+    /// Create an [`Error::InvalidConfig`] that preserves the underlying error as its source.
+    pub fn invalid_config(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Error::InvalidConfig {
+            message: message.into(),
+            source: source.into(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                Error::Unavailable(e.to_string())
+            }
+            _ => Error::Generic(e.to_string()),
+        }
+    }
+}
+
 impl From<JwtError> for Error {
     fn from(e: JwtError) -> Self {
         match e.kind() {
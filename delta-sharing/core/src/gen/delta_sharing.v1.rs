@@ -41,6 +41,9 @@ pub struct Table {
     /// A unique identifier for the share this table belongs to.
     #[prost(string, optional, tag="5")]
     pub share_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The time the table was last updated, formatted as RFC 3339.
+    #[prost(string, optional, tag="6")]
+    pub last_updated: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
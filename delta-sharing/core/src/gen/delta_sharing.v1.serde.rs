@@ -1756,6 +1756,9 @@ impl serde::Serialize for Table {
         if self.share_id.is_some() {
             len += 1;
         }
+        if self.last_updated.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("delta_sharing.v1.Table", len)?;
         if let Some(v) = self.id.as_ref() {
             struct_ser.serialize_field("id", v)?;
@@ -1772,6 +1775,9 @@ impl serde::Serialize for Table {
         if let Some(v) = self.share_id.as_ref() {
             struct_ser.serialize_field("shareId", v)?;
         }
+        if let Some(v) = self.last_updated.as_ref() {
+            struct_ser.serialize_field("lastUpdated", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -1788,6 +1794,8 @@ impl<'de> serde::Deserialize<'de> for Table {
             "share",
             "share_id",
             "shareId",
+            "last_updated",
+            "lastUpdated",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1797,6 +1805,7 @@ impl<'de> serde::Deserialize<'de> for Table {
             Schema,
             Share,
             ShareId,
+            LastUpdated,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -1823,6 +1832,7 @@ impl<'de> serde::Deserialize<'de> for Table {
                             "schema" => Ok(GeneratedField::Schema),
                             "share" => Ok(GeneratedField::Share),
                             "shareId" | "share_id" => Ok(GeneratedField::ShareId),
+                            "lastUpdated" | "last_updated" => Ok(GeneratedField::LastUpdated),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1847,6 +1857,7 @@ impl<'de> serde::Deserialize<'de> for Table {
                 let mut schema__ = None;
                 let mut share__ = None;
                 let mut share_id__ = None;
+                let mut last_updated__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Id => {
@@ -1879,6 +1890,12 @@ impl<'de> serde::Deserialize<'de> for Table {
                             }
                             share_id__ = map_.next_value()?;
                         }
+                        GeneratedField::LastUpdated => {
+                            if last_updated__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("lastUpdated"));
+                            }
+                            last_updated__ = map_.next_value()?;
+                        }
                     }
                 }
                 Ok(Table {
@@ -1887,6 +1904,7 @@ impl<'de> serde::Deserialize<'de> for Table {
                     schema: schema__.unwrap_or_default(),
                     share: share__.unwrap_or_default(),
                     share_id: share_id__,
+                    last_updated: last_updated__,
                 })
             }
         }
@@ -1,12 +1,24 @@
-use std::sync::Arc;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, PoisonError, RwLock};
 
+use chrono::{DateTime, SecondsFormat, Utc};
+use dashmap::mapref::one::Ref;
 use dashmap::DashMap;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::ObjectStore;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{Error, Result};
+use crate::pagination::{paginate_versioned, OrderBy, Page, Pagination};
+use crate::tree::{table_matches, CatalogTree, SchemaNode, ShareNode};
 use crate::types as t;
-use crate::{DiscoveryHandler, TableLocationResover};
+use crate::{
+    Decision, DiscoveryHandler, Permission, Policy, Resource, TableFormat, TableLocationResover,
+};
 
 #[cfg(feature = "profiles")]
 use crate::profiles::DeltaRecipient;
@@ -15,52 +27,751 @@ pub type DefaultInMemoryHandler = InMemoryHandler<DeltaRecipient>;
 #[cfg(not(feature = "profiles"))]
 pub type DefaultInMemoryHandler = InMemoryHandler<()>;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Prefix of property keys that must not be exposed to recipients.
+pub const PRIVATE_PROPERTY_PREFIX: &str = "_";
+
+/// Prefix of table property keys holding storage options of the table, e.g. `_storage.region`.
+///
+/// The prefix starts with [`PRIVATE_PROPERTY_PREFIX`], so storage options are never exposed
+/// to recipients.
+pub const STORAGE_OPTION_PREFIX: &str = "_storage.";
+
+/// Kind of a shared table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TableKind {
+    /// A delta table stored at the table location.
+    #[default]
+    Table,
+    /// A view over delta tables defined by a SQL query.
+    ///
+    /// Views are listed like tables, but cannot be queried yet.
+    View { definition: String },
+}
+
+impl TableKind {
+    /// Returns true for [`TableKind::Table`].
+    pub fn is_table(&self) -> bool {
+        matches!(self, TableKind::Table)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct TableConfig {
+    /// Id of the table reported to recipients.
+    ///
+    /// Defaults to a UUID derived from the names of the share, schema and table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Name schemas refer to the table by.
+    ///
+    /// A config named by the fully qualified name of a table, e.g. `share1.schema1.table1`,
+    /// defines the table for that share and schema only. It takes precedence over a config
+    /// named `table1`, so a table name can be reused with different settings.
     pub name: String,
+    /// Other names the table can be requested by, e.g. its names before it was renamed.
+    ///
+    /// Listings only contain the table under its `name`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(alias = "storage_location", alias = "storageLocation")]
     pub location: String,
+    #[serde(default)]
+    pub format: TableFormat,
+    /// Whether the table is a delta table or a view.
+    #[serde(default, skip_serializing_if = "TableKind::is_table")]
+    pub kind: TableKind,
+    /// Columns the table is partitioned by.
+    #[serde(
+        default,
+        alias = "partition_columns",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub partition_columns: Vec<String>,
+    /// Time the table was last updated, as reported in table listings.
+    ///
+    /// This is not derived from the delta log, so it is only known if configured.
+    #[serde(
+        default,
+        alias = "last_updated",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_updated: Option<DateTime<Utc>>,
+    /// Time the table was added to the config, used to order listings by creation time.
+    #[serde(default, alias = "created_at", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Extension properties of the table.
+    ///
+    /// Properties with keys starting with [`PRIVATE_PROPERTY_PREFIX`] are only visible
+    /// to the server.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
+impl TableConfig {
+    /// Create the config of a parquet table stored at `location`.
+    pub fn new(name: impl Into<String>, location: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            aliases: Vec::new(),
+            location: location.into(),
+            format: TableFormat::default(),
+            kind: TableKind::default(),
+            partition_columns: Vec::new(),
+            last_updated: None,
+            created_at: None,
+            properties: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaConfig {
+    /// Name shares refer to the schema by.
+    ///
+    /// Like for tables, a config named by the fully qualified name of a schema, e.g.
+    /// `share1.schema1`, defines the schema for that share only.
     pub name: String,
+    #[serde(alias = "table_refs")]
     pub table_refs: Vec<String>,
+    /// Time the schema was added to the config, used to order listings by creation time.
+    #[serde(default, alias = "created_at", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl SchemaConfig {
+    /// Create the config of a schema containing the tables named in `table_refs`.
+    pub fn new(
+        name: impl Into<String>,
+        table_refs: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            table_refs: table_refs.into_iter().map(Into::into).collect(),
+            created_at: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ShareConfig {
     pub name: String,
+    #[serde(alias = "schema_refs")]
     pub schema_refs: Vec<String>,
+    /// Extension properties of the share, which can be used to filter listings.
+    ///
+    /// Properties with keys starting with [`PRIVATE_PROPERTY_PREFIX`] are only visible
+    /// to the server.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+    /// Schema used when a request for the share does not specify a schema.
+    #[serde(
+        default,
+        alias = "default_schema",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_schema: Option<String>,
+    /// Time the share was added to the config, used to order listings by creation time.
+    #[serde(default, alias = "created_at", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ShareConfig {
+    /// Create the config of a share containing the schemas named in `schema_refs`.
+    pub fn new(
+        name: impl Into<String>,
+        schema_refs: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            schema_refs: schema_refs.into_iter().map(Into::into).collect(),
+            properties: HashMap::new(),
+            default_schema: None,
+            created_at: None,
+        }
+    }
+}
+
+/// Configuration of the shares, schemas and tables served by an [`InMemoryHandler`].
+///
+/// Keys are spelled in camelCase, e.g. `schemaRefs`. Multi-word keys are also accepted in
+/// snake_case, e.g. `schema_refs`, and the `location` of a table as `storage_location`.
+/// Configs are always serialized with the camelCase keys.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct InMemoryConfig {
+    #[serde(default)]
     pub shares: Vec<ShareConfig>,
+    #[serde(default)]
     pub schemas: Vec<SchemaConfig>,
+    #[serde(default)]
     pub tables: Vec<TableConfig>,
+    /// Base url that relative table locations are resolved against.
+    #[serde(
+        default,
+        alias = "storage_root",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub storage_root: Option<String>,
+    /// Options for accessing the storage of all tables, e.g. credentials of the object store.
+    ///
+    /// Tables override these options with properties prefixed by [`STORAGE_OPTION_PREFIX`].
+    #[serde(
+        default,
+        alias = "storage_options",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub storage_options: HashMap<String, String>,
 }
 
-pub struct InMemoryHandler<T: Send + Sync> {
-    // The data in memory
-    shares: Arc<DashMap<String, Vec<String>>>,
-    schemas: Arc<DashMap<String, Vec<String>>>,
-    tables: Arc<DashMap<String, TableConfig>>,
-    _phantom: std::marker::PhantomData<T>,
+/// Likely mistake in an [`InMemoryConfig`], which does not prevent serving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// A share without schemas.
+    EmptyShare { share: String },
+    /// A schema without tables.
+    EmptySchema { schema: String },
+    /// A schema that is not contained in any share.
+    UnreferencedSchema { schema: String },
+    /// A table that is not contained in any schema.
+    UnreferencedTable { table: String },
+    /// A table location that looks like a template placeholder, e.g. `s3://<bucket-name>/table`.
+    PlaceholderLocation { table: String, location: String },
 }
 
-impl<T: Send + Sync> InMemoryHandler<T> {
-    pub fn new(config: InMemoryConfig) -> Self {
-        let shares = Arc::new(DashMap::new());
-        let schemas = Arc::new(DashMap::new());
-        let tables = Arc::new(DashMap::new());
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyShare { share } => write!(f, "share '{}' contains no schemas", share),
+            Self::EmptySchema { schema } => write!(f, "schema '{}' contains no tables", schema),
+            Self::UnreferencedSchema { schema } => {
+                write!(f, "schema '{}' is not contained in any share", schema)
+            }
+            Self::UnreferencedTable { table } => {
+                write!(f, "table '{}' is not contained in any schema", table)
+            }
+            Self::PlaceholderLocation { table, location } => write!(
+                f,
+                "location '{}' of table '{}' looks like a placeholder",
+                location, table
+            ),
+        }
+    }
+}
+
+/// Whether `location` contains a template placeholder like `<bucket-name>` or `${BUCKET}`.
+fn is_placeholder(location: &str) -> bool {
+    let enclosed = |open: &str, close: char| {
+        location
+            .find(open)
+            .is_some_and(|start| location[start..].contains(close))
+    };
+    enclosed("<", '>') || enclosed("${", '}') || enclosed("{{", '}')
+}
+
+/// Kind of an entry of the catalog described by an [`InMemoryConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CatalogItem {
+    Share,
+    Schema,
+    Table,
+}
+
+impl std::fmt::Display for CatalogItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Share => f.write_str("share"),
+            Self::Schema => f.write_str("schema"),
+            Self::Table => f.write_str("table"),
+        }
+    }
+}
+
+/// Entry of a [`CatalogDiff`], identified by its fully qualified name.
+///
+/// Names are qualified like `share.schema.table` and quoted as [`t::TableRef::parse`]
+/// expects.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CatalogEntry {
+    pub item: CatalogItem,
+    pub name: String,
+}
+
+impl std::fmt::Display for CatalogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.item, self.name)
+    }
+}
+
+/// Differences between the catalogs described by two [`InMemoryConfig`]s.
+///
+/// Entries are ordered by kind, shares first, and by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CatalogDiff {
+    /// Entries that only exist in the new config.
+    pub added: Vec<CatalogEntry>,
+    /// Entries that only exist in the old config.
+    pub removed: Vec<CatalogEntry>,
+    /// Entries that exist in both configs, but whose settings differ.
+    pub changed: Vec<CatalogEntry>,
+}
+
+impl CatalogDiff {
+    /// Returns true if both configs describe the same catalog.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for CatalogDiff {
+    /// Format one line per entry, prefixed by `+` if added, `-` if removed and `~` if changed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (prefix, entries) in [
+            ("+", &self.added),
+            ("-", &self.removed),
+            ("~", &self.changed),
+        ] {
+            for entry in entries {
+                writeln!(f, "{} {}", prefix, entry)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InMemoryConfig {
+    /// Compute the entries of the catalog together with a digest of their settings.
+    ///
+    /// Schemas and tables are listed once per share containing them. Digests of schemas
+    /// and tables that are referenced but not defined are `None`.
+    fn catalog_entries(&self) -> BTreeMap<CatalogEntry, Option<u64>> {
+        let schemas = self
+            .schemas
+            .iter()
+            .map(|schema| (schema.name.as_str(), schema))
+            .collect::<HashMap<_, _>>();
+        let tables = self
+            .tables
+            .iter()
+            .map(|table| (table.name.as_str(), table))
+            .collect::<HashMap<_, _>>();
+        let entry = |item, name| CatalogEntry { item, name };
+        let mut entries = BTreeMap::new();
+        for share in &self.shares {
+            entries.insert(
+                entry(CatalogItem::Share, t::quoted(&share.name)),
+                Some(share_digest(share)),
+            );
+            for schema_ref in &share.schema_refs {
+                let name = t::schema_fqn(&share.name, schema_ref);
+                let schema = schemas
+                    .get(name.as_str())
+                    .or_else(|| schemas.get(schema_ref.as_str()));
+                let digest = schema.map(|schema| {
+                    let mut hasher = DefaultHasher::new();
+                    schema.created_at.hash(&mut hasher);
+                    hasher.finish()
+                });
+                entries.insert(entry(CatalogItem::Schema, name), digest);
+                for table_ref in schema.iter().flat_map(|schema| &schema.table_refs) {
+                    let name = t::TableRef {
+                        share: share.name.clone(),
+                        schema: schema_ref.clone(),
+                        table: table_ref.clone(),
+                    };
+                    let name = name.to_string();
+                    let digest = tables
+                        .get(name.as_str())
+                        .or_else(|| tables.get(table_ref.as_str()))
+                        .map(|table| table_digest(table));
+                    entries.insert(entry(CatalogItem::Table, name), digest);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Compare the catalog described by this config with the one described by `other`.
+    ///
+    /// Entries are matched by their fully qualified names, so renaming a share, schema or
+    /// table removes the entries under the old name and adds them under the new one. An entry
+    /// is changed if any of its own settings differ, e.g. the location of a table. Adding or
+    /// removing the schemas of a share or the tables of a schema only adds or removes the
+    /// contained entries.
+    pub fn diff(&self, other: &InMemoryConfig) -> CatalogDiff {
+        let before = self.catalog_entries();
+        let mut after = other.catalog_entries();
+        let mut diff = CatalogDiff::default();
+        for (entry, digest) in before {
+            match after.remove(&entry) {
+                Some(other) if other != digest => diff.changed.push(entry),
+                Some(_) => {}
+                None => diff.removed.push(entry),
+            }
+        }
+        diff.added.extend(after.into_keys());
+        diff
+    }
+
+    /// Report likely mistakes in the config, in the order of the shares, schemas and tables.
+    ///
+    /// Unlike [`InMemoryConfig::validate`], the warnings do not prevent serving the config.
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+        for share in self
+            .shares
+            .iter()
+            .filter(|share| share.schema_refs.is_empty())
+        {
+            warnings.push(LintWarning::EmptyShare {
+                share: share.name.clone(),
+            });
+        }
+        // schemas and tables are also referenced by their fully qualified names.
+        let schema_refs = self
+            .shares
+            .iter()
+            .flat_map(|share| {
+                share
+                    .schema_refs
+                    .iter()
+                    .flat_map(|schema| [schema.clone(), t::schema_fqn(&share.name, schema)])
+            })
+            .collect::<HashSet<_>>();
+        for schema in &self.schemas {
+            if schema.table_refs.is_empty() {
+                warnings.push(LintWarning::EmptySchema {
+                    schema: schema.name.clone(),
+                });
+            }
+            if !schema_refs.contains(&schema.name) {
+                warnings.push(LintWarning::UnreferencedSchema {
+                    schema: schema.name.clone(),
+                });
+            }
+        }
+        let mut table_refs = self
+            .schemas
+            .iter()
+            .flat_map(|schema| schema.table_refs.iter().cloned())
+            .collect::<HashSet<_>>();
+        for share in &self.shares {
+            for schema_ref in &share.schema_refs {
+                let qualified = t::schema_fqn(&share.name, schema_ref);
+                for schema in self
+                    .schemas
+                    .iter()
+                    .filter(|schema| schema.name == *schema_ref || schema.name == qualified)
+                {
+                    table_refs.extend(
+                        schema
+                            .table_refs
+                            .iter()
+                            .map(|table| t::table_fqn(&share.name, schema_ref, table)),
+                    );
+                }
+            }
+        }
+        for table in &self.tables {
+            if !table_refs.contains(&table.name) {
+                warnings.push(LintWarning::UnreferencedTable {
+                    table: table.name.clone(),
+                });
+            }
+            if is_placeholder(&table.location) {
+                warnings.push(LintWarning::PlaceholderLocation {
+                    table: table.name.clone(),
+                    location: table.location.clone(),
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Check that the config is consistent.
+    ///
+    /// Table aliases must neither be the name of another table nor an alias of another table,
+    /// so every name resolves to a single table.
+    pub fn validate(&self) -> Result<()> {
+        let names = self
+            .tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .collect::<HashSet<_>>();
+        let mut aliases = HashMap::<&str, &str>::new();
+        for table in &self.tables {
+            for alias in table.aliases.iter().filter(|alias| **alias != table.name) {
+                if names.contains(alias.as_str()) {
+                    return Err(Error::invalid_config(
+                        format!("alias '{}' of table '{}' is invalid", alias, table.name),
+                        format!("'{}' is the name of another table", alias),
+                    ));
+                }
+                if let Some(other) = aliases.insert(alias, &table.name) {
+                    if other != table.name {
+                        return Err(Error::invalid_config(
+                            format!("alias '{}' of table '{}' is invalid", alias, table.name),
+                            format!("'{}' is also an alias of table '{}'", alias, other),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<ShareConfig> for InMemoryConfig {
+    fn from_iter<I: IntoIterator<Item = ShareConfig>>(iter: I) -> Self {
+        let mut config = Self::default();
+        config.extend(iter);
+        config
+    }
+}
+
+impl Extend<ShareConfig> for InMemoryConfig {
+    fn extend<I: IntoIterator<Item = ShareConfig>>(&mut self, iter: I) {
+        self.shares.extend(iter);
+    }
+}
+
+impl Extend<SchemaConfig> for InMemoryConfig {
+    fn extend<I: IntoIterator<Item = SchemaConfig>>(&mut self, iter: I) {
+        self.schemas.extend(iter);
+    }
+}
+
+impl Extend<TableConfig> for InMemoryConfig {
+    fn extend<I: IntoIterator<Item = TableConfig>>(&mut self, iter: I) {
+        self.tables.extend(iter);
+    }
+}
+
+/// Generate the JSON Schema for the [`InMemoryConfig`] file format.
+///
+/// The schema can be used by editors to provide autocompletion and validation
+/// when authoring configuration files.
+#[cfg(feature = "schema")]
+pub fn config_json_schema() -> serde_json::Value {
+    let schema = schemars::schema_for!(InMemoryConfig);
+    serde_json::to_value(schema).expect("JSON Schema should serialize to JSON")
+}
+
+/// Options to filter the results of a listing on the server.
+///
+/// # Example
+/// ```
+/// use delta_sharing_core::ListOptions;
+///
+/// let options = ListOptions::default().with_property("tier", "gold");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    properties: Vec<(String, String)>,
+    order_by: Option<OrderBy>,
+}
+
+impl ListOptions {
+    /// Only return items whose extension property `key` is set to `value`.
+    ///
+    /// Multiple properties can be given, in which case all of them must match.
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// List the items in the given ordering, instead of ordered by name.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    fn matches(&self, properties: Option<&HashMap<String, String>>) -> bool {
+        self.properties.iter().all(|(key, value)| {
+            properties
+                .and_then(|properties| properties.get(key))
+                .is_some_and(|v| v == value)
+        })
+    }
+}
+
+/// Options to filter the tables returned by the table listings of an [`InMemoryHandler`].
+///
+/// # Example
+/// ```
+/// use delta_sharing_core::ListTableOptions;
+///
+/// let options = ListTableOptions::default().partitioned_by("date");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListTableOptions {
+    pub partitioned_by: Option<String>,
+    pub order_by: Option<OrderBy>,
+}
+
+impl ListTableOptions {
+    /// Only return tables that are partitioned by `column`.
+    pub fn partitioned_by(mut self, column: impl Into<String>) -> Self {
+        self.partitioned_by = Some(column.into());
+        self
+    }
+
+    /// List the tables in the given ordering, instead of ordered by name.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    fn matches(&self, table: &TableConfig) -> bool {
+        self.partitioned_by
+            .as_ref()
+            .is_none_or(|column| table.partition_columns.contains(column))
+    }
+}
+
+fn pagination(
+    max_results: Option<i32>,
+    page_token: Option<String>,
+    order_by: Option<OrderBy>,
+) -> Pagination {
+    let pagination = Pagination::new(max_results, page_token);
+    match order_by {
+        Some(order_by) => pagination.with_order_by(order_by),
+        None => pagination,
+    }
+}
+
+/// An item returned by the listings, which can be ordered by an [`OrderBy`].
+trait Listed {
+    /// Key used when ordering by name.
+    fn name_key(&self) -> (&str, &str);
+
+    /// Key used when ordering by id.
+    fn id(&self) -> Option<&str>;
+
+    /// Key used when ordering by creation time.
+    fn created_at(&self, catalog: &Catalog) -> Option<DateTime<Utc>>;
+}
+
+impl Listed for t::Share {
+    fn name_key(&self) -> (&str, &str) {
+        (&self.name, "")
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn created_at(&self, catalog: &Catalog) -> Option<DateTime<Utc>> {
+        catalog.share_created_at.get(&self.name).map(|at| *at)
+    }
+}
+
+impl Listed for t::Schema {
+    fn name_key(&self) -> (&str, &str) {
+        (&self.name, "")
+    }
+
+    fn id(&self) -> Option<&str> {
+        None
+    }
+
+    fn created_at(&self, catalog: &Catalog) -> Option<DateTime<Utc>> {
+        let key = catalog.schema_key(&self.share, &self.name);
+        catalog.schema_created_at.get(&key).map(|at| *at)
+    }
+}
+
+impl Listed for t::Table {
+    fn name_key(&self) -> (&str, &str) {
+        (&self.schema, &self.name)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn created_at(&self, catalog: &Catalog) -> Option<DateTime<Utc>> {
+        catalog
+            .tables
+            .get(&catalog.table_key(&self.share, &self.schema, &self.name))
+            .and_then(|table| table.created_at)
+    }
+}
+
+/// Sort items collected in insertion order by the given ordering.
+fn sort_listing<L: Listed>(items: &mut [L], order_by: OrderBy, catalog: &Catalog) {
+    match order_by {
+        OrderBy::Name => items.sort_by(|a, b| a.name_key().cmp(&b.name_key())),
+        OrderBy::Id => items.sort_by(|a, b| (a.id(), a.name_key()).cmp(&(b.id(), b.name_key()))),
+        OrderBy::CreatedAt => items.sort_by(|a, b| {
+            let (a_at, b_at) = (a.created_at(catalog), b.created_at(catalog));
+            // items without a creation time are listed last.
+            (a_at.is_none(), a_at, a.name_key()).cmp(&(b_at.is_none(), b_at, b.name_key()))
+        }),
+        OrderBy::Insertion => (),
+    }
+}
+
+/// The shares, schemas and tables loaded from an [`InMemoryConfig`].
+struct Catalog {
+    shares: DashMap<String, Vec<String>>,
+    // Names of the shares in the order they are configured.
+    share_order: Vec<String>,
+    share_properties: DashMap<String, HashMap<String, String>>,
+    default_schemas: DashMap<String, String>,
+    share_created_at: DashMap<String, DateTime<Utc>>,
+    schemas: DashMap<String, Vec<String>>,
+    schema_created_at: DashMap<String, DateTime<Utc>>,
+    tables: DashMap<String, TableConfig>,
+    // Canonical names of the tables by their aliases.
+    table_aliases: DashMap<String, String>,
+    storage_root: Option<String>,
+    storage_options: HashMap<String, String>,
+    // Hash of the config, used to detect page tokens issued for a different config.
+    version: u64,
+}
+
+impl Catalog {
+    fn new(config: InMemoryConfig) -> Self {
+        let version = content_version(&config);
+        let shares = DashMap::new();
+        let mut share_order = Vec::new();
+        let share_properties = DashMap::new();
+        let default_schemas = DashMap::new();
+        let share_created_at = DashMap::new();
+        let schemas = DashMap::new();
+        let schema_created_at = DashMap::new();
+        let tables = DashMap::new();
+        let table_aliases = DashMap::new();
 
         for share in config.shares {
+            if !share.properties.is_empty() {
+                share_properties.insert(share.name.clone(), share.properties);
+            }
+            if let Some(default_schema) = share.default_schema {
+                default_schemas.insert(share.name.clone(), default_schema);
+            }
+            if let Some(created_at) = share.created_at {
+                share_created_at.insert(share.name.clone(), created_at);
+            }
+            share_order.push(share.name.clone());
             shares.insert(share.name, share.schema_refs);
         }
 
         for schema in config.schemas {
+            if let Some(created_at) = schema.created_at {
+                schema_created_at.insert(schema.name.clone(), created_at);
+            }
             schemas.insert(schema.name, schema.table_refs);
         }
 
@@ -68,169 +779,857 @@ impl<T: Send + Sync> InMemoryHandler<T> {
             tables.insert(table.name.clone(), table);
         }
 
-        Self {
+        let catalog = Self {
             shares,
+            share_order,
+            share_properties,
+            default_schemas,
+            share_created_at,
             schemas,
+            schema_created_at,
             tables,
-            _phantom: std::marker::PhantomData,
+            table_aliases,
+            storage_root: config.storage_root,
+            storage_options: config.storage_options,
+            version,
+        };
+        // aliases are resolved within the schema of a share, like the names of the tables.
+        for share in catalog.shares.iter() {
+            for schema in share.value() {
+                let Some(table_refs) = catalog
+                    .schemas
+                    .get(&catalog.schema_key(share.key(), schema))
+                else {
+                    continue;
+                };
+                for table_ref in table_refs.iter() {
+                    let key = catalog.table_key(share.key(), schema, table_ref);
+                    let Some(table) = catalog.tables.get(&key) else {
+                        continue;
+                    };
+                    for alias in &table.aliases {
+                        catalog
+                            .table_aliases
+                            .insert(t::table_fqn(share.key(), schema, alias), table_ref.clone());
+                    }
+                }
+            }
         }
+        catalog
     }
-}
-
-#[async_trait::async_trait]
-impl<T: Send + Sync> DiscoveryHandler for InMemoryHandler<T> {
-    type Recipient = T;
 
-    async fn list_shares(
-        &self,
-        _request: t::ListSharesRequest,
-        _recipient: Self::Recipient,
-    ) -> Result<t::ListSharesResponse> {
-        let shares = self
-            .shares
-            .iter()
-            .map(|share| {
-                let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, share.key().as_bytes());
-                t::Share {
-                    id: Some(id.into()),
-                    name: share.key().clone(),
-                }
-            })
-            .collect();
-        Ok(t::ListSharesResponse {
-            items: shares,
-            next_page_token: None,
-        })
+    /// Get the name of the config of `schema` in `share`.
+    ///
+    /// A schema config named by the fully qualified name of the schema takes precedence over
+    /// one named by the schema name alone.
+    fn schema_key(&self, share: &str, schema: &str) -> String {
+        let qualified = t::schema_fqn(share, schema);
+        if self.schemas.contains_key(&qualified) {
+            return qualified;
+        }
+        schema.to_string()
     }
 
-    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
-        if self.shares.contains_key(&request.share) {
-            let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
-            Ok(t::GetShareResponse {
-                share: Some(t::Share {
-                    id: Some(id.to_string()),
-                    name: request.share,
-                }),
-            })
-        } else {
-            Err(Error::NotFound)
+    /// Get the name of the config of `table` in `share.schema`.
+    ///
+    /// Like for schemas, a table config named by the fully qualified name of the table takes
+    /// precedence.
+    fn table_key(&self, share: &str, schema: &str, table: &str) -> String {
+        let qualified = t::table_fqn(share, schema, table);
+        if self.tables.contains_key(&qualified) {
+            return qualified;
         }
+        table.to_string()
     }
 
-    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
-        match self.shares.get(&request.share) {
-            Some(schema_refs) => {
-                let schemas = schema_refs
-                    .iter()
-                    .map(|schema_ref| t::Schema {
-                        name: schema_ref.clone(),
-                        share: request.share.clone(),
-                    })
-                    .collect();
-                Ok(t::ListSchemasResponse {
-                    items: schemas,
-                    next_page_token: None,
-                })
+    /// Resolve a table location to an absolute url.
+    ///
+    /// Absolute locations are returned unchanged, while relative locations are resolved
+    /// against the configured storage root.
+    fn location_url(&self, location: &str) -> Result<url::Url> {
+        let invalid = || Error::InvalidTableLocation(location.to_string());
+        match url::Url::parse(location) {
+            Ok(url) => Ok(url),
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                let root = self.storage_root.as_ref().ok_or_else(invalid)?;
+                // make sure the last segment of the root is kept when joining.
+                let root = if root.ends_with('/') {
+                    url::Url::parse(root)
+                } else {
+                    url::Url::parse(&format!("{}/", root))
+                }
+                .map_err(|_| invalid())?;
+                root.join(location.trim_start_matches('/'))
+                    .map_err(|_| invalid())
             }
-            None => Err(Error::NotFound),
+            Err(_) => Err(invalid()),
         }
     }
 
-    async fn list_schema_tables(
-        &self,
-        request: t::ListSchemaTablesRequest,
-    ) -> Result<t::ListSchemaTablesResponse> {
-        let schema_refs = self.shares.get(&request.share).ok_or(Error::NotFound)?;
-        if !schema_refs.contains(&request.schema) {
-            return Err(Error::NotFound);
-        }
-        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
-        match self.schemas.get(&request.schema) {
-            Some(tables) => {
-                let tables = tables
-                    .iter()
-                    .flat_map(|table_ref| {
-                        self.tables.get(table_ref).map(|v| t::Table {
-                            id: Some(Uuid::new_v5(&share_id, v.name.as_bytes()).to_string()),
-                            name: v.name.clone(),
-                            share: request.share.clone(),
-                            schema: request.schema.clone(),
-                            share_id: Some(share_id.to_string()),
-                        })
-                    })
-                    .collect();
-                Ok(t::ListSchemaTablesResponse {
-                    items: tables,
-                    next_page_token: None,
-                })
-            }
-            None => Err(crate::error::Error::NotFound),
+    /// Resolve the location of the data of a table.
+    ///
+    /// Views have no data of their own, so they are rejected until they can be materialized.
+    fn table_url(&self, table: &TableConfig) -> Result<url::Url> {
+        if let TableKind::View { .. } = table.kind {
+            return Err(Error::InvalidArgument(format!(
+                "table '{}' is a view, querying views is not supported",
+                table.name
+            )));
         }
+        self.location_url(&table.location)
     }
 
-    async fn list_share_tables(
-        &self,
-        request: t::ListShareTablesRequest,
-    ) -> Result<t::ListShareTablesResponse> {
-        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
-        match self.shares.get(&request.share) {
-            Some(schema_refs) => {
-                let tables = schema_refs
-                    .iter()
-                    .flat_map(|schema_ref| {
-                        self.schemas.get(schema_ref).map(|v| {
-                            v.iter()
-                                .flat_map(|table_ref| {
-                                    self.tables.get(table_ref).map(|v| t::Table {
-                                        id: Some(
-                                            Uuid::new_v5(&share_id, v.name.as_bytes()).to_string(),
-                                        ),
-                                        name: v.name.clone(),
-                                        share: request.share.clone(),
-                                        schema: schema_ref.clone(),
-                                        share_id: Some(share_id.to_string()),
-                                    })
-                                })
-                                .collect::<Vec<_>>()
-                        })
-                    })
-                    .flatten()
-                    .collect();
-                Ok(t::ListShareTablesResponse {
-                    items: tables,
-                    next_page_token: None,
-                })
-            }
-            None => Err(Error::NotFound),
+    /// Get the name of the schema a request refers to.
+    ///
+    /// An empty schema refers to the default schema of the share, if one is configured.
+    fn schema_name(&self, share: &str, schema: &str) -> Result<String> {
+        if !schema.is_empty() {
+            return Ok(schema.to_string());
         }
+        self.default_schemas
+            .get(share)
+            .map(|schema| schema.value().clone())
+            .ok_or_else(|| Error::NotFound(format!("share '{}' has no default schema", share)))
     }
-}
 
-#[async_trait::async_trait]
-impl<T: Send + Sync> TableLocationResover for InMemoryHandler<T> {
-    async fn resolve(&self, table_ref: &t::TableRef) -> Result<url::Url> {
+    /// Get the name a table is listed under from its name or one of its aliases.
+    fn table_name(&self, table_ref: &t::TableRef) -> Result<String> {
         let Some(schemas) = self.shares.get(&table_ref.share) else {
-            return Err(Error::NotFound);
+            return Err(share_not_found(&table_ref.share));
         };
-        if !schemas.contains(&table_ref.schema) {
-            return Err(Error::NotFound);
+        let schema = self.schema_name(&table_ref.share, &table_ref.schema)?;
+        if !schemas.contains(&schema) {
+            return Err(schema_not_found(&table_ref.share, &schema));
         }
-        let Some(tables) = self.schemas.get(&table_ref.schema) else {
-            return Err(Error::NotFound);
+        let Some(tables) = self
+            .schemas
+            .get(&self.schema_key(&table_ref.share, &schema))
+        else {
+            return Err(schema_not_found(&table_ref.share, &schema));
         };
-        if !tables.contains(&table_ref.table) {
-            return Err(Error::NotFound);
+        if tables.contains(&table_ref.table) {
+            return Ok(table_ref.table.clone());
+        }
+        let alias = t::table_fqn(&table_ref.share, &schema, &table_ref.table);
+        match self.table_aliases.get(&alias) {
+            Some(name) if tables.contains(name.value()) => Ok(name.value().clone()),
+            _ => Err(table_not_found(table_ref)),
         }
-        let table = self.tables.get(&table_ref.table).ok_or(Error::NotFound)?;
-        Ok(url::Url::parse(&table.location)
-            .map_err(|_| Error::InvalidTableLocation(table.location.clone()))?)
+    }
+
+    /// Get the config of the table a request refers to by its name or one of its aliases.
+    fn table_config(&self, table_ref: &t::TableRef) -> Result<Ref<'_, String, TableConfig>> {
+        let name = self.table_name(table_ref)?;
+        let schema = self.schema_name(&table_ref.share, &table_ref.schema)?;
+        self.tables
+            .get(&self.table_key(&table_ref.share, &schema, &name))
+            .ok_or_else(|| table_not_found(table_ref))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::profiles::DeltaRecipient;
+pub struct InMemoryHandler<T: Send + Sync> {
+    // The data in memory, replaced as a whole when the config is reloaded.
+    catalog: RwLock<Arc<Catalog>>,
+    // Policy restricting the shares and tables walked on behalf of a recipient.
+    policy: Option<Arc<dyn Policy<Recipient = T>>>,
+}
+
+impl<T: Send + Sync> InMemoryHandler<T> {
+    pub fn new(config: InMemoryConfig) -> Self {
+        Self {
+            catalog: RwLock::new(Arc::new(Catalog::new(config))),
+            policy: None,
+        }
+    }
+
+    /// Only include the shares and tables the `policy` allows the recipient to read in
+    /// [`DiscoveryHandler::describe`], [`DiscoveryHandler::search_tables`] and
+    /// [`InMemoryHandler::distinct_property_keys`].
+    ///
+    /// Without a policy these walk the whole catalog regardless of the recipient. The list
+    /// APIs are not filtered, the server authorizes them per request.
+    pub fn with_policy(mut self, policy: Arc<dyn Policy<Recipient = T>>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Describe the contents of `catalog` readable by `recipient`.
+    ///
+    /// Callers pass the catalog snapshot they use for the rest of the request, so that all
+    /// data of a response comes from the same version of the config.
+    async fn describe_catalog(&self, catalog: &Catalog, recipient: &T) -> Result<CatalogTree> {
+        let mut shares = catalog
+            .shares
+            .iter()
+            .map(|share| {
+                let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, share.key().as_bytes());
+                let mut schemas = share
+                    .value()
+                    .iter()
+                    .map(|schema_ref| {
+                        let mut tables = catalog
+                            .schemas
+                            .get(&catalog.schema_key(share.key(), schema_ref))
+                            .map(|table_refs| {
+                                table_refs
+                                    .iter()
+                                    .flat_map(|table_ref| {
+                                        let key =
+                                            catalog.table_key(share.key(), schema_ref, table_ref);
+                                        catalog.tables.get(&key).map(|v| t::Table {
+                                            id: Some(table_id(
+                                                &v, &share_id, schema_ref, table_ref,
+                                            )),
+                                            name: table_ref.clone(),
+                                            share: share.key().clone(),
+                                            schema: schema_ref.clone(),
+                                            share_id: Some(share_id.to_string()),
+                                            last_updated: last_updated(&v),
+                                        })
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default();
+                        sort_listing(&mut tables, OrderBy::Name, catalog);
+                        SchemaNode {
+                            schema: t::Schema {
+                                name: schema_ref.clone(),
+                                share: share.key().clone(),
+                            },
+                            tables,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                schemas.sort_by(|a, b| a.schema.name.cmp(&b.schema.name));
+                ShareNode {
+                    share: t::Share {
+                        id: Some(share_id.to_string()),
+                        name: share.key().clone(),
+                    },
+                    schemas,
+                }
+            })
+            .collect::<Vec<_>>();
+        shares.sort_by(|a, b| a.share.name.cmp(&b.share.name));
+        let mut readable = Vec::with_capacity(shares.len());
+        for mut share in shares {
+            if !self
+                .can_read(Resource::share(&share.share.name), recipient)
+                .await?
+            {
+                continue;
+            }
+            for schema in &mut share.schemas {
+                let mut tables = Vec::with_capacity(schema.tables.len());
+                for table in std::mem::take(&mut schema.tables) {
+                    let table_ref = t::TableRef {
+                        share: table.share.clone(),
+                        schema: table.schema.clone(),
+                        table: table.name.clone(),
+                    };
+                    if self
+                        .can_read(Resource::table(table_ref.to_string()), recipient)
+                        .await?
+                    {
+                        tables.push(table);
+                    }
+                }
+                schema.tables = tables;
+            }
+            readable.push(share);
+        }
+        Ok(CatalogTree { shares: readable })
+    }
+
+    /// Returns true if the policy allows `recipient` to read `resource`, or no policy is set.
+    async fn can_read(&self, resource: Resource, recipient: &T) -> Result<bool> {
+        let Some(policy) = &self.policy else {
+            return Ok(true);
+        };
+        let decision = policy
+            .authorize(resource, Permission::Read, recipient)
+            .await?;
+        Ok(decision == Decision::Allow)
+    }
+
+    /// Replace the served shares, schemas and tables with the contents of `config`.
+    ///
+    /// Requests in flight keep using the data they started with, while subsequent
+    /// requests observe the new config.
+    pub fn reload(&self, config: InMemoryConfig) {
+        let catalog = Arc::new(Catalog::new(config));
+        *self.catalog.write().unwrap_or_else(PoisonError::into_inner) = catalog;
+    }
+
+    /// Check that the storage locations of all tables can be reached.
+    ///
+    /// Object stores are created from the table locations with [`object_store::parse_url`].
+    /// See [`InMemoryHandler::validate_storage_with`].
+    pub async fn validate_storage(&self) -> Result<()> {
+        self.validate_storage_with(|url| {
+            let (store, path) = object_store::parse_url(url)?;
+            Ok((Arc::from(store), path))
+        })
+        .await
+    }
+
+    /// Check that the storage locations of all tables can be reached.
+    ///
+    /// `store` returns the object store serving a location and the path of the location
+    /// within the store. A location is reachable if it contains at least one object. Views
+    /// are skipped, as their location is never read. All unreachable tables are named in
+    /// the returned [`Error::InvalidConfig`].
+    pub async fn validate_storage_with<F>(&self, store: F) -> Result<()>
+    where
+        F: Fn(&url::Url) -> std::result::Result<(Arc<dyn ObjectStore>, Path), object_store::Error>,
+    {
+        let catalog = self.catalog();
+        let mut locations = catalog
+            .tables
+            .iter()
+            .filter(|table| table.kind.is_table())
+            .map(|table| (table.name.clone(), catalog.table_url(&table)))
+            .collect::<Vec<_>>();
+        locations.sort_by(|a, b| a.0.cmp(&b.0));
+        let store = &store;
+        let checks = locations.iter().map(|(name, url)| async move {
+            let url = url.as_ref().map_err(|e| (name, e.to_string()))?;
+            let (store, path) = store(url).map_err(|e| (name, e.to_string()))?;
+            let first = store.list(Some(&path)).next().await;
+            match first {
+                Some(Ok(_)) => Ok(()),
+                Some(Err(e)) => Err((name, e.to_string())),
+                None => Err((name, format!("no objects found at '{}'", url))),
+            }
+        });
+        let (names, errors): (Vec<_>, Vec<_>) = futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .filter_map(|result| result.err())
+            .map(|(name, e)| (format!("'{}'", name), format!("table '{}': {}", name, e)))
+            .unzip();
+        if names.is_empty() {
+            return Ok(());
+        }
+        Err(Error::invalid_config(
+            format!("storage of tables {} is unreachable", names.join(", ")),
+            errors.join("; "),
+        ))
+    }
+
+    fn catalog(&self) -> Arc<Catalog> {
+        self.catalog
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// List the shares matching the given `options`.
+    ///
+    /// Pagination is applied to the filtered shares, so pages are filled with matching
+    /// shares only.
+    pub fn list_shares_with_options(
+        &self,
+        request: t::ListSharesRequest,
+        options: &ListOptions,
+    ) -> Result<t::ListSharesResponse> {
+        let catalog = self.catalog();
+        let pagination = pagination(request.max_results, request.page_token, options.order_by);
+        let mut shares = catalog
+            .share_order
+            .iter()
+            .filter(|share| options.matches(catalog.share_properties.get(*share).as_deref()))
+            .map(|share| {
+                let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, share.as_bytes());
+                t::Share {
+                    id: Some(id.into()),
+                    name: share.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        sort_listing(&mut shares, pagination.order_by(), &catalog);
+        let page = paginate_versioned(shares, &pagination, catalog.version)?;
+        Ok(t::ListSharesResponse {
+            items: page.items,
+            next_page_token: page.next_page_token,
+        })
+    }
+
+    /// List the tables in a schema matching the given `options`.
+    ///
+    /// Pagination is applied to the filtered tables.
+    pub fn list_schema_tables_with_options(
+        &self,
+        request: t::ListSchemaTablesRequest,
+        options: &ListTableOptions,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        let catalog = self.catalog();
+        let schema_refs = catalog
+            .shares
+            .get(&request.share)
+            .ok_or_else(|| share_not_found(&request.share))?;
+        let schema = catalog.schema_name(&request.share, &request.schema)?;
+        if !schema_refs.contains(&schema) {
+            return Err(schema_not_found(&request.share, &schema));
+        }
+        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
+        let pagination = pagination(request.max_results, request.page_token, options.order_by);
+        let response = match catalog
+            .schemas
+            .get(&catalog.schema_key(&request.share, &schema))
+        {
+            Some(tables) => {
+                let mut tables = tables
+                    .iter()
+                    .flat_map(|table_ref| {
+                        catalog
+                            .tables
+                            .get(&catalog.table_key(&request.share, &schema, table_ref))
+                            .filter(|v| options.matches(v))
+                            .map(|v| t::Table {
+                                id: Some(table_id(&v, &share_id, &schema, table_ref)),
+                                name: table_ref.clone(),
+                                share: request.share.clone(),
+                                schema: schema.clone(),
+                                share_id: Some(share_id.to_string()),
+                                last_updated: last_updated(&v),
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                sort_listing(&mut tables, pagination.order_by(), &catalog);
+                let page = paginate_versioned(tables, &pagination, catalog.version)?;
+                Ok(t::ListSchemaTablesResponse {
+                    items: page.items,
+                    next_page_token: page.next_page_token,
+                })
+            }
+            None => Err(schema_not_found(&request.share, &schema)),
+        };
+        response
+    }
+
+    /// List the tables in a share matching the given `options`.
+    ///
+    /// Pagination is applied to the filtered tables.
+    pub fn list_share_tables_with_options(
+        &self,
+        request: t::ListShareTablesRequest,
+        options: &ListTableOptions,
+    ) -> Result<t::ListShareTablesResponse> {
+        let catalog = self.catalog();
+        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
+        let pagination = pagination(request.max_results, request.page_token, options.order_by);
+        let response = match catalog.shares.get(&request.share) {
+            Some(schema_refs) => {
+                let mut tables = schema_refs
+                    .iter()
+                    .flat_map(|schema_ref| {
+                        let key = catalog.schema_key(&request.share, schema_ref);
+                        catalog.schemas.get(&key).map(|v| {
+                            v.iter()
+                                .flat_map(|table_ref| {
+                                    catalog
+                                        .tables
+                                        .get(&catalog.table_key(
+                                            &request.share,
+                                            schema_ref,
+                                            table_ref,
+                                        ))
+                                        .filter(|v| options.matches(v))
+                                        .map(|v| t::Table {
+                                            id: Some(table_id(
+                                                &v, &share_id, schema_ref, table_ref,
+                                            )),
+                                            name: table_ref.clone(),
+                                            share: request.share.clone(),
+                                            schema: schema_ref.clone(),
+                                            share_id: Some(share_id.to_string()),
+                                            last_updated: last_updated(&v),
+                                        })
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+                sort_listing(&mut tables, pagination.order_by(), &catalog);
+                let page = paginate_versioned(tables, &pagination, catalog.version)?;
+                Ok(t::ListShareTablesResponse {
+                    items: page.items,
+                    next_page_token: page.next_page_token,
+                })
+            }
+            None => Err(share_not_found(&request.share)),
+        };
+        response
+    }
+
+    /// Get the format in which the table is shared by default.
+    pub fn table_format(&self, table_ref: &t::TableRef) -> Result<TableFormat> {
+        Ok(self.catalog().table_config(table_ref)?.format)
+    }
+
+    /// Get whether the table is a delta table or a view.
+    pub fn table_kind(&self, table_ref: &t::TableRef) -> Result<TableKind> {
+        Ok(self.catalog().table_config(table_ref)?.kind.clone())
+    }
+
+    /// Get the configured time the table was last updated.
+    pub fn table_last_updated(&self, table_ref: &t::TableRef) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.catalog().table_config(table_ref)?.last_updated)
+    }
+
+    /// Get the configured time the share was created.
+    pub fn share_created_at(&self, share: &str) -> Result<Option<DateTime<Utc>>> {
+        let catalog = self.catalog();
+        if !catalog.shares.contains_key(share) {
+            return Err(share_not_found(share));
+        }
+        Ok(catalog.share_created_at.get(share).map(|at| *at))
+    }
+
+    /// Get the configured time the schema was created.
+    pub fn schema_created_at(&self, share: &str, schema: &str) -> Result<Option<DateTime<Utc>>> {
+        let catalog = self.catalog();
+        let schema_refs = catalog
+            .shares
+            .get(share)
+            .ok_or_else(|| share_not_found(share))?;
+        let schema = catalog.schema_name(share, schema)?;
+        if !schema_refs.contains(&schema) {
+            return Err(schema_not_found(share, &schema));
+        }
+        let key = catalog.schema_key(share, &schema);
+        Ok(catalog.schema_created_at.get(&key).map(|at| *at))
+    }
+
+    /// Get the id of a table, as reported in listings and table metadata.
+    pub fn table_id(&self, table_ref: &t::TableRef) -> Result<String> {
+        let catalog = self.catalog();
+        let table = catalog.table_config(table_ref)?;
+        let name = catalog.table_name(table_ref)?;
+        let schema = catalog.schema_name(&table_ref.share, &table_ref.schema)?;
+        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, table_ref.share.as_bytes());
+        Ok(table_id(&table, &share_id, &schema, &name))
+    }
+
+    /// Get the configured time the table was created.
+    pub fn table_created_at(&self, table_ref: &t::TableRef) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.catalog().table_config(table_ref)?.created_at)
+    }
+
+    /// Get the properties of a share that may be exposed to recipients.
+    pub fn share_properties(&self, share: &str) -> Result<HashMap<String, String>> {
+        Ok(public_properties(self.private_share_properties(share)?))
+    }
+
+    /// Get all properties of a share, including private properties.
+    pub fn private_share_properties(&self, share: &str) -> Result<HashMap<String, String>> {
+        let catalog = self.catalog();
+        if !catalog.shares.contains_key(share) {
+            return Err(share_not_found(share));
+        }
+        let properties = catalog
+            .share_properties
+            .get(share)
+            .map(|properties| properties.value().clone())
+            .unwrap_or_default();
+        Ok(properties)
+    }
+
+    /// Get the properties of a table that may be exposed to recipients.
+    pub fn table_properties(&self, table_ref: &t::TableRef) -> Result<HashMap<String, String>> {
+        Ok(public_properties(self.private_table_properties(table_ref)?))
+    }
+
+    /// Get all properties of a table, including private properties.
+    pub fn private_table_properties(
+        &self,
+        table_ref: &t::TableRef,
+    ) -> Result<HashMap<String, String>> {
+        Ok(self.catalog().table_config(table_ref)?.properties.clone())
+    }
+
+    /// Get the options for accessing the storage of a table, e.g. its region or endpoint.
+    ///
+    /// The configured storage options are merged with the table properties prefixed by
+    /// [`STORAGE_OPTION_PREFIX`], which take precedence. The options may hold credentials,
+    /// so they must only be used to open or sign the files of the table.
+    pub fn table_storage_options(
+        &self,
+        table_ref: &t::TableRef,
+    ) -> Result<HashMap<String, String>> {
+        let catalog = self.catalog();
+        let table = catalog.table_config(table_ref)?;
+        let mut options = catalog.storage_options.clone();
+        options.extend(table.properties.iter().filter_map(|(key, value)| {
+            let key = key.strip_prefix(STORAGE_OPTION_PREFIX)?;
+            Some((key.to_string(), value.clone()))
+        }));
+        Ok(options)
+    }
+
+    /// Collect the keys of the properties on all shares and tables the recipient can read.
+    ///
+    /// The catalog is walked like in [`DiscoveryHandler::describe`], so only shares and tables
+    /// the policy allows `recipient` to read contribute keys. Private properties are left out.
+    pub async fn distinct_property_keys(&self, recipient: T) -> Result<HashSet<String>> {
+        let catalog = self.catalog();
+        let tree = self.describe_catalog(&catalog, &recipient).await?;
+        let mut keys = HashSet::new();
+        for share in tree.shares {
+            if let Some(properties) = catalog.share_properties.get(&share.share.name) {
+                keys.extend(public_keys(properties.value()));
+            }
+            for table in share.schemas.into_iter().flat_map(|schema| schema.tables) {
+                let key = catalog.table_key(&table.share, &table.schema, &table.name);
+                if let Some(config) = catalog.tables.get(&key) {
+                    keys.extend(public_keys(&config.properties));
+                }
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// Compute a hash of the contents of `config` that is independent of the order of entries.
+fn content_version(config: &InMemoryConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut shares = config.shares.iter().collect::<Vec<_>>();
+    shares.sort_by(|a, b| a.name.cmp(&b.name));
+    for share in shares {
+        (&share.name, &share.schema_refs, share_digest(share)).hash(&mut hasher);
+    }
+    let mut schemas = config.schemas.iter().collect::<Vec<_>>();
+    schemas.sort_by(|a, b| a.name.cmp(&b.name));
+    for schema in schemas {
+        (&schema.name, &schema.table_refs, &schema.created_at).hash(&mut hasher);
+    }
+    let mut tables = config.tables.iter().collect::<Vec<_>>();
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+    for table in tables {
+        (&table.name, table_digest(table)).hash(&mut hasher);
+    }
+    config.storage_root.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn sorted(properties: &HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    properties.sort();
+    properties
+}
+
+/// Hash of the settings of `share`, excluding its name and schemas.
+fn share_digest(share: &ShareConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (&share.default_schema, &share.created_at).hash(&mut hasher);
+    sorted(&share.properties).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of the settings of `table`, excluding its name.
+fn table_digest(table: &TableConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (
+        &table.id,
+        &table.aliases,
+        &table.location,
+        table.format.as_str(),
+    )
+        .hash(&mut hasher);
+    table.kind.hash(&mut hasher);
+    table.partition_columns.hash(&mut hasher);
+    table.last_updated.hash(&mut hasher);
+    table.created_at.hash(&mut hasher);
+    sorted(&table.properties).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Id of `table` listed as `name` in `schema` of the share with the id `share_id`.
+///
+/// The configured id is used if set. Otherwise the id is derived from the share, schema and
+/// table names, so it is stable across restarts.
+fn table_id(table: &TableConfig, share_id: &Uuid, schema: &str, name: &str) -> String {
+    match &table.id {
+        Some(id) => id.clone(),
+        None => Uuid::new_v5(share_id, format!("{}.{}", schema, name).as_bytes()).to_string(),
+    }
+}
+
+/// Format the time `table` was last updated as reported in table listings.
+fn last_updated(table: &TableConfig) -> Option<String> {
+    table
+        .last_updated
+        .map(|ts| ts.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+fn public_properties(mut properties: HashMap<String, String>) -> HashMap<String, String> {
+    properties.retain(|key, _| !key.starts_with(PRIVATE_PROPERTY_PREFIX));
+    properties
+}
+
+fn public_keys(properties: &HashMap<String, String>) -> impl Iterator<Item = String> + '_ {
+    properties
+        .keys()
+        .filter(|key| !key.starts_with(PRIVATE_PROPERTY_PREFIX))
+        .cloned()
+}
+
+fn share_not_found(share: &str) -> Error {
+    Error::NotFound(format!("share '{}' does not exist", share))
+}
+
+fn schema_not_found(share: &str, schema: &str) -> Error {
+    Error::NotFound(format!(
+        "schema '{}' does not exist",
+        t::schema_fqn(share, schema)
+    ))
+}
+
+fn table_not_found(table_ref: &t::TableRef) -> Error {
+    Error::NotFound(format!("table '{}' does not exist", table_ref))
+}
+
+#[async_trait::async_trait]
+impl<T: Send + Sync> DiscoveryHandler for InMemoryHandler<T> {
+    type Recipient = T;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        _recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        self.list_shares_with_options(request, &ListOptions::default())
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        let catalog = self.catalog();
+        if catalog.shares.contains_key(&request.share) {
+            let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, request.share.as_bytes());
+            Ok(t::GetShareResponse {
+                share: Some(t::Share {
+                    id: Some(id.to_string()),
+                    name: request.share,
+                }),
+            })
+        } else {
+            Err(share_not_found(&request.share))
+        }
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        let catalog = self.catalog();
+        let pagination = Pagination::new(request.max_results, request.page_token);
+        let response = match catalog.shares.get(&request.share) {
+            Some(schema_refs) => {
+                let mut schemas = schema_refs
+                    .iter()
+                    .map(|schema_ref| t::Schema {
+                        name: schema_ref.clone(),
+                        share: request.share.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                sort_listing(&mut schemas, pagination.order_by(), &catalog);
+                let page = paginate_versioned(schemas, &pagination, catalog.version)?;
+                Ok(t::ListSchemasResponse {
+                    items: page.items,
+                    next_page_token: page.next_page_token,
+                })
+            }
+            None => Err(share_not_found(&request.share)),
+        };
+        response
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        self.list_schema_tables_with_options(request, &ListTableOptions::default())
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        self.list_share_tables_with_options(request, &ListTableOptions::default())
+    }
+
+    /// Search the tables by a substring of their names or the values of their properties.
+    ///
+    /// Besides the names, `query` is matched against the values of the properties of the
+    /// tables that are visible to recipients. Page tokens are rejected once the config is
+    /// reloaded, like the tokens of the listings.
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        let catalog = self.catalog();
+        let query = query.to_lowercase();
+        let property_matches = |table: &t::Table| {
+            let key = catalog.table_key(&table.share, &table.schema, &table.name);
+            catalog.tables.get(&key).is_some_and(|config| {
+                config.properties.iter().any(|(key, value)| {
+                    !key.starts_with(PRIVATE_PROPERTY_PREFIX)
+                        && value.to_lowercase().contains(&query)
+                })
+            })
+        };
+        let tables = self
+            .describe_catalog(&catalog, &recipient)
+            .await?
+            .shares
+            .into_iter()
+            .flat_map(|share| share.schemas)
+            .flat_map(|schema| schema.tables)
+            .filter(|table| table_matches(table, &query) || property_matches(table))
+            .collect::<Vec<_>>();
+        paginate_versioned(tables, &pagination, catalog.version)
+    }
+
+    /// Describe the shares, schemas and tables readable by the recipient.
+    ///
+    /// Shares and tables are filtered with the policy set with
+    /// [`InMemoryHandler::with_policy`].
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        self.describe_catalog(&self.catalog(), &recipient).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + Sync> TableLocationResover for InMemoryHandler<T> {
+    async fn resolve(&self, table_ref: &t::TableRef) -> Result<url::Url> {
+        let catalog = self.catalog();
+        let table = catalog.table_config(table_ref)?;
+        catalog.table_url(&table)
+    }
+
+    async fn resolve_all(&self, table_refs: &[t::TableRef]) -> Vec<Result<url::Url>> {
+        // resolve all tables against the same catalog, even if it is reloaded concurrently.
+        let catalog = self.catalog();
+        table_refs
+            .iter()
+            .map(|table_ref| {
+                let table = catalog.table_config(table_ref)?;
+                catalog.table_url(&table)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::DeltaRecipient;
 
     #[tokio::test]
     async fn test_in_memory_handler() {
@@ -238,15 +1637,29 @@ mod tests {
             shares: vec![ShareConfig {
                 name: "share1".to_string(),
                 schema_refs: vec!["schema1".to_string()],
+                properties: HashMap::new(),
+                default_schema: None,
+                created_at: None,
             }],
             schemas: vec![SchemaConfig {
                 name: "schema1".to_string(),
                 table_refs: vec!["table1".to_string()],
+                created_at: None,
             }],
             tables: vec![TableConfig {
+                id: None,
                 name: "table1".to_string(),
+                aliases: vec![],
                 location: "file:///tmp".to_string(),
+                format: TableFormat::Parquet,
+                kind: TableKind::Table,
+                partition_columns: vec![],
+                last_updated: None,
+                created_at: None,
+                properties: HashMap::new(),
             }],
+            storage_root: None,
+            storage_options: HashMap::new(),
         };
         let handler = DefaultInMemoryHandler::new(config);
 
@@ -299,4 +1712,1740 @@ mod tests {
         assert_eq!(tables.items.len(), 1);
         assert_eq!(tables.items[0].name, "table1");
     }
+
+    #[test]
+    fn test_list_shares_filtered_by_property() {
+        let mut config = InMemoryConfig::default();
+        for (name, tier) in [
+            ("share1", "gold"),
+            ("share2", "silver"),
+            ("share3", "gold"),
+            ("share4", "gold"),
+            ("share5", "bronze"),
+        ] {
+            config.shares.push(ShareConfig {
+                name: name.to_string(),
+                schema_refs: vec![],
+                properties: HashMap::from([("tier".to_string(), tier.to_string())]),
+                default_schema: None,
+                created_at: None,
+            });
+        }
+        config.shares.push(ShareConfig {
+            name: "share6".to_string(),
+            schema_refs: vec![],
+            properties: HashMap::new(),
+            default_schema: None,
+            created_at: None,
+        });
+        let handler = DefaultInMemoryHandler::new(config);
+        let options = ListOptions::default().with_property("tier", "gold");
+
+        let page = handler
+            .list_shares_with_options(
+                t::ListSharesRequest {
+                    max_results: Some(2),
+                    page_token: None,
+                },
+                &options,
+            )
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share1", "share3"]);
+        assert!(page.next_page_token.is_some());
+
+        let page = handler
+            .list_shares_with_options(
+                t::ListSharesRequest {
+                    max_results: Some(2),
+                    page_token: page.next_page_token,
+                },
+                &options,
+            )
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share4"]);
+        assert_eq!(page.next_page_token, None);
+
+        let page = handler
+            .list_shares_with_options(t::ListSharesRequest::default(), &ListOptions::default())
+            .unwrap();
+        assert_eq!(page.items.len(), 6);
+    }
+
+    #[test]
+    fn test_list_tables_partitioned_by() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1", "schema2"]}],
+            "schemas": [
+                {"name": "schema1", "tableRefs": ["events", "users", "clicks"]},
+                {"name": "schema2", "tableRefs": ["orders"]}
+            ],
+            "tables": [
+                {"name": "events", "location": "file:///tmp", "partitionColumns": ["date"]},
+                {"name": "users", "location": "file:///tmp", "partitionColumns": ["country"]},
+                {"name": "clicks", "location": "file:///tmp", "partitionColumns": ["country", "date"]},
+                {"name": "orders", "location": "file:///tmp", "partitionColumns": ["date"]}
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+        let options = ListTableOptions::default().partitioned_by("date");
+
+        let page = handler
+            .list_share_tables_with_options(
+                t::ListShareTablesRequest {
+                    share: "share1".to_string(),
+                    max_results: Some(2),
+                    page_token: None,
+                },
+                &options,
+            )
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["clicks", "events"]);
+
+        let page = handler
+            .list_share_tables_with_options(
+                t::ListShareTablesRequest {
+                    share: "share1".to_string(),
+                    max_results: Some(2),
+                    page_token: page.next_page_token,
+                },
+                &options,
+            )
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["orders"]);
+        assert_eq!(page.next_page_token, None);
+
+        let page = handler
+            .list_schema_tables_with_options(
+                t::ListSchemaTablesRequest {
+                    share: "share1".to_string(),
+                    schema: "schema1".to_string(),
+                    ..Default::default()
+                },
+                &options,
+            )
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["clicks", "events"]);
+
+        let page = handler
+            .list_schema_tables_with_options(
+                t::ListSchemaTablesRequest {
+                    share: "share1".to_string(),
+                    schema: "schema1".to_string(),
+                    ..Default::default()
+                },
+                &ListTableOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(page.items.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_config_from_iter() {
+        let mut config = ["share2", "share1"]
+            .into_iter()
+            .map(|name| ShareConfig::new(name, ["schema1"]))
+            .collect::<InMemoryConfig>();
+        config.extend([ShareConfig::new("share3", Vec::<String>::new())]);
+        config.extend([SchemaConfig::new("schema1", ["table1"])]);
+        config.extend([TableConfig::new("table1", "file:///tmp/table1")]);
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let shares = handler
+            .list_shares(t::ListSharesRequest::default(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        let names = shares
+            .items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share1", "share2", "share3"]);
+
+        let tables = handler
+            .list_share_tables(t::ListShareTablesRequest {
+                share: "share2".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(tables.items.len(), 1);
+        assert_eq!(tables.items[0].name, "table1");
+    }
+
+    #[tokio::test]
+    async fn test_validate_storage() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [],
+            "tables": [
+                {"name": "reachable", "location": "memory:///tables/reachable"},
+                {"name": "missing", "location": "memory:///tables/missing"},
+                {
+                    "name": "view1",
+                    "location": "memory:///views/view1",
+                    "kind": {"type": "view", "definition": "SELECT id FROM reachable"}
+                }
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+        let store: Arc<dyn ObjectStore> = Arc::new(object_store::memory::InMemory::new());
+        store
+            .put(
+                &Path::from("tables/reachable/_delta_log/00000000000000000000.json"),
+                "{}".into(),
+            )
+            .await
+            .unwrap();
+        let resolve = |url: &url::Url| Ok((store.clone(), Path::from_url_path(url.path())?));
+
+        let err = handler.validate_storage_with(resolve).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: storage of tables 'missing' is unreachable"
+        );
+
+        store
+            .put(
+                &Path::from("tables/missing/_delta_log/00000000000000000000.json"),
+                "{}".into(),
+            )
+            .await
+            .unwrap();
+        handler.validate_storage_with(resolve).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_view() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "view1"]}],
+            "tables": [
+                {"name": "table1", "location": "s3://bucket/table1"},
+                {
+                    "name": "view1",
+                    "location": "s3://bucket/table1",
+                    "kind": {"type": "view", "definition": "SELECT id FROM table1"}
+                }
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let tables = handler
+            .list_schema_tables(t::ListSchemaTablesRequest {
+                share: "share1".to_string(),
+                schema: "schema1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let names = tables
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["table1", "view1"]);
+
+        let table_ref = |table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+        assert_eq!(
+            handler.table_kind(&table_ref("view1")).unwrap(),
+            TableKind::View {
+                definition: "SELECT id FROM table1".to_string()
+            }
+        );
+        assert_eq!(
+            handler.table_kind(&table_ref("table1")).unwrap(),
+            TableKind::Table
+        );
+        assert!(handler.resolve(&table_ref("table1")).await.is_ok());
+        let err = handler.resolve(&table_ref("view1")).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(message) if message.contains("view1")));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_all() {
+        let config = InMemoryConfig {
+            shares: vec![ShareConfig::new("share1", ["schema1"])],
+            schemas: vec![SchemaConfig::new("schema1", ["table1", "table2"])],
+            tables: vec![
+                TableConfig::new("table1", "s3://bucket/table1"),
+                TableConfig::new("table2", "s3://bucket/table2"),
+            ],
+            storage_root: None,
+            storage_options: HashMap::new(),
+        };
+        let handler = DefaultInMemoryHandler::new(config);
+        let table_ref = |share: &str, table: &str| t::TableRef {
+            share: share.to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+
+        let tables = [
+            table_ref("share1", "table2"),
+            table_ref("share1", "missing"),
+            table_ref("missing", "table1"),
+            table_ref("share1", "table1"),
+        ];
+        let resolved = handler.resolve_all(&tables).await;
+        assert_eq!(resolved.len(), 4);
+        assert_eq!(resolved[0].as_ref().unwrap().as_str(), "s3://bucket/table2");
+        assert!(matches!(resolved[1], Err(Error::NotFound(_))));
+        assert!(matches!(resolved[2], Err(Error::NotFound(_))));
+        assert_eq!(resolved[3].as_ref().unwrap().as_str(), "s3://bucket/table1");
+
+        // the default implementation resolves the tables one by one in the same order.
+        let rewrite = crate::LocationRewriteResolver::new(Arc::new(handler), vec![]);
+        let defaults = futures::future::join_all(tables.iter().map(|t| rewrite.resolve(t))).await;
+        for (resolved, expected) in rewrite.resolve_all(&tables).await.iter().zip(&defaults) {
+            assert_eq!(resolved.is_ok(), expected.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_share_names() {
+        let config = ["share3", "share1", "share2"]
+            .into_iter()
+            .map(|name| ShareConfig::new(name, Vec::<String>::new()))
+            .collect::<InMemoryConfig>();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let request = t::ListSharesRequest {
+            max_results: Some(2),
+            page_token: None,
+        };
+        let shares = handler
+            .list_shares(request.clone(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        let names = handler
+            .list_share_names(request, DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        let expected = shares
+            .items
+            .iter()
+            .map(|s| s.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names.items, expected);
+        assert_eq!(names.items, vec!["share1", "share2"]);
+        assert_eq!(names.next_page_token, shares.next_page_token);
+
+        let names = handler
+            .list_share_names(
+                t::ListSharesRequest {
+                    max_results: Some(2),
+                    page_token: names.next_page_token,
+                },
+                DeltaRecipient::Anonymous,
+            )
+            .await
+            .unwrap();
+        assert_eq!(names.items, vec!["share3"]);
+        assert_eq!(names.next_page_token, None);
+    }
+
+    #[test]
+    fn test_list_tables_order_by_id() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["a", "b", "c", "d", "e"]}],
+            "tables": [
+                {"name": "a", "location": "file:///tmp"},
+                {"name": "b", "location": "file:///tmp"},
+                {"name": "c", "location": "file:///tmp"},
+                {"name": "d", "location": "file:///tmp"},
+                {"name": "e", "location": "file:///tmp"}
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+        let options = ListTableOptions::default().order_by(OrderBy::Id);
+
+        let mut ids = Vec::new();
+        let mut page_token = None;
+        loop {
+            // the ordering is only requested for the first page, later pages use the token.
+            let options = if page_token.is_none() {
+                options.clone()
+            } else {
+                ListTableOptions::default()
+            };
+            let page = handler
+                .list_schema_tables_with_options(
+                    t::ListSchemaTablesRequest {
+                        share: "share1".to_string(),
+                        schema: "schema1".to_string(),
+                        max_results: Some(2),
+                        page_token,
+                    },
+                    &options,
+                )
+                .unwrap();
+            ids.extend(page.items.into_iter().map(|t| t.id.unwrap()));
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+        let mut expected = ids.clone();
+        expected.sort();
+        assert_eq!(ids.len(), 5);
+        assert_eq!(ids, expected);
+
+        let page = handler
+            .list_share_tables_with_options(
+                t::ListShareTablesRequest {
+                    share: "share1".to_string(),
+                    max_results: Some(2),
+                    page_token: None,
+                },
+                &options,
+            )
+            .unwrap();
+        let token = page.next_page_token.unwrap();
+        let err = handler
+            .list_share_tables_with_options(
+                t::ListShareTablesRequest {
+                    share: "share1".to_string(),
+                    max_results: Some(2),
+                    page_token: Some(token),
+                },
+                &ListTableOptions::default().order_by(OrderBy::Name),
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::MalformedPagination(_)));
+    }
+
+    #[test]
+    fn test_list_shares_order_by_insertion() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [
+                {"name": "zeta", "schemaRefs": []},
+                {"name": "alpha", "schemaRefs": []},
+                {"name": "mu", "schemaRefs": []}
+            ],
+            "schemas": [],
+            "tables": []
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let names = |options: &ListOptions| {
+            handler
+                .list_shares_with_options(t::ListSharesRequest::default(), options)
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&ListOptions::default()), vec!["alpha", "mu", "zeta"]);
+        assert_eq!(
+            names(&ListOptions::default().order_by(OrderBy::Insertion)),
+            vec!["zeta", "alpha", "mu"]
+        );
+    }
+
+    #[test]
+    fn test_created_at() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [
+                {"name": "share1", "schemaRefs": ["schema1"], "createdAt": "2024-03-02T00:00:00Z"},
+                {"name": "share2", "schemaRefs": []},
+                {"name": "share3", "schemaRefs": [], "createdAt": "2024-03-01T00:00:00Z"}
+            ],
+            "schemas": [
+                {"name": "schema1", "tableRefs": ["a", "b", "c"], "createdAt": "2024-03-01T00:00:00Z"}
+            ],
+            "tables": [
+                {"name": "a", "location": "file:///tmp"},
+                {"name": "b", "location": "file:///tmp", "createdAt": "2024-03-03T00:00:00+02:00"},
+                {"name": "c", "location": "file:///tmp", "createdAt": "2024-03-01T00:00:00Z"}
+            ]
+        }))
+        .unwrap();
+        let created_at = |at: &str| Some(at.parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(
+            config.tables[1].created_at,
+            created_at("2024-03-02T22:00:00Z")
+        );
+        let serialized = serde_json::to_value(&config).unwrap();
+        assert_eq!(serialized["shares"][0]["createdAt"], "2024-03-02T00:00:00Z");
+        assert!(serialized["shares"][1].get("createdAt").is_none());
+        let handler = DefaultInMemoryHandler::new(config);
+
+        assert_eq!(
+            handler.share_created_at("share1").unwrap(),
+            created_at("2024-03-02T00:00:00Z")
+        );
+        assert_eq!(handler.share_created_at("share2").unwrap(), None);
+        assert!(handler.share_created_at("missing").is_err());
+        assert_eq!(
+            handler.schema_created_at("share1", "schema1").unwrap(),
+            created_at("2024-03-01T00:00:00Z")
+        );
+        let table_ref = t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: "b".to_string(),
+        };
+        assert_eq!(
+            handler.table_created_at(&table_ref).unwrap(),
+            created_at("2024-03-02T22:00:00Z")
+        );
+
+        let options = ListOptions::default().order_by(OrderBy::CreatedAt);
+        let shares = handler
+            .list_shares_with_options(t::ListSharesRequest::default(), &options)
+            .unwrap();
+        let names = shares
+            .items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share3", "share1", "share2"]);
+
+        let options = ListTableOptions::default().order_by(OrderBy::CreatedAt);
+        let mut names = Vec::new();
+        let mut page_token = None;
+        loop {
+            let page = handler
+                .list_schema_tables_with_options(
+                    t::ListSchemaTablesRequest {
+                        share: "share1".to_string(),
+                        schema: "schema1".to_string(),
+                        max_results: Some(1),
+                        page_token,
+                    },
+                    &options,
+                )
+                .unwrap();
+            names.extend(page.items.into_iter().map(|t| t.name));
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+        assert_eq!(names, vec!["c", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_table_last_updated() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {"name": "table1", "location": "file:///tmp", "lastUpdated": "2024-03-01T12:30:00Z"},
+                {"name": "table2", "location": "file:///tmp"}
+            ]
+        }))
+        .unwrap();
+        let last_updated = "2024-03-01T12:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(config.tables[0].last_updated, Some(last_updated));
+        assert_eq!(config.tables[1].last_updated, None);
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["tables"][0]["lastUpdated"], "2024-03-01T12:30:00Z");
+        assert!(value["tables"][1].get("lastUpdated").is_none());
+
+        let handler = DefaultInMemoryHandler::new(config);
+        let table_ref = t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: "table1".to_string(),
+        };
+        assert_eq!(
+            handler.table_last_updated(&table_ref).unwrap(),
+            Some(last_updated)
+        );
+
+        let tables = handler
+            .list_schema_tables(t::ListSchemaTablesRequest {
+                share: "share1".to_string(),
+                schema: "schema1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let value = serde_json::to_value(&tables).unwrap();
+        assert_eq!(value["items"][0]["lastUpdated"], "2024-03-01T12:30:00Z");
+        assert!(value["items"][1].get("lastUpdated").is_none());
+        let roundtrip = serde_json::from_value::<t::ListSchemaTablesResponse>(value).unwrap();
+        assert_eq!(roundtrip, tables);
+    }
+
+    #[tokio::test]
+    async fn test_default_schema() {
+        let mut config = describe_config();
+        config.shares[0].default_schema = Some("schema1".to_string());
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let table_ref = |share: &str, table: &str| t::TableRef {
+            share: share.to_string(),
+            schema: "".to_string(),
+            table: table.to_string(),
+        };
+        let location = handler.resolve(&table_ref("share1", "table1")).await;
+        assert_eq!(location.unwrap().as_str(), "file:///tmp");
+        let result = handler.resolve(&table_ref("share2", "table3")).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+
+        let tables = handler
+            .list_schema_tables(t::ListSchemaTablesRequest {
+                share: "share1".to_string(),
+                schema: "".to_string(),
+                max_results: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        let names = tables
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["table1", "table2"]);
+        assert!(tables.items.iter().all(|t| t.schema == "schema1"));
+
+        let result = handler
+            .list_schema_tables(t::ListSchemaTablesRequest {
+                share: "share2".to_string(),
+                schema: "".to_string(),
+                max_results: None,
+                page_token: None,
+            })
+            .await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let handler = DefaultInMemoryHandler::new(describe_config());
+        let share = t::GetShareRequest {
+            share: "share1".to_string(),
+        };
+        assert!(handler.get_share(share.clone()).await.is_ok());
+
+        let mut config = InMemoryConfig::default();
+        config.shares.push(ShareConfig {
+            name: "share3".to_string(),
+            schema_refs: vec![],
+            properties: HashMap::new(),
+            default_schema: None,
+            created_at: None,
+        });
+        handler.reload(config);
+
+        let shares = handler
+            .list_shares(t::ListSharesRequest::default(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert_eq!(shares.items.len(), 1);
+        assert_eq!(shares.items[0].name, "share3");
+        assert!(matches!(
+            handler.get_share(share).await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_private_properties() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{
+                "name": "share1",
+                "schemaRefs": ["schema1"],
+                "properties": {"tier": "gold", "_owner": "team-a"}
+            }],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1"]}],
+            "tables": [{
+                "name": "table1",
+                "location": "file:///tmp",
+                "properties": {"_owner": "team-b", "_credentials": "vault://a"}
+            }]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let properties = handler.share_properties("share1").unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties["tier"], "gold");
+        let properties = handler.private_share_properties("share1").unwrap();
+        assert_eq!(properties["_owner"], "team-a");
+
+        let table_ref = t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: "table1".to_string(),
+        };
+        assert!(handler.table_properties(&table_ref).unwrap().is_empty());
+        let properties = handler.private_table_properties(&table_ref).unwrap();
+        assert_eq!(properties["_owner"], "team-b");
+        assert_eq!(properties.len(), 2);
+
+        assert!(matches!(
+            handler.share_properties("share2"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_table_ids() {
+        let config = || {
+            serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+                "shares": [{"name": "share1", "schemaRefs": ["schema1", "schema2"]}],
+                "schemas": [
+                    {"name": "schema1", "tableRefs": ["table1", "table2"]},
+                    {"name": "schema2", "tableRefs": ["table2"]}
+                ],
+                "tables": [
+                    {"id": "my-table-id", "name": "table1", "location": "file:///tmp/table1"},
+                    {"name": "table2", "location": "file:///tmp/table2"}
+                ]
+            }))
+            .unwrap()
+        };
+        let table_ref = |schema: &str, table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        let handler = DefaultInMemoryHandler::new(config());
+
+        let id = handler.table_id(&table_ref("schema1", "table1")).unwrap();
+        assert_eq!(id, "my-table-id");
+
+        let id = handler.table_id(&table_ref("schema1", "table2")).unwrap();
+        let share_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, b"share1");
+        assert_eq!(id, Uuid::new_v5(&share_id, b"schema1.table2").to_string());
+        assert_ne!(
+            id,
+            handler.table_id(&table_ref("schema2", "table2")).unwrap()
+        );
+        let restarted = DefaultInMemoryHandler::new(config());
+        assert_eq!(
+            id,
+            restarted.table_id(&table_ref("schema1", "table2")).unwrap()
+        );
+
+        let tables = handler
+            .list_share_tables(t::ListShareTablesRequest {
+                share: "share1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        for table in tables.items {
+            let table_ref = table_ref(&table.schema, &table.name);
+            assert_eq!(table.id, Some(handler.table_id(&table_ref).unwrap()));
+        }
+        assert!(matches!(
+            handler.table_id(&table_ref("schema1", "missing")),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_table_aliases() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {"name": "table1", "aliases": ["old_table1"], "location": "file:///tmp/table1"},
+                {"name": "table2", "location": "file:///tmp/table2"}
+            ]
+        }))
+        .unwrap();
+        config.validate().unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+        let table_ref = |table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+
+        let url = handler.resolve(&table_ref("old_table1")).await.unwrap();
+        assert_eq!(url.as_str(), "file:///tmp/table1");
+        assert_eq!(
+            handler.table_id(&table_ref("old_table1")).unwrap(),
+            handler.table_id(&table_ref("table1")).unwrap()
+        );
+
+        let tables = handler
+            .list_share_tables(t::ListShareTablesRequest {
+                share: "share1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let names = tables
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["table1", "table2"]);
+    }
+
+    #[test]
+    fn test_table_aliases_collide() {
+        let config = |aliases: [serde_json::Value; 2]| {
+            serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+                "tables": [
+                    {"name": "table1", "aliases": aliases[0], "location": "file:///tmp/table1"},
+                    {"name": "table2", "aliases": aliases[1], "location": "file:///tmp/table2"}
+                ]
+            }))
+            .unwrap()
+        };
+
+        let result = config([serde_json::json!(["table2"]), serde_json::json!([])]).validate();
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { message, .. }) if message.contains("'table2'"))
+        );
+        let result = config([serde_json::json!(["old"]), serde_json::json!(["old"])]).validate();
+        assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+        let result = config([serde_json::json!(["table1", "old"]), serde_json::json!([])]);
+        result.validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_table_storage_options() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {
+                    "name": "table1",
+                    "location": "s3://bucket/table1",
+                    "properties": {
+                        "owner": "team-a",
+                        "_storage.region": "eu-west-1",
+                        "_storage.endpoint": "https://s3.example.com",
+                        "_storage.access_key_id": "table-key"
+                    }
+                },
+                {"name": "table2", "location": "s3://bucket/table2"}
+            ],
+            "storageOptions": {"access_key_id": "key", "secret_access_key": "secret"}
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+        let table_ref = |table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+
+        let options = handler.table_storage_options(&table_ref("table1")).unwrap();
+        assert_eq!(options["region"], "eu-west-1");
+        assert_eq!(options["endpoint"], "https://s3.example.com");
+        assert_eq!(options["access_key_id"], "table-key");
+        assert_eq!(options["secret_access_key"], "secret");
+        assert_eq!(options.len(), 4);
+        let options = handler.table_storage_options(&table_ref("table2")).unwrap();
+        assert_eq!(options["access_key_id"], "key");
+        assert_eq!(options.len(), 2);
+
+        let properties = handler.table_properties(&table_ref("table1")).unwrap();
+        assert_eq!(properties.keys().collect::<Vec<_>>(), vec!["owner"]);
+        let tables = handler
+            .list_share_tables(t::ListShareTablesRequest {
+                share: "share1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let response = format!("{:?}", tables);
+        assert!(!response.contains("secret") && !response.contains("table-key"));
+    }
+
+    #[test]
+    fn test_lint() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [
+                {"name": "share1", "schemaRefs": ["schema1", "schema2"]},
+                {"name": "share2", "schemaRefs": []}
+            ],
+            "schemas": [
+                {"name": "schema1", "tableRefs": ["table1", "table2"]},
+                {"name": "schema2", "tableRefs": []},
+                {"name": "schema3", "tableRefs": ["table1"]}
+            ],
+            "tables": [
+                {"name": "table1", "location": "s3://<bucket-name>/table1"},
+                {"name": "table2", "location": "s3://bucket/table2"},
+                {"name": "table3", "location": "s3://${BUCKET}/table3"}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.lint(),
+            vec![
+                LintWarning::EmptyShare {
+                    share: "share2".to_string()
+                },
+                LintWarning::EmptySchema {
+                    schema: "schema2".to_string()
+                },
+                LintWarning::UnreferencedSchema {
+                    schema: "schema3".to_string()
+                },
+                LintWarning::PlaceholderLocation {
+                    table: "table1".to_string(),
+                    location: "s3://<bucket-name>/table1".to_string()
+                },
+                LintWarning::UnreferencedTable {
+                    table: "table3".to_string()
+                },
+                LintWarning::PlaceholderLocation {
+                    table: "table3".to_string(),
+                    location: "s3://${BUCKET}/table3".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            config.lint()[1].to_string(),
+            "schema 'schema2' contains no tables"
+        );
+        assert!(describe_config().lint().is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = describe_config();
+        assert!(before.diff(&describe_config()).is_empty());
+
+        let mut after = describe_config();
+        after
+            .tables
+            .push(TableConfig::new("table4", "file:///tmp/table4"));
+        after.schemas[1].table_refs.push("table4".to_string());
+        after.shares[0].name = "renamed".to_string();
+        let diff = before.diff(&after);
+
+        let entries = |entries: &[CatalogEntry]| {
+            entries
+                .iter()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            entries(&diff.added),
+            [
+                "share renamed",
+                "schema renamed.schema1",
+                "table renamed.schema1.table1",
+                "table renamed.schema1.table2",
+                "table share2.schema2.table4",
+            ]
+        );
+        assert_eq!(
+            entries(&diff.removed),
+            [
+                "share share1",
+                "schema share1.schema1",
+                "table share1.schema1.table1",
+                "table share1.schema1.table2",
+            ]
+        );
+        assert!(diff.changed.is_empty());
+
+        // settings are compared for entries present in both configs.
+        let mut moved = describe_config();
+        moved.tables[2].location = "file:///tmp/moved".to_string();
+        moved.shares[1].default_schema = Some("schema2".to_string());
+        let diff = before.diff(&moved);
+        assert_eq!(
+            diff.to_string(),
+            "~ share share2\n~ table share2.schema2.table3\n"
+        );
+    }
+
+    #[test]
+    fn test_snake_case_keys() {
+        let config = |snake_case: bool| {
+            let keys = if snake_case {
+                [
+                    "schema_refs",
+                    "default_schema",
+                    "table_refs",
+                    "storage_location",
+                    "partition_columns",
+                    "storage_root",
+                ]
+            } else {
+                [
+                    "schemaRefs",
+                    "defaultSchema",
+                    "tableRefs",
+                    "location",
+                    "partitionColumns",
+                    "storageRoot",
+                ]
+            };
+            let value = serde_json::json!({
+                "shares": [{"name": "share1", keys[0]: ["schema1"], keys[1]: "schema1"}],
+                "schemas": [{"name": "schema1", keys[2]: ["table1"]}],
+                "tables": [{"name": "table1", keys[3]: "table1", keys[4]: ["date"]}],
+                keys[5]: "s3://bucket/"
+            });
+            serde_json::from_value::<InMemoryConfig>(value).unwrap()
+        };
+
+        let (snake_case, camel_case) = (config(true), config(false));
+        for config in [&snake_case, &camel_case] {
+            assert_eq!(config.shares[0].schema_refs, vec!["schema1"]);
+            assert_eq!(config.shares[0].default_schema.as_deref(), Some("schema1"));
+            assert_eq!(config.schemas[0].table_refs, vec!["table1"]);
+            assert_eq!(config.tables[0].location, "table1");
+            assert_eq!(config.tables[0].partition_columns, vec!["date"]);
+            assert_eq!(config.storage_root.as_deref(), Some("s3://bucket/"));
+        }
+        assert_eq!(
+            serde_json::to_value(&snake_case).unwrap(),
+            serde_json::to_value(&camel_case).unwrap()
+        );
+        let value = serde_json::to_value(&snake_case).unwrap();
+        assert!(value["shares"][0].get("schemaRefs").is_some());
+        assert!(value["shares"][0].get("schema_refs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_distinct_property_keys() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [
+                {
+                    "name": "share1",
+                    "schemaRefs": ["schema1"],
+                    "properties": {"tier": "gold", "_secret": "a"}
+                },
+                {"name": "share2", "schemaRefs": [], "properties": {"tier": "silver"}}
+            ],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {
+                    "name": "table1",
+                    "location": "file:///tmp/table1",
+                    "properties": {"owner": "team-a", "_credentials": "vault://a"}
+                },
+                {"name": "table2", "location": "file:///tmp/table2"}
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let keys = handler
+            .distinct_property_keys(DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert_eq!(
+            keys,
+            HashSet::from(["tier".to_string(), "owner".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_invalidates_page_tokens() {
+        let handler = DefaultInMemoryHandler::new(describe_config());
+        let request = t::ListSharesRequest {
+            max_results: Some(1),
+            page_token: None,
+        };
+        let page = handler
+            .list_shares(request, DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        let request = t::ListSharesRequest {
+            max_results: Some(1),
+            page_token: page.next_page_token,
+        };
+
+        // reloading the same content keeps tokens valid.
+        handler.reload(describe_config());
+        let page = handler
+            .list_shares(request.clone(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert_eq!(page.items[0].name, "share2");
+
+        let mut config = describe_config();
+        config.shares.remove(0);
+        handler.reload(config);
+        let result = handler
+            .list_shares(request, DeltaRecipient::Anonymous)
+            .await;
+        assert!(matches!(result, Err(Error::MalformedPagination(_))));
+    }
+
+    #[tokio::test]
+    async fn test_empty_in_memory_handler() {
+        let config = serde_json::from_str::<InMemoryConfig>("{}").unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let shares = handler
+            .list_shares(t::ListSharesRequest::default(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert!(shares.items.is_empty());
+        assert!(shares.next_page_token.is_none());
+
+        let err = handler
+            .get_share(t::GetShareRequest {
+                share: "share1".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotFound(message) if message.contains("share1")));
+
+        let err = handler
+            .list_share_tables(t::ListShareTablesRequest {
+                share: "share1".to_string(),
+                max_results: None,
+                page_token: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotFound(message) if message.contains("share1")));
+
+        let err = handler
+            .resolve(&t::TableRef {
+                share: "share1".to_string(),
+                schema: "schema1".to_string(),
+                table: "table1".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotFound(message) if message.contains("share1")));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_config_json_schema() {
+        let schema = config_json_schema();
+
+        assert_eq!(schema["properties"]["shares"]["type"], "array");
+        let required = schema["definitions"]["TableConfig"]["required"]
+            .as_array()
+            .unwrap();
+        assert!(required.contains(&serde_json::json!("location")));
+        let share_properties = &schema["definitions"]["ShareConfig"]["properties"];
+        assert!(share_properties.get("schemaRefs").is_some());
+    }
+
+    #[test]
+    fn test_table_format_config() {
+        let config =
+            serde_json::from_str::<TableConfig>(r#"{"name": "table1", "location": "file:///tmp"}"#)
+                .unwrap();
+        assert_eq!(config.format, TableFormat::Parquet);
+
+        let config = serde_json::from_str::<TableConfig>(
+            r#"{"name": "table1", "location": "file:///tmp", "format": "delta"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.format, TableFormat::Delta);
+
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["format"], "delta");
+        let config = serde_json::from_value::<TableConfig>(value).unwrap();
+        assert_eq!(config.format, TableFormat::Delta);
+    }
+
+    #[test]
+    fn test_table_format() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {"name": "table1", "location": "file:///tmp"},
+                {"name": "table2", "location": "file:///tmp", "format": "delta"}
+            ]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let table_ref = |table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+        assert_eq!(
+            handler.table_format(&table_ref("table1")).unwrap(),
+            TableFormat::Parquet
+        );
+        assert_eq!(
+            handler.table_format(&table_ref("table2")).unwrap(),
+            TableFormat::Delta
+        );
+        assert!(matches!(
+            handler.table_format(&table_ref("table3")),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    fn describe_config() -> InMemoryConfig {
+        serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [
+                {"name": "share1", "schemaRefs": ["schema1"]},
+                {"name": "share2", "schemaRefs": ["schema2"]}
+            ],
+            "schemas": [
+                {"name": "schema1", "tableRefs": ["table1", "table2"]},
+                {"name": "schema2", "tableRefs": ["table3"]}
+            ],
+            "tables": [
+                {"name": "table1", "location": "file:///tmp"},
+                {"name": "table2", "location": "file:///tmp"},
+                {"name": "table3", "location": "file:///tmp"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn table_names(node: &SchemaNode) -> Vec<&str> {
+        node.tables.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_describe() {
+        let handler = DefaultInMemoryHandler::new(describe_config());
+
+        let tree = handler.describe(DeltaRecipient::Anonymous).await.unwrap();
+        assert_eq!(tree.shares.len(), 2);
+        assert_eq!(tree.shares[0].share.name, "share1");
+        assert_eq!(tree.shares[0].schemas.len(), 1);
+        assert_eq!(tree.shares[0].schemas[0].schema.name, "schema1");
+        assert_eq!(
+            table_names(&tree.shares[0].schemas[0]),
+            ["table1", "table2"]
+        );
+        assert_eq!(tree.shares[1].share.name, "share2");
+        assert_eq!(table_names(&tree.shares[1].schemas[0]), ["table3"]);
+
+        // the direct implementation must agree with walking the list APIs.
+        let walked = crate::tree::describe(&handler, DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert_eq!(tree, walked);
+    }
+
+    /// Handler that only lists the shares contained in the recipient's claims.
+    struct RestrictedHandler(DefaultInMemoryHandler);
+
+    #[async_trait::async_trait]
+    impl DiscoveryHandler for RestrictedHandler {
+        type Recipient = DeltaRecipient;
+
+        async fn list_shares(
+            &self,
+            request: t::ListSharesRequest,
+            recipient: Self::Recipient,
+        ) -> Result<t::ListSharesResponse> {
+            let mut response = self.0.list_shares(request, recipient.clone()).await?;
+            response.items.retain(|share| match &recipient {
+                DeltaRecipient::Anonymous => false,
+                DeltaRecipient::Profile(claims) => claims.shares.contains(&share.name),
+            });
+            Ok(response)
+        }
+
+        async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+            self.0.get_share(request).await
+        }
+
+        async fn list_schemas(
+            &self,
+            request: t::ListSchemasRequest,
+        ) -> Result<t::ListSchemasResponse> {
+            self.0.list_schemas(request).await
+        }
+
+        async fn list_schema_tables(
+            &self,
+            request: t::ListSchemaTablesRequest,
+        ) -> Result<t::ListSchemaTablesResponse> {
+            self.0.list_schema_tables(request).await
+        }
+
+        async fn list_share_tables(
+            &self,
+            request: t::ListShareTablesRequest,
+        ) -> Result<t::ListShareTablesResponse> {
+            self.0.list_share_tables(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_describe_respects_recipient() {
+        let handler = RestrictedHandler(DefaultInMemoryHandler::new(describe_config()));
+        let recipient = DeltaRecipient::Profile(crate::DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec!["share2".to_string()],
+            expiration: None,
+            admin: None,
+        });
+
+        let tree = handler.describe(recipient).await.unwrap();
+        assert_eq!(tree.shares.len(), 1);
+        assert_eq!(tree.shares[0].share.name, "share2");
+        assert_eq!(tree.shares[0].schemas.len(), 1);
+        assert_eq!(tree.shares[0].schemas[0].schema.name, "schema2");
+        assert_eq!(table_names(&tree.shares[0].schemas[0]), ["table3"]);
+
+        let tree = handler.describe(DeltaRecipient::Anonymous).await.unwrap();
+        assert!(tree.shares.is_empty());
+    }
+
+    /// Policy granting the shares in the recipient's claims, except for `table2`.
+    struct ClaimsPolicy;
+
+    #[async_trait::async_trait]
+    impl Policy for ClaimsPolicy {
+        type Recipient = DeltaRecipient;
+
+        async fn authorize(
+            &self,
+            resource: Resource,
+            _permission: Permission,
+            recipient: &Self::Recipient,
+        ) -> Result<Decision> {
+            let DeltaRecipient::Profile(claims) = recipient else {
+                return Ok(Decision::Deny);
+            };
+            let allowed = match resource {
+                Resource::Share(share) => claims.shares.contains(&share),
+                Resource::Table(table) => !table.ends_with(".table2"),
+                _ => false,
+            };
+            Ok(if allowed {
+                Decision::Allow
+            } else {
+                Decision::Deny
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_describe_with_policy() {
+        let mut config = describe_config();
+        config.shares[1]
+            .properties
+            .insert("tier".to_string(), "gold".to_string());
+        for (table, key) in config.tables.iter_mut().zip(["owner", "pii", "region"]) {
+            table
+                .properties
+                .insert(key.to_string(), "finance".to_string());
+        }
+        let handler = DefaultInMemoryHandler::new(config).with_policy(Arc::new(ClaimsPolicy));
+        let recipient = DeltaRecipient::Profile(crate::DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec!["share1".to_string()],
+            expiration: None,
+            admin: None,
+        });
+
+        // share2 is not granted and table2 is denied, so neither is described.
+        let tree = handler.describe(recipient.clone()).await.unwrap();
+        assert_eq!(tree.shares.len(), 1);
+        assert_eq!(tree.shares[0].share.name, "share1");
+        assert_eq!(table_names(&tree.shares[0].schemas[0]), ["table1"]);
+
+        let page = handler
+            .search_tables("finance", recipient.clone(), Pagination::default())
+            .await
+            .unwrap();
+        let names = page
+            .items
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["table1"]);
+
+        let keys = handler.distinct_property_keys(recipient).await.unwrap();
+        assert_eq!(keys, HashSet::from(["owner".to_string()]));
+
+        let tree = handler.describe(DeltaRecipient::Anonymous).await.unwrap();
+        assert!(tree.shares.is_empty());
+        let keys = handler
+            .distinct_property_keys(DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        assert!(keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_table_ref() {
+        let handler = RestrictedHandler(DefaultInMemoryHandler::new(describe_config()));
+        let recipient = DeltaRecipient::Profile(crate::DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec!["share2".to_string()],
+            expiration: None,
+            admin: None,
+        });
+
+        let table_ref = t::TableRef::parse("share2.schema2.table3").unwrap();
+        let table = handler
+            .get_table_ref(&table_ref, recipient.clone())
+            .await
+            .unwrap();
+        assert_eq!(table.name, "table3");
+        assert_eq!(table.share, "share2");
+
+        for name in ["share1.schema1.table1", "share2.schema2.missing"] {
+            let table_ref = t::TableRef::parse(name).unwrap();
+            let result = handler.get_table_ref(&table_ref, recipient.clone()).await;
+            assert!(matches!(result, Err(Error::NotFound(_))), "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_tables() {
+        let mut config = describe_config();
+        let properties = &mut config.tables[0].properties;
+        properties.insert("owner".to_string(), "Finance".to_string());
+        config.tables[1]
+            .properties
+            .insert("_owner".to_string(), "finance".to_string());
+        let handler = DefaultInMemoryHandler::new(config);
+        let names = |page: &Page<t::Table>| {
+            page.items
+                .iter()
+                .map(|table| table.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let page = handler
+            .search_tables(
+                "SHARE1",
+                DeltaRecipient::Anonymous,
+                Pagination::new(Some(1), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(names(&page), ["table1"]);
+        let pagination = Pagination::new(Some(1), page.next_page_token);
+        let page = handler
+            .search_tables("SHARE1", DeltaRecipient::Anonymous, pagination)
+            .await
+            .unwrap();
+        assert_eq!(names(&page), ["table2"]);
+        assert_eq!(page.next_page_token, None);
+
+        // only the values of public properties are matched.
+        let page = handler
+            .search_tables("finance", DeltaRecipient::Anonymous, Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(names(&page), ["table1"]);
+
+        // the default implementation only matches names of tables the recipient can read.
+        let handler = RestrictedHandler(handler);
+        let recipient = DeltaRecipient::Profile(crate::DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec!["share2".to_string()],
+            expiration: None,
+            admin: None,
+        });
+        let page = handler
+            .search_tables("Table", recipient.clone(), Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(names(&page), ["table3"]);
+        let page = handler
+            .search_tables("finance", recipient, Pagination::default())
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    /// Handler that records the largest page returned by the wrapped handler.
+    struct CountingHandler {
+        inner: DefaultInMemoryHandler,
+        calls: std::sync::atomic::AtomicUsize,
+        max_items: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CountingHandler {
+        fn record(&self, items: usize) {
+            use std::sync::atomic::Ordering;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.max_items.fetch_max(items, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DiscoveryHandler for CountingHandler {
+        type Recipient = DeltaRecipient;
+
+        async fn list_shares(
+            &self,
+            request: t::ListSharesRequest,
+            recipient: Self::Recipient,
+        ) -> Result<t::ListSharesResponse> {
+            let response = self.inner.list_shares(request, recipient).await?;
+            self.record(response.items.len());
+            Ok(response)
+        }
+
+        async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+            self.inner.get_share(request).await
+        }
+
+        async fn list_schemas(
+            &self,
+            request: t::ListSchemasRequest,
+        ) -> Result<t::ListSchemasResponse> {
+            let response = self.inner.list_schemas(request).await?;
+            self.record(response.items.len());
+            Ok(response)
+        }
+
+        async fn list_schema_tables(
+            &self,
+            request: t::ListSchemaTablesRequest,
+        ) -> Result<t::ListSchemaTablesResponse> {
+            let response = self.inner.list_schema_tables(request).await?;
+            self.record(response.items.len());
+            Ok(response)
+        }
+
+        async fn list_share_tables(
+            &self,
+            request: t::ListShareTablesRequest,
+        ) -> Result<t::ListShareTablesResponse> {
+            let response = self.inner.list_share_tables(request).await?;
+            self.record(response.items.len());
+            Ok(response)
+        }
+    }
+
+    fn large_catalog() -> InMemoryConfig {
+        let mut config = InMemoryConfig::default();
+        for share in 0..25 {
+            let schema = format!("schema{}", share);
+            let table_refs = (0..40)
+                .map(|table| format!("table{}_{}", share, table))
+                .collect::<Vec<_>>();
+            for table in &table_refs {
+                config.tables.push(TableConfig {
+                    id: None,
+                    name: table.clone(),
+                    aliases: vec![],
+                    location: "file:///tmp".to_string(),
+                    format: TableFormat::Parquet,
+                    kind: TableKind::Table,
+                    partition_columns: vec![],
+                    last_updated: None,
+                    created_at: None,
+                    properties: HashMap::new(),
+                });
+            }
+            config.schemas.push(SchemaConfig {
+                name: schema.clone(),
+                table_refs,
+                created_at: None,
+            });
+            config.shares.push(ShareConfig {
+                name: format!("share{}", share),
+                schema_refs: vec![schema],
+                properties: HashMap::new(),
+                default_schema: None,
+                created_at: None,
+            });
+        }
+        config
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tables() {
+        use futures::{StreamExt, TryStreamExt};
+        use std::sync::atomic::Ordering;
+
+        let handler = CountingHandler {
+            inner: DefaultInMemoryHandler::new(large_catalog()),
+            calls: Default::default(),
+            max_items: Default::default(),
+        };
+
+        // tables are fetched lazily, so taking the first few only fetches a single page.
+        let first = crate::list_all_tables(&handler, DeltaRecipient::Anonymous, 10)
+            .take(5)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 5);
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 3);
+
+        let tables = crate::list_all_tables(&handler, DeltaRecipient::Anonymous, 10)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 1000);
+        let names = tables
+            .iter()
+            .map(|table| (table.share.as_str(), table.name.as_str()))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(names.len(), 1000);
+        assert!(handler.max_items.load(Ordering::SeqCst) <= 10);
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tables_resume() {
+        use futures::{StreamExt, TryStreamExt};
+        use std::sync::atomic::Ordering;
+
+        let handler = CountingHandler {
+            inner: DefaultInMemoryHandler::new(large_catalog()),
+            calls: Default::default(),
+            max_items: Default::default(),
+        };
+        let all = crate::list_all_tables_from(&handler, DeltaRecipient::Anonymous, 10, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 1000);
+
+        // resume in the middle of a schema, at the end of a schema, and after the last table.
+        for position in [0, 436, 439, 999] {
+            let (_, token) = &all[position];
+            let rest = crate::list_all_tables_from(
+                &handler,
+                DeltaRecipient::Anonymous,
+                10,
+                Some(token.clone()),
+            )
+            .map_ok(|(table, _)| table)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+            let expected = all[position + 1..]
+                .iter()
+                .map(|(table, _)| table.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(rest, expected);
+        }
+        assert!(handler.max_items.load(Ordering::SeqCst) <= 10);
+
+        // resuming yields the same tokens as the uninterrupted listing.
+        let (_, token) = &all[500];
+        let resumed = crate::list_all_tables_from(
+            &handler,
+            DeltaRecipient::Anonymous,
+            10,
+            Some(token.clone()),
+        )
+        .take(3)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+        assert_eq!(resumed, all[501..504]);
+
+        for token in ["", "7368617265", "zz.00.1", "7368617265.736368656d61.x"] {
+            let result = crate::list_all_tables_from(
+                &handler,
+                DeltaRecipient::Anonymous,
+                10,
+                Some(token.to_string()),
+            )
+            .try_collect::<Vec<_>>()
+            .await;
+            assert!(matches!(result, Err(Error::MalformedPagination(_))));
+        }
+
+        // tokens for a share or schema that no longer exists are stale.
+        let share = all[0].1.split('.').collect::<Vec<_>>();
+        for token in [
+            format!("{}.{}.1", "756e6b6e6f776e", share[1]),
+            format!("{}.{}.1", share[0], "756e6b6e6f776e"),
+        ] {
+            let result =
+                crate::list_all_tables_from(&handler, DeltaRecipient::Anonymous, 10, Some(token))
+                    .try_collect::<Vec<_>>()
+                    .await;
+            assert!(matches!(result, Err(Error::MalformedPagination(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_relative_location() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1", "table2"]}],
+            "tables": [
+                {"name": "table1", "location": "table/prefix"},
+                {"name": "table2", "location": "abfss://container@account.dfs.core.windows.net/table"}
+            ],
+            "storageRoot": "s3://bucket/root"
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let table_ref = |table: &str| t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: table.to_string(),
+        };
+        let url = handler.resolve(&table_ref("table1")).await.unwrap();
+        assert_eq!(url.as_str(), "s3://bucket/root/table/prefix");
+        let url = handler.resolve(&table_ref("table2")).await.unwrap();
+        assert_eq!(
+            url.as_str(),
+            "abfss://container@account.dfs.core.windows.net/table"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_relative_location_without_root() {
+        let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+            "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+            "schemas": [{"name": "schema1", "tableRefs": ["table1"]}],
+            "tables": [{"name": "table1", "location": "table/prefix"}]
+        }))
+        .unwrap();
+        let handler = DefaultInMemoryHandler::new(config);
+
+        let err = handler
+            .resolve(&t::TableRef {
+                share: "share1".to_string(),
+                schema: "schema1".to_string(),
+                table: "table1".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidTableLocation(location) if location == "table/prefix"));
+    }
 }
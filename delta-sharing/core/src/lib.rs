@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[allow(dead_code)]
 pub mod types {
@@ -19,6 +19,146 @@ pub mod types {
         pub table: String,
     }
 
+    impl TableRef {
+        /// Parse a fully qualified table name like `share.schema.table`.
+        ///
+        /// Names containing dots are quoted with backticks, e.g. ``share.`my.schema`.table``.
+        /// Backticks within a quoted name are escaped by doubling them.
+        pub fn parse(name: &str) -> crate::Result<Self> {
+            let invalid = |reason: &str| {
+                crate::Error::InvalidArgument(format!("invalid table name '{}': {}", name, reason))
+            };
+            let mut parts = Vec::new();
+            let mut chars = name.chars().peekable();
+            loop {
+                let mut part = String::new();
+                if chars.next_if_eq(&'`').is_some() {
+                    loop {
+                        match chars.next() {
+                            Some('`') if chars.next_if_eq(&'`').is_some() => part.push('`'),
+                            Some('`') => break,
+                            Some(c) => part.push(c),
+                            None => return Err(invalid("unterminated quote")),
+                        }
+                    }
+                } else {
+                    while let Some(c) = chars.next_if(|c| *c != '.') {
+                        if c == '`' {
+                            return Err(invalid("quotes must enclose a whole name"));
+                        }
+                        part.push(c);
+                    }
+                }
+                if part.is_empty() {
+                    return Err(invalid("names must not be empty"));
+                }
+                parts.push(part);
+                match chars.next() {
+                    Some('.') => continue,
+                    Some(_) => return Err(invalid("quotes must enclose a whole name")),
+                    None => break,
+                }
+            }
+            let [share, schema, table] = <[String; 3]>::try_from(parts)
+                .map_err(|_| invalid("expected a name like 'share.schema.table'"))?;
+            Ok(Self {
+                share,
+                schema,
+                table,
+            })
+        }
+    }
+
+    /// Quote `name` as part of a fully qualified name, as [`TableRef::parse`] expects.
+    pub(crate) fn quoted(name: &str) -> String {
+        if name.is_empty() || name.contains(['.', '`']) {
+            format!("`{}`", name.replace('`', "``"))
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Fully qualified name of the schema `schema` within the share `share`.
+    pub(crate) fn schema_fqn(share: &str, schema: &str) -> String {
+        format!("{}.{}", quoted(share), quoted(schema))
+    }
+
+    /// Fully qualified name of the table `table` within `share.schema`.
+    pub(crate) fn table_fqn(share: &str, schema: &str, table: &str) -> String {
+        format!("{}.{}", schema_fqn(share, schema), quoted(table))
+    }
+
+    impl std::fmt::Display for TableRef {
+        /// Format the fully qualified name of the table, quoting names as [`TableRef::parse`]
+        /// expects.
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&table_fqn(&self.share, &self.schema, &self.table))
+        }
+    }
+
+    impl Share {
+        /// Fully qualified name of the share, quoted as [`TableRef::parse`] expects.
+        ///
+        /// # Example
+        /// ```
+        /// use delta_sharing_core::types::Share;
+        ///
+        /// let share = Share { name: "my.share".into(), ..Default::default() };
+        /// assert_eq!(share.fqn(), "`my.share`");
+        /// ```
+        pub fn fqn(&self) -> String {
+            quoted(&self.name)
+        }
+    }
+
+    impl Schema {
+        /// Fully qualified name `share.schema` of the schema, quoted as [`TableRef::parse`]
+        /// expects.
+        ///
+        /// # Example
+        /// ```
+        /// use delta_sharing_core::types::Schema;
+        ///
+        /// let schema = Schema { name: "schema".into(), share: "share".into() };
+        /// assert_eq!(schema.fqn(), "share.schema");
+        /// let schema = Schema { name: "my.schema".into(), share: "share".into() };
+        /// assert_eq!(schema.fqn(), "share.`my.schema`");
+        /// ```
+        pub fn fqn(&self) -> String {
+            schema_fqn(&self.share, &self.name)
+        }
+    }
+
+    impl Table {
+        /// Fully qualified name `share.schema.table` of the table, quoted as
+        /// [`TableRef::parse`] expects.
+        ///
+        /// # Example
+        /// ```
+        /// use delta_sharing_core::types::{Table, TableRef};
+        ///
+        /// let table = Table {
+        ///     name: "table".into(),
+        ///     schema: "schema".into(),
+        ///     share: "share".into(),
+        ///     ..Default::default()
+        /// };
+        /// assert_eq!(table.fqn(), "share.schema.table");
+        ///
+        /// let table = Table {
+        ///     name: "my.table".into(),
+        ///     schema: "schema".into(),
+        ///     share: "share".into(),
+        ///     ..Default::default()
+        /// };
+        /// assert_eq!(table.fqn(), "share.schema.`my.table`");
+        /// assert_eq!(TableRef::parse(&table.fqn()).unwrap().table, "my.table");
+        /// ```
+        pub fn fqn(&self) -> String {
+            table_fqn(&self.share, &self.schema, &self.name)
+        }
+    }
+
     include!("gen/delta_sharing.v1.rs");
 }
 pub mod capabilities;
@@ -26,17 +166,47 @@ pub mod error;
 #[cfg(feature = "memory")]
 mod in_memory;
 mod kernel;
+mod maintenance;
+mod map_recipient;
+mod ordering;
+pub mod pagination;
 pub mod policies;
 #[cfg(feature = "profiles")]
 mod profiles;
+#[cfg(feature = "memory")]
+mod records;
+#[cfg(feature = "memory")]
+mod reference;
+pub mod retry;
+mod rewrite;
+mod shared;
+mod slow_log;
+#[cfg(feature = "sqlx")]
+mod sql;
+mod tree;
 
 pub use error::*;
 #[cfg(feature = "memory")]
 pub use in_memory::*;
 pub use kernel::*;
+pub use maintenance::{MaintenanceHandler, MaintenanceMode};
+pub use map_recipient::MapRecipientHandler;
+pub use pagination::{Limit, OrderBy, Page, PageRef, Pagination};
 pub use policies::*;
 #[cfg(feature = "profiles")]
 pub use profiles::*;
+#[cfg(feature = "memory")]
+pub use records::TableRecord;
+#[cfg(feature = "memory")]
+pub use reference::{
+    ReferenceSchemaConfig, ReferenceServerConfig, ReferenceShareConfig, ReferenceTableConfig,
+};
+pub use rewrite::{LocationRewriteResolver, RewriteRule};
+pub use shared::SharedHandler;
+pub use slow_log::SlowLogHandler;
+#[cfg(feature = "sqlx")]
+pub use sql::*;
+pub use tree::{list_all_tables, list_all_tables_from, CatalogTree, SchemaNode, ShareNode};
 pub use types::*;
 
 /// Handler for discovering shares, schemas, and tables exposed by a Delta Sharing server.
@@ -51,6 +221,23 @@ pub trait DiscoveryHandler: Send + Sync {
         recipient: Self::Recipient,
     ) -> Result<ListSharesResponse>;
 
+    /// List the names of all shares that the recipient is allowed to read.
+    ///
+    /// Pages contain the names of the shares [`DiscoveryHandler::list_shares`] returns for
+    /// the same request, so page tokens can be used with either method. Implementations
+    /// may override this to avoid building the full shares.
+    async fn list_share_names(
+        &self,
+        request: ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        let response = self.list_shares(request, recipient).await?;
+        Ok(Page {
+            items: response.items.into_iter().map(|share| share.name).collect(),
+            next_page_token: response.next_page_token,
+        })
+    }
+
     /// Get a share by name.
     async fn get_share(&self, request: GetShareRequest) -> Result<GetShareResponse>;
 
@@ -68,12 +255,62 @@ pub trait DiscoveryHandler: Send + Sync {
         &self,
         request: ListShareTablesRequest,
     ) -> Result<ListShareTablesResponse>;
+
+    /// Get the table `table_ref` refers to, if the recipient is allowed to read its share.
+    ///
+    /// The default implementation walks the pages of [`DiscoveryHandler::list_share_names`]
+    /// and [`DiscoveryHandler::list_schema_tables`] until the table is found.
+    async fn get_table_ref(&self, table_ref: &TableRef, recipient: Self::Recipient) -> Result<Table>
+    where
+        Self::Recipient: Clone,
+    {
+        tree::get_table(self, table_ref, recipient).await
+    }
+
+    /// Search the tables the recipient is allowed to read by a substring of their names.
+    ///
+    /// A table matches if `query` is contained in the name of the table, its schema or its
+    /// share, ignoring case. Pagination is applied to the matching tables, so pages are
+    /// filled with matching tables only. The default implementation walks all pages of
+    /// [`DiscoveryHandler::list_shares`] and [`DiscoveryHandler::list_share_tables`] and
+    /// lists the matches in that order.
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        let tables = tree::search_tables(self, query, recipient).await?;
+        pagination::paginate(tables, &pagination)
+    }
+
+    /// Describe all shares, schemas, and tables the recipient is allowed to read.
+    ///
+    /// The default implementation walks all pages of the list APIs, so only shares
+    /// returned by [`DiscoveryHandler::list_shares`] for the recipient are included.
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        tree::describe(self, recipient).await
+    }
 }
 
 /// Resolver for the storage location of a table.
 #[async_trait::async_trait]
 pub trait TableLocationResover: Send + Sync {
     async fn resolve(&self, table: &types::TableRef) -> Result<url::Url>;
+
+    /// Resolve the storage locations of many tables.
+    ///
+    /// The results are returned in the order of `tables`. The default implementation
+    /// resolves all tables concurrently with [`TableLocationResover::resolve`].
+    async fn resolve_all(&self, tables: &[types::TableRef]) -> Vec<Result<url::Url>> {
+        futures::future::join_all(tables.iter().map(|table| self.resolve(table))).await
+    }
 }
 
 /// Handler for querying tables exposed by a Delta Sharing server.
@@ -85,6 +322,44 @@ pub trait TableQueryHandler: Send + Sync {
     ) -> Result<GetTableVersionResponse>;
 }
 
+#[async_trait::async_trait]
+impl<T: TableQueryHandler + ?Sized> TableQueryHandler for Arc<T> {
+    async fn get_table_version(
+        &self,
+        request: GetTableVersionRequest,
+    ) -> Result<GetTableVersionResponse> {
+        (**self).get_table_version(request).await
+    }
+}
+
+/// Format in which the data of a table is shared with recipients.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    /// Data is shared as parquet files.
+    #[default]
+    Parquet,
+    /// Data is shared as delta log actions.
+    Delta,
+}
+
+impl TableFormat {
+    /// Returns the `responseFormat` value used in the protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableFormat::Parquet => "parquet",
+            TableFormat::Delta => "delta",
+        }
+    }
+}
+
+impl std::fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Permission that a policy can authorize.
 #[derive(Debug, Clone)]
 pub enum Permission {
@@ -177,3 +452,82 @@ pub trait ProfileManager: Send + Sync {
     /// This should invalidate the profile and prevent it from being used.
     async fn revoke_profile(&self, fingerprint: String) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_ref() {
+        let table_ref = TableRef::parse("share1.schema1.table1").unwrap();
+        assert_eq!(
+            (
+                table_ref.share.as_str(),
+                table_ref.schema.as_str(),
+                table_ref.table.as_str()
+            ),
+            ("share1", "schema1", "table1")
+        );
+        assert_eq!(table_ref.to_string(), "share1.schema1.table1");
+
+        let table_ref = TableRef::parse("share1.`my.schema`.`tab``le`").unwrap();
+        assert_eq!(table_ref.schema, "my.schema");
+        assert_eq!(table_ref.table, "tab`le");
+        assert_eq!(table_ref.to_string(), "share1.`my.schema`.`tab``le`");
+    }
+
+    #[cfg(feature = "memory")]
+    #[tokio::test]
+    async fn test_decorators_forward_search_tables() {
+        use std::time::Duration;
+
+        let inner = || {
+            let mut config = [ShareConfig::new("share1", ["schema1"])]
+                .into_iter()
+                .collect::<InMemoryConfig>();
+            config
+                .schemas
+                .push(SchemaConfig::new("schema1", ["table1"]));
+            let mut table = TableConfig::new("table1", "s3://bucket/table1");
+            table.properties.insert("team".into(), "finance".into());
+            config.tables.push(table);
+            InMemoryHandler::<String>::new(config)
+        };
+
+        // only the in-memory handler matches the values of table properties.
+        async fn search<H: DiscoveryHandler<Recipient = String>>(handler: H) -> Vec<String> {
+            let page = handler
+                .search_tables("finance", "alice".to_string(), Pagination::default())
+                .await
+                .unwrap();
+            page.items.into_iter().map(|table| table.name).collect()
+        }
+        assert_eq!(
+            search(SlowLogHandler::new(inner(), Duration::from_secs(1))).await,
+            vec!["table1"]
+        );
+        assert_eq!(
+            search(MapRecipientHandler::new(inner(), |r: &String| r.clone())).await,
+            vec!["table1"]
+        );
+        assert_eq!(
+            search(MaintenanceHandler::new(inner(), MaintenanceMode::new())).await,
+            vec!["table1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_table_ref() {
+        for name in [
+            "share1.schema1",
+            "share1.schema1.table1.column1",
+            "share1..table1",
+            "share1.`schema1.table1",
+            "share1.`schema1`x.table1",
+            "share1.sch`ema1.table1",
+        ] {
+            let result = TableRef::parse(name);
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{}", name);
+        }
+    }
+}
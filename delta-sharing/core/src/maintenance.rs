@@ -0,0 +1,201 @@
+//! Read-only maintenance mode, e.g. while the storage of tables is migrated.
+//!
+//! The [`MaintenanceHandler`] keeps serving shares, schemas and tables, but rejects queries
+//! with [`Error::Unavailable`] while its [`MaintenanceMode`] is enabled.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::tree::CatalogTree;
+use crate::types as t;
+use crate::{DiscoveryHandler, Page, Pagination, TableQueryHandler};
+
+/// Switch for the maintenance mode, shared by all handlers and endpoints controlling it.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+}
+
+impl MaintenanceMode {
+    /// Create a new [`MaintenanceMode`], which is disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable the maintenance mode.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Whether the maintenance mode is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+}
+
+/// Handler that rejects queries while the maintenance mode is enabled.
+pub struct MaintenanceHandler<H> {
+    inner: H,
+    mode: MaintenanceMode,
+}
+
+impl<H> MaintenanceHandler<H> {
+    /// Create a new [`MaintenanceHandler`] wrapping `inner`, controlled by `mode`.
+    pub fn new(inner: H, mode: MaintenanceMode) -> Self {
+        Self { inner, mode }
+    }
+
+    /// Returns the maintenance mode controlling the handler.
+    pub fn mode(&self) -> &MaintenanceMode {
+        &self.mode
+    }
+}
+
+#[async_trait::async_trait]
+impl<H> DiscoveryHandler for MaintenanceHandler<H>
+where
+    H: DiscoveryHandler,
+{
+    type Recipient = H::Recipient;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        self.inner.list_shares(request, recipient).await
+    }
+
+    async fn list_share_names(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        self.inner.list_share_names(request, recipient).await
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        self.inner.get_share(request).await
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        self.inner.list_schemas(request).await
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        self.inner.list_schema_tables(request).await
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        self.inner.list_share_tables(request).await
+    }
+
+    async fn get_table_ref(
+        &self,
+        table_ref: &t::TableRef,
+        recipient: Self::Recipient,
+    ) -> Result<t::Table>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.get_table_ref(table_ref, recipient).await
+    }
+
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.search_tables(query, recipient, pagination).await
+    }
+
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.describe(recipient).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<H> TableQueryHandler for MaintenanceHandler<H>
+where
+    H: TableQueryHandler,
+{
+    async fn get_table_version(
+        &self,
+        request: t::GetTableVersionRequest,
+    ) -> Result<t::GetTableVersionResponse> {
+        if self.mode.is_enabled() {
+            return Err(Error::Unavailable(
+                "tables cannot be queried during maintenance".to_string(),
+            ));
+        }
+        self.inner.get_table_version(request).await
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+    use crate::{InMemoryConfig, InMemoryHandler, ShareConfig};
+
+    /// Query handler reporting the same version for every table.
+    struct FixedVersionHandler;
+
+    #[async_trait::async_trait]
+    impl TableQueryHandler for FixedVersionHandler {
+        async fn get_table_version(
+            &self,
+            _: t::GetTableVersionRequest,
+        ) -> Result<t::GetTableVersionResponse> {
+            Ok(t::GetTableVersionResponse { version: 7 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_mode() {
+        let config = [ShareConfig::new("share1", Vec::<String>::new())]
+            .into_iter()
+            .collect::<InMemoryConfig>();
+        let mode = MaintenanceMode::new();
+        let discovery = MaintenanceHandler::new(InMemoryHandler::<()>::new(config), mode.clone());
+        let query = MaintenanceHandler::new(FixedVersionHandler, mode.clone());
+        let request = t::GetTableVersionRequest::default();
+
+        let version = query.get_table_version(request.clone()).await.unwrap();
+        assert_eq!(version.version, 7);
+
+        mode.set_enabled(true);
+        assert!(query.mode().is_enabled());
+        let shares = discovery
+            .list_shares(t::ListSharesRequest::default(), ())
+            .await
+            .unwrap();
+        assert_eq!(shares.items[0].name, "share1");
+        let share = discovery
+            .get_share(t::GetShareRequest {
+                share: "share1".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(share.share.unwrap().name, "share1");
+        let result = query.get_table_version(request.clone()).await;
+        assert!(matches!(result, Err(Error::Unavailable(_))));
+
+        mode.set_enabled(false);
+        let version = query.get_table_version(request).await.unwrap();
+        assert_eq!(version.version, 7);
+    }
+}
@@ -0,0 +1,198 @@
+//! Mapping of recipients before they are passed to a [`DiscoveryHandler`].
+//!
+//! The [`MapRecipientHandler`] adapts the recipients authenticated by the server to the
+//! recipients known to the wrapped handler, e.g. to namespace recipients by tenant.
+
+use crate::error::Result;
+use crate::tree::CatalogTree;
+use crate::types as t;
+use crate::{DiscoveryHandler, Page, Pagination};
+
+/// Handler that maps the recipient of every request before delegating to another handler.
+pub struct MapRecipientHandler<H, F> {
+    inner: H,
+    map: F,
+}
+
+impl<H, F> MapRecipientHandler<H, F>
+where
+    H: DiscoveryHandler,
+    F: Fn(&H::Recipient) -> H::Recipient + Send + Sync,
+{
+    /// Create a new [`MapRecipientHandler`] applying `map` to the recipients passed to `inner`.
+    pub fn new(inner: H, map: F) -> Self {
+        Self { inner, map }
+    }
+
+    /// Returns the wrapped handler.
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl<H, F> DiscoveryHandler for MapRecipientHandler<H, F>
+where
+    H: DiscoveryHandler,
+    H::Recipient: Sync,
+    F: Fn(&H::Recipient) -> H::Recipient + Send + Sync,
+{
+    type Recipient = H::Recipient;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        let recipient = (self.map)(&recipient);
+        self.inner.list_shares(request, recipient).await
+    }
+
+    async fn list_share_names(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        let recipient = (self.map)(&recipient);
+        self.inner.list_share_names(request, recipient).await
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        self.inner.get_share(request).await
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        self.inner.list_schemas(request).await
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        self.inner.list_schema_tables(request).await
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        self.inner.list_share_tables(request).await
+    }
+
+    async fn get_table_ref(
+        &self,
+        table_ref: &t::TableRef,
+        recipient: Self::Recipient,
+    ) -> Result<t::Table>
+    where
+        Self::Recipient: Clone,
+    {
+        let recipient = (self.map)(&recipient);
+        self.inner.get_table_ref(table_ref, recipient).await
+    }
+
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        let recipient = (self.map)(&recipient);
+        self.inner.search_tables(query, recipient, pagination).await
+    }
+
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        let recipient = (self.map)(&recipient);
+        self.inner.describe(recipient).await
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{InMemoryConfig, InMemoryHandler, ShareConfig};
+
+    /// Handler recording the recipients it is called with.
+    struct RecordingHandler {
+        inner: InMemoryHandler<String>,
+        recipients: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl DiscoveryHandler for RecordingHandler {
+        type Recipient = String;
+
+        async fn list_shares(
+            &self,
+            request: t::ListSharesRequest,
+            recipient: Self::Recipient,
+        ) -> Result<t::ListSharesResponse> {
+            self.recipients.lock().unwrap().push(recipient.clone());
+            self.inner.list_shares(request, recipient).await
+        }
+
+        async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+            self.inner.get_share(request).await
+        }
+
+        async fn list_schemas(
+            &self,
+            request: t::ListSchemasRequest,
+        ) -> Result<t::ListSchemasResponse> {
+            self.inner.list_schemas(request).await
+        }
+
+        async fn list_schema_tables(
+            &self,
+            request: t::ListSchemaTablesRequest,
+        ) -> Result<t::ListSchemaTablesResponse> {
+            self.inner.list_schema_tables(request).await
+        }
+
+        async fn list_share_tables(
+            &self,
+            request: t::ListShareTablesRequest,
+        ) -> Result<t::ListShareTablesResponse> {
+            self.inner.list_share_tables(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_map_recipient() {
+        let config = [ShareConfig::new("share1", Vec::<String>::new())]
+            .into_iter()
+            .collect::<InMemoryConfig>();
+        let recording = RecordingHandler {
+            inner: InMemoryHandler::new(config),
+            recipients: Mutex::new(Vec::new()),
+        };
+        let handler = MapRecipientHandler::new(recording, |recipient: &String| {
+            format!("tenant1:{}", recipient)
+        });
+
+        let shares = handler
+            .list_shares(t::ListSharesRequest::default(), "alice".to_string())
+            .await
+            .unwrap();
+        assert_eq!(shares.items[0].name, "share1");
+        let names = handler
+            .list_share_names(t::ListSharesRequest::default(), "alice".to_string())
+            .await
+            .unwrap();
+        assert_eq!(names.items, vec!["share1"]);
+        handler.describe("bob".to_string()).await.unwrap();
+
+        assert_eq!(
+            *handler.inner().recipients.lock().unwrap(),
+            vec!["tenant1:alice", "tenant1:alice", "tenant1:bob"]
+        );
+    }
+}
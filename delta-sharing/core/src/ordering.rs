@@ -0,0 +1,118 @@
+//! Ordering of the catalog items returned by the discovery APIs.
+//!
+//! Items are ordered by name, then by id. The remaining fields break ties, so that
+//! items only compare equal if they are equal.
+
+use std::cmp::Ordering;
+
+use crate::types as t;
+
+impl Eq for t::Share {}
+
+impl Ord for t::Share {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for t::Share {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for t::Schema {}
+
+impl Ord for t::Schema {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.share.cmp(&other.share))
+    }
+}
+
+impl PartialOrd for t::Schema {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for t::Table {}
+
+impl Ord for t::Table {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.id.cmp(&other.id))
+            .then_with(|| self.schema.cmp(&other.schema))
+            .then_with(|| self.share.cmp(&other.share))
+            .then_with(|| self.share_id.cmp(&other.share_id))
+            .then_with(|| self.last_updated.cmp(&other.last_updated))
+    }
+}
+
+impl PartialOrd for t::Table {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share(name: &str, id: Option<&str>) -> t::Share {
+        t::Share {
+            name: name.to_string(),
+            id: id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_sort_shares() {
+        let mut shares = [
+            share("c", None),
+            share("a", Some("2")),
+            share("b", None),
+            share("a", Some("1")),
+        ];
+        shares.sort();
+        let keys = shares
+            .iter()
+            .map(|s| (s.name.as_str(), s.id.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![("a", Some("1")), ("a", Some("2")), ("b", None), ("c", None)]
+        );
+    }
+
+    #[test]
+    fn test_ordering_consistent_with_eq() {
+        let table = t::Table {
+            name: "table".to_string(),
+            schema: "schema".to_string(),
+            share: "share".to_string(),
+            ..Default::default()
+        };
+        let other = t::Table {
+            schema: "other".to_string(),
+            ..table.clone()
+        };
+        assert_eq!(table.cmp(&table.clone()), Ordering::Equal);
+        assert_ne!(table.cmp(&other), Ordering::Equal);
+        assert_ne!(table, other);
+
+        let schema = t::Schema {
+            name: "schema".to_string(),
+            share: "a".to_string(),
+        };
+        let other = t::Schema {
+            share: "b".to_string(),
+            ..schema.clone()
+        };
+        assert!(schema < other);
+    }
+}
@@ -0,0 +1,611 @@
+//! Pagination of list responses.
+//!
+//! List requests carry an optional `maxResults` and `pageToken`. The [`Pagination`] type
+//! captures these parameters and [`paginate`] applies them to a collection of items,
+//! producing a [`Page`] with the items of the requested page and the token for the next one.
+//! [`paginate_ref`] does the same for borrowed items, returning a [`PageRef`] view into them.
+//!
+//! Page tokens are offsets into the listed items. Since the items may change between requests,
+//! e.g. when the server configuration is reloaded or the server restarts with a different
+//! configuration, [`paginate_versioned`] additionally embeds a version of the listed content in
+//! the token. Tokens issued for a different version are rejected as stale with
+//! [`Error::MalformedPagination`], and clients have to restart the listing from the first page.
+//!
+//! The `maxResults` of a request is validated as a [`Limit`], the type shared by all
+//! client-provided limits on the number of results.
+//!
+//! Items are listed in the [`OrderBy`] ordering requested with [`Pagination::with_order_by`].
+//! The ordering is embedded in the page tokens, so subsequent pages keep the ordering of the
+//! first page.
+
+use crate::error::{Error, Result};
+
+/// Number of results returned per page if the client does not specify `maxResults`.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Maximum number of results returned per page.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// A positive limit on the number of results requested by a client.
+///
+/// Both the `maxResults` of list requests and the `limitHint` of query requests are limits.
+/// Zero and negative values are rejected with [`Error::InvalidArgument`], values larger than
+/// the cap are clamped to it.
+///
+/// # Example
+/// ```
+/// use delta_sharing_core::Limit;
+///
+/// assert_eq!(Limit::max_results(20).unwrap().get(), 20);
+/// assert_eq!(Limit::max_results(1000).unwrap().get(), 500);
+/// assert!(Limit::max_results(0).is_err());
+/// assert_eq!(Limit::limit_hint(1000, 100).unwrap().get(), 100);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Limit(usize);
+
+impl Limit {
+    /// Validate the value of the request `parameter`, clamping it to `cap`.
+    pub fn try_new(parameter: &str, value: i64, cap: usize) -> Result<Self> {
+        if value <= 0 {
+            return Err(Error::InvalidArgument(format!(
+                "{} must be positive, got {}",
+                parameter, value
+            )));
+        }
+        Ok(Self(usize::try_from(value).unwrap_or(usize::MAX).min(cap)))
+    }
+
+    /// Validate the `maxResults` of a list request, clamping it to [`MAX_PAGE_SIZE`].
+    pub fn max_results(value: i32) -> Result<Self> {
+        Self::try_new("maxResults", value.into(), MAX_PAGE_SIZE)
+    }
+
+    /// Validate the `limitHint` of a query request, clamping it to `cap`.
+    pub fn limit_hint(value: i64, cap: usize) -> Result<Self> {
+        Self::try_new("limitHint", value, cap)
+    }
+
+    /// Returns the number of results.
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+/// Ordering of the items in a listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OrderBy {
+    /// Order items by name.
+    #[default]
+    Name,
+    /// Order items by id, items without an id are ordered by name.
+    Id,
+    /// Keep the order in which items were added.
+    Insertion,
+    /// Order items by creation time, items without a creation time are listed last.
+    CreatedAt,
+}
+
+impl OrderBy {
+    /// Returns the name of the ordering as used in page tokens.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderBy::Name => "name",
+            OrderBy::Id => "id",
+            OrderBy::Insertion => "insertion",
+            OrderBy::CreatedAt => "created",
+        }
+    }
+}
+
+impl std::fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for OrderBy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "name" => Ok(OrderBy::Name),
+            "id" => Ok(OrderBy::Id),
+            "insertion" => Ok(OrderBy::Insertion),
+            "created" => Ok(OrderBy::CreatedAt),
+            _ => Err(Error::MalformedPagination(format!(
+                "unknown ordering '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Pagination parameters of a list request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pagination {
+    max_results: Option<i32>,
+    page_token: Option<String>,
+    order_by: Option<OrderBy>,
+}
+
+impl Pagination {
+    /// Create a new [`Pagination`] instance.
+    ///
+    /// The page token is not validated until the pagination is applied. Use
+    /// [`Pagination::try_new`] to reject malformed tokens up front.
+    pub fn new(max_results: Option<i32>, page_token: Option<String>) -> Self {
+        Self {
+            max_results,
+            page_token,
+            order_by: None,
+        }
+    }
+
+    /// Request the items to be listed in the given ordering.
+    ///
+    /// Page tokens are only valid for the ordering they were issued for.
+    pub fn with_order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    /// Create a new [`Pagination`] instance, validating the page token and `maxResults`.
+    ///
+    /// Returns [`Error::MalformedPagination`] if the page token is not a valid token, and
+    /// [`Error::InvalidArgument`] if `maxResults` is not a valid [`Limit`].
+    pub fn try_new(max_results: Option<i32>, page_token: Option<String>) -> Result<Self> {
+        if let Some(max_results) = max_results {
+            Limit::max_results(max_results)?;
+        }
+        if let Some(token) = &page_token {
+            parse_page_token(token)?;
+        }
+        Ok(Self::new(max_results, page_token))
+    }
+
+    /// Returns the maximum number of results requested by the client.
+    pub fn max_results(&self) -> Option<i32> {
+        self.max_results
+    }
+
+    /// Returns the page token provided by the client.
+    pub fn page_token(&self) -> Option<&str> {
+        self.page_token.as_deref()
+    }
+
+    /// Returns the ordering the items should be listed in.
+    ///
+    /// This is the ordering encoded in the page token if one is given, so that all pages
+    /// of a listing use the same ordering. Otherwise it is the requested ordering, which
+    /// defaults to [`OrderBy::Name`].
+    pub fn order_by(&self) -> OrderBy {
+        self.page_token()
+            .and_then(|token| parse_page_token(token).ok())
+            .map(|token| token.order_by)
+            .or(self.order_by)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of items that should be returned in a page.
+    ///
+    /// If the client did not specify `maxResults`, the `default` is used. The result is
+    /// never larger than `cap`. Non-positive values of `maxResults`, which are rejected by
+    /// [`Pagination::try_new`], yield an empty page.
+    ///
+    /// # Example
+    /// ```
+    /// use delta_sharing_core::Pagination;
+    ///
+    /// assert_eq!(Pagination::new(None, None).effective_limit(100, 500), 100);
+    /// assert_eq!(Pagination::new(Some(0), None).effective_limit(100, 500), 0);
+    /// assert_eq!(Pagination::new(Some(1000), None).effective_limit(100, 500), 500);
+    /// ```
+    pub fn effective_limit(&self, default: usize, cap: usize) -> usize {
+        match self.max_results {
+            Some(max_results) => {
+                Limit::try_new("maxResults", max_results.into(), cap).map_or(0, |limit| limit.get())
+            }
+            None => default.min(cap),
+        }
+    }
+}
+
+/// A single page of a list response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The items in the page.
+    pub items: Vec<T>,
+    /// Token that can be used to retrieve the next page.
+    pub next_page_token: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Returns the number of items in the page.
+    ///
+    /// This can be used to pre-allocate collections when converting the page.
+    pub fn capacity_hint(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// A single page of a list response, borrowing the items from the underlying collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRef<'a, T> {
+    /// The items in the page.
+    pub items: &'a [T],
+    /// Token that can be used to retrieve the next page.
+    pub next_page_token: Option<String>,
+}
+
+impl<T: Clone> PageRef<'_, T> {
+    /// Convert the borrowed page into an owned [`Page`] by cloning the items in the page.
+    pub fn to_page(&self) -> Page<T> {
+        Page {
+            items: self.items.to_vec(),
+            next_page_token: self.next_page_token.clone(),
+        }
+    }
+}
+
+/// Select the page of `items` requested by the `pagination` parameters.
+///
+/// The page token encodes the offset of the first item in the page. The page reuses the
+/// allocation of `items`, so no items are cloned or moved into a new buffer.
+pub fn paginate<T>(mut items: Vec<T>, pagination: &Pagination) -> Result<Page<T>> {
+    let (start, end, next_page_token) = page_bounds(items.len(), pagination)?;
+    items.truncate(end);
+    items.drain(..start);
+    Ok(Page {
+        items,
+        next_page_token,
+    })
+}
+
+/// Select the page of `items` requested by the `pagination` parameters, where `version`
+/// identifies the content the items were listed from.
+///
+/// Page tokens issued for a different `version` are rejected with
+/// [`Error::MalformedPagination`].
+///
+/// # Example
+/// ```
+/// use delta_sharing_core::pagination::{paginate_versioned, Pagination};
+///
+/// let page = paginate_versioned(vec![1, 2, 3], &Pagination::new(Some(2), None), 1).unwrap();
+/// let pagination = Pagination::new(Some(2), page.next_page_token);
+/// assert!(paginate_versioned(vec![1, 2, 3], &pagination, 1).is_ok());
+/// assert!(paginate_versioned(vec![1, 2, 4], &pagination, 2).is_err());
+/// ```
+pub fn paginate_versioned<T>(
+    mut items: Vec<T>,
+    pagination: &Pagination,
+    version: u64,
+) -> Result<Page<T>> {
+    let (start, end, next_page_token) =
+        versioned_page_bounds(items.len(), pagination, Some(version))?;
+    items.truncate(end);
+    items.drain(..start);
+    Ok(Page {
+        items,
+        next_page_token,
+    })
+}
+
+/// Select the page of borrowed `items` requested by the `pagination` parameters.
+///
+/// Produces the same items and page token as [`paginate`] without taking ownership.
+///
+/// # Example
+/// ```
+/// use delta_sharing_core::pagination::{paginate_ref, Pagination};
+///
+/// let items = vec![1, 2, 3];
+/// let page = paginate_ref(&items, &Pagination::new(Some(2), None)).unwrap();
+/// assert_eq!(page.items, &[1, 2]);
+/// assert_eq!(page.next_page_token.as_deref(), Some("2"));
+/// ```
+pub fn paginate_ref<'a, T>(items: &'a [T], pagination: &Pagination) -> Result<PageRef<'a, T>> {
+    let (start, end, next_page_token) = page_bounds(items.len(), pagination)?;
+    Ok(PageRef {
+        items: &items[start..end],
+        next_page_token,
+    })
+}
+
+/// Compute the range of the requested page within `len` items and the token for the next page.
+fn page_bounds(len: usize, pagination: &Pagination) -> Result<(usize, usize, Option<String>)> {
+    versioned_page_bounds(len, pagination, None)
+}
+
+fn versioned_page_bounds(
+    len: usize,
+    pagination: &Pagination,
+    version: Option<u64>,
+) -> Result<(usize, usize, Option<String>)> {
+    let offset = match pagination.page_token() {
+        Some(token) => {
+            let page_token = parse_page_token(token)?;
+            if version.is_some() && page_token.version != version {
+                return Err(Error::MalformedPagination(format!(
+                    "page token '{}' is stale, the listing changed since it was issued",
+                    token
+                )));
+            }
+            if pagination
+                .order_by
+                .is_some_and(|order_by| order_by != page_token.order_by)
+            {
+                return Err(Error::MalformedPagination(format!(
+                    "page token '{}' was issued for a listing ordered by {}",
+                    token, page_token.order_by
+                )));
+            }
+            page_token.offset
+        }
+        None => 0,
+    };
+    let limit = pagination.effective_limit(DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE);
+    // NOTE: Offsets at or past the end of the items, e.g. after the listing shrank between
+    //       unversioned requests, select an empty last page instead of failing.
+    let start = offset.min(len);
+    let end = start.saturating_add(limit).min(len);
+    let next_page_token = (end < len).then_some(PageToken {
+        offset: end,
+        version,
+        order_by: pagination.order_by(),
+    });
+    Ok((start, end, next_page_token.map(|token| token.to_string())))
+}
+
+/// Decoded page token, formatted as `<offset>[-<version>][@<order>]`.
+///
+/// The ordering is omitted for the default [`OrderBy::Name`].
+struct PageToken {
+    offset: usize,
+    version: Option<u64>,
+    order_by: OrderBy,
+}
+
+impl std::fmt::Display for PageToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "{}-{:016x}", self.offset, version)?,
+            None => write!(f, "{}", self.offset)?,
+        }
+        if self.order_by != OrderBy::Name {
+            write!(f, "@{}", self.order_by)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_page_token(token: &str) -> Result<PageToken> {
+    let invalid = || Error::MalformedPagination(format!("invalid page token '{}'", token));
+    let (position, order_by) = match token.split_once('@') {
+        Some((position, order_by)) => (position, order_by.parse().map_err(|_| invalid())?),
+        None => (token, OrderBy::Name),
+    };
+    let (offset, version) = match position.split_once('-') {
+        Some((offset, version)) => {
+            let version = u64::from_str_radix(version, 16).map_err(|_| invalid())?;
+            (offset, Some(version))
+        }
+        None => (position, None),
+    };
+    let offset = offset.parse::<usize>().map_err(|_| invalid())?;
+    Ok(PageToken {
+        offset,
+        version,
+        order_by,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_limit_defaults() {
+        let pagination = Pagination::new(None, None);
+        assert_eq!(pagination.effective_limit(100, 500), 100);
+        assert_eq!(pagination.effective_limit(1000, 500), 500);
+    }
+
+    #[test]
+    fn effective_limit_zero() {
+        let pagination = Pagination::new(Some(0), None);
+        assert_eq!(pagination.effective_limit(100, 500), 0);
+        let pagination = Pagination::new(Some(-1), None);
+        assert_eq!(pagination.effective_limit(100, 500), 0);
+    }
+
+    #[test]
+    fn effective_limit_over_cap() {
+        let pagination = Pagination::new(Some(501), None);
+        assert_eq!(pagination.effective_limit(100, 500), 500);
+        let pagination = Pagination::new(Some(20), None);
+        assert_eq!(pagination.effective_limit(100, 500), 20);
+    }
+
+    #[test]
+    fn limit_rejects_non_positive() {
+        for value in [0, -1, i64::MIN] {
+            let err = Limit::limit_hint(value, 100).unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidArgument(message) if message.contains("limitHint"))
+            );
+        }
+        for value in [0, -1] {
+            let err = Limit::max_results(value).unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidArgument(message) if message.contains("maxResults"))
+            );
+        }
+        assert!(matches!(
+            Pagination::try_new(Some(0), None),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn limit_clamps_to_cap() {
+        assert_eq!(Limit::limit_hint(1, 100).unwrap().get(), 1);
+        assert_eq!(Limit::limit_hint(100, 100).unwrap().get(), 100);
+        assert_eq!(Limit::limit_hint(i64::MAX, 100).unwrap().get(), 100);
+        assert_eq!(Limit::max_results(i32::MAX).unwrap().get(), MAX_PAGE_SIZE);
+        let pagination = Pagination::try_new(Some(i32::MAX), None).unwrap();
+        assert_eq!(
+            pagination.effective_limit(DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE),
+            Limit::max_results(i32::MAX).unwrap().get()
+        );
+    }
+
+    #[test]
+    fn paginate_items() {
+        let items = (0..5).collect::<Vec<_>>();
+
+        let page = paginate(items.clone(), &Pagination::new(Some(2), None)).unwrap();
+        assert_eq!(page.items, vec![0, 1]);
+        assert_eq!(page.capacity_hint(), 2);
+        assert_eq!(page.next_page_token.as_deref(), Some("2"));
+
+        let page = paginate(
+            items.clone(),
+            &Pagination::new(Some(2), page.next_page_token),
+        )
+        .unwrap();
+        assert_eq!(page.items, vec![2, 3]);
+
+        let page = paginate(items, &Pagination::new(Some(2), page.next_page_token)).unwrap();
+        assert_eq!(page.items, vec![4]);
+        assert_eq!(page.next_page_token, None);
+    }
+
+    #[test]
+    fn paginate_offset_at_or_past_end() {
+        let items = (0..5).collect::<Vec<_>>();
+        for offset in [5, 6, usize::MAX] {
+            let pagination = Pagination::new(Some(2), Some(offset.to_string()));
+
+            let page = paginate(items.clone(), &pagination).unwrap();
+            assert!(page.items.is_empty());
+            assert_eq!(page.next_page_token, None);
+
+            let page = paginate_ref(&items, &pagination).unwrap();
+            assert!(page.items.is_empty());
+            assert_eq!(page.next_page_token, None);
+        }
+
+        let page = paginate_versioned(items.clone(), &Pagination::new(Some(5), None), 1).unwrap();
+        assert_eq!(page.items, items);
+        assert_eq!(page.next_page_token, None);
+        let token = PageToken {
+            offset: 5,
+            version: Some(1),
+            order_by: OrderBy::default(),
+        };
+        let pagination = Pagination::new(Some(2), Some(token.to_string()));
+        let page = paginate_versioned(items, &pagination, 1).unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(page.next_page_token, None);
+    }
+
+    #[test]
+    fn try_new_rejects_garbage_token() {
+        let err = Pagination::try_new(Some(10), Some("not-a-token".to_string())).unwrap_err();
+        assert!(
+            matches!(err, Error::MalformedPagination(message) if message.contains("not-a-token"))
+        );
+    }
+
+    #[test]
+    fn try_new_accepts_valid_token() {
+        let pagination = Pagination::try_new(Some(10), Some("20".to_string())).unwrap();
+        assert_eq!(pagination.page_token(), Some("20"));
+        assert_eq!(
+            pagination,
+            Pagination::new(Some(10), Some("20".to_string()))
+        );
+
+        let pagination = Pagination::try_new(None, None).unwrap();
+        assert_eq!(pagination, Pagination::default());
+    }
+
+    #[test]
+    fn paginate_ref_matches_paginate() {
+        let items = (0..1234).map(|i| i.to_string()).collect::<Vec<_>>();
+        let requests = [
+            Pagination::new(None, None),
+            Pagination::new(Some(0), None),
+            Pagination::new(Some(7), Some("30".to_string())),
+            Pagination::new(Some(500), Some("1000".to_string())),
+            Pagination::new(Some(10), Some("5000".to_string())),
+        ];
+        for pagination in requests {
+            let borrowed = paginate_ref(&items, &pagination).unwrap();
+            let owned = paginate(items.clone(), &pagination).unwrap();
+            assert_eq!(borrowed.to_page(), owned);
+            assert_eq!(borrowed.items, owned.items.as_slice());
+        }
+    }
+
+    #[test]
+    fn paginate_reuses_allocation() {
+        let items = (0..MAX_PAGE_SIZE).collect::<Vec<_>>();
+        let ptr = items.as_ptr();
+        let page = paginate(items, &Pagination::new(Some(MAX_PAGE_SIZE as i32), None)).unwrap();
+        assert_eq!(page.items.as_ptr(), ptr);
+        assert_eq!(page.items.len(), MAX_PAGE_SIZE);
+        assert_eq!(page.next_page_token, None);
+    }
+
+    #[test]
+    fn paginate_versioned_rejects_stale_tokens() {
+        let items = (0..5).collect::<Vec<_>>();
+
+        let page = paginate_versioned(items.clone(), &Pagination::new(Some(2), None), 7).unwrap();
+        assert_eq!(page.items, vec![0, 1]);
+        let token = page.next_page_token.unwrap();
+        assert!(Pagination::try_new(None, Some(token.clone())).is_ok());
+
+        let pagination = Pagination::new(Some(2), Some(token));
+        let page = paginate_versioned(items.clone(), &pagination, 7).unwrap();
+        assert_eq!(page.items, vec![2, 3]);
+
+        let err = paginate_versioned(items.clone(), &pagination, 8).unwrap_err();
+        assert!(matches!(err, Error::MalformedPagination(message) if message.contains("stale")));
+
+        // tokens without a version were not issued for any versioned listing.
+        let pagination = Pagination::new(Some(2), Some("2".to_string()));
+        assert!(paginate_versioned(items, &pagination, 7).is_err());
+    }
+
+    #[test]
+    fn paginate_order_by() {
+        let items = (0..5).collect::<Vec<_>>();
+
+        let pagination = Pagination::new(Some(2), None).with_order_by(OrderBy::Id);
+        assert_eq!(pagination.order_by(), OrderBy::Id);
+        let page = paginate_versioned(items.clone(), &pagination, 1).unwrap();
+        let token = page.next_page_token.unwrap();
+        assert!(token.ends_with("@id"));
+
+        // the token preserves the ordering, even if it is not requested again.
+        let pagination = Pagination::new(Some(2), Some(token.clone()));
+        assert_eq!(pagination.order_by(), OrderBy::Id);
+        let page = paginate_versioned(items.clone(), &pagination, 1).unwrap();
+        assert_eq!(page.items, vec![2, 3]);
+        assert!(page.next_page_token.unwrap().ends_with("@id"));
+
+        let pagination = Pagination::new(Some(2), Some(token)).with_order_by(OrderBy::Name);
+        let err = paginate_versioned(items.clone(), &pagination, 1).unwrap_err();
+        assert!(matches!(err, Error::MalformedPagination(message) if message.contains("id")));
+
+        // the default ordering is not encoded, so tokens stay compatible.
+        let page = paginate(items, &Pagination::new(Some(2), None)).unwrap();
+        assert_eq!(page.next_page_token.as_deref(), Some("2"));
+        assert_eq!(Pagination::new(None, None).order_by(), OrderBy::Name);
+        assert!(Pagination::try_new(None, Some("2@unknown".to_string())).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Access control for anonymous recipients.
+
+use crate::error::Result;
+use crate::{Decision, DefaultRecipient, Permission, Policy, ProfileClaims, Resource};
+
+/// Policy that controls whether anonymous recipients may access any resource.
+///
+/// When anonymous access is disabled, all requests from anonymous recipients are denied,
+/// while requests from recipients with a profile are delegated to the wrapped policy.
+pub struct AnonymousAccessPolicy<P> {
+    policy: P,
+    allow_anonymous: bool,
+}
+
+impl<P> AnonymousAccessPolicy<P> {
+    /// Create a new instance of [`AnonymousAccessPolicy`] wrapping `policy`.
+    ///
+    /// Anonymous access is allowed by default.
+    pub fn new(policy: P) -> Self {
+        Self {
+            policy,
+            allow_anonymous: true,
+        }
+    }
+
+    /// Set whether anonymous recipients may access resources.
+    pub fn allow_anonymous(mut self, allow_anonymous: bool) -> Self {
+        self.allow_anonymous = allow_anonymous;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<P, C> Policy for AnonymousAccessPolicy<P>
+where
+    P: Policy<Recipient = DefaultRecipient<C>>,
+    C: ProfileClaims,
+{
+    type Recipient = DefaultRecipient<C>;
+
+    async fn authorize(
+        &self,
+        resource: Resource,
+        permission: Permission,
+        recipient: &Self::Recipient,
+    ) -> Result<Decision> {
+        if !self.allow_anonymous && matches!(recipient, DefaultRecipient::Anonymous) {
+            return Ok(Decision::Deny);
+        }
+        self.policy.authorize(resource, permission, recipient).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policies::ConstantPolicy;
+    use crate::{DefaultClaims, DeltaRecipient};
+
+    fn profile() -> DeltaRecipient {
+        DeltaRecipient::Profile(DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec!["share".to_string()],
+            expiration: None,
+            admin: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn allow_anonymous_by_default() {
+        let policy = AnonymousAccessPolicy::new(ConstantPolicy::<DeltaRecipient>::default());
+        let decision = policy
+            .authorize(
+                Resource::share("share"),
+                Permission::Read,
+                &DeltaRecipient::Anonymous,
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision, Decision::Allow);
+    }
+
+    #[tokio::test]
+    async fn deny_anonymous() {
+        let policy = AnonymousAccessPolicy::new(ConstantPolicy::<DeltaRecipient>::default())
+            .allow_anonymous(false);
+
+        let decision = policy
+            .authorize(
+                Resource::share("share"),
+                Permission::Read,
+                &DeltaRecipient::Anonymous,
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision, Decision::Deny);
+
+        let decision = policy
+            .authorize(Resource::share("share"), Permission::Read, &profile())
+            .await
+            .unwrap();
+        assert_eq!(decision, Decision::Allow);
+    }
+}
@@ -6,7 +6,18 @@
 //! recipient.
 
 use crate::error::Result;
-use crate::{Decision, Permission, Policy, Resource};
+use crate::types::TableRef;
+use crate::{Decision, DiscoveryHandler, GetShareRequest, Permission, Policy, Resource};
+
+#[cfg(feature = "profiles")]
+mod anonymous;
+#[cfg(feature = "profiles")]
+mod rate_limit;
+
+#[cfg(feature = "profiles")]
+pub use anonymous::*;
+#[cfg(feature = "profiles")]
+pub use rate_limit::*;
 
 /// Policy that always returns a constant decision.
 ///
@@ -64,6 +75,50 @@ impl<T: Send + Sync> Policy for ConstantPolicy<T> {
     }
 }
 
+/// Check if the recipient is allowed to read a share.
+///
+/// Returns an error if the share does not exist, and `false` if it exists but the
+/// recipient is not allowed to read it.
+pub async fn can_read_share<T: Send + Sync>(
+    discovery: &dyn DiscoveryHandler<Recipient = T>,
+    policy: &dyn Policy<Recipient = T>,
+    share: &str,
+    recipient: &T,
+) -> Result<bool> {
+    discovery
+        .get_share(GetShareRequest {
+            share: share.to_string(),
+        })
+        .await?;
+    let decision = policy
+        .authorize(Resource::share(share), Permission::Read, recipient)
+        .await?;
+    Ok(decision == Decision::Allow)
+}
+
+/// Check if the recipient is allowed to read a table, without loading the table.
+///
+/// Returns an error if the table does not exist, and `false` if it exists but the
+/// recipient is not allowed to read it. The storage location of the table is not
+/// resolved, so views and tables with relative locations are checked like any other
+/// table. Tables are identified by their fully qualified name `<share>.<schema>.<table>`,
+/// quoted as [`TableRef::parse`] expects.
+pub async fn can_read_table<T: Clone + Send + Sync>(
+    discovery: &dyn DiscoveryHandler<Recipient = T>,
+    policy: &dyn Policy<Recipient = T>,
+    table_ref: &TableRef,
+    recipient: &T,
+) -> Result<bool> {
+    discovery
+        .get_table_ref(table_ref, recipient.clone())
+        .await?;
+    let resource = Resource::table(table_ref.to_string());
+    let decision = policy
+        .authorize(resource, Permission::Read, recipient)
+        .await?;
+    Ok(decision == Decision::Allow)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -118,4 +173,128 @@ mod test {
             .unwrap();
         assert_eq!(decision, Decision::Deny);
     }
+
+    #[cfg(feature = "memory")]
+    mod access {
+        use super::*;
+        use crate::{Error, InMemoryConfig, InMemoryHandler};
+
+        fn handler() -> InMemoryHandler<()> {
+            let config = serde_json::from_value::<InMemoryConfig>(serde_json::json!({
+                "shares": [{"name": "share1", "schemaRefs": ["schema1"]}],
+                "schemas": [{"name": "schema1", "tableRefs": ["table1", "view1", "relative1", "my.table"]}],
+                "tables": [
+                    {"name": "table1", "location": "file:///tmp"},
+                    {"name": "view1", "location": "", "kind": {"type": "view", "definition": "SELECT 1"}},
+                    {"name": "relative1", "location": "relative/table1"},
+                    {"name": "my.table", "location": "file:///tmp/my.table"}
+                ]
+            }))
+            .unwrap();
+            InMemoryHandler::new(config)
+        }
+
+        fn table_ref(table: &str) -> TableRef {
+            TableRef {
+                share: "share1".to_string(),
+                schema: "schema1".to_string(),
+                table: table.to_string(),
+            }
+        }
+
+        #[tokio::test]
+        async fn accessible() {
+            let handler = handler();
+            let policy = ConstantPolicy::new(Decision::Allow);
+
+            assert!(can_read_share(&handler, &policy, "share1", &())
+                .await
+                .unwrap());
+            assert!(can_read_table(&handler, &policy, &table_ref("table1"), &())
+                .await
+                .unwrap());
+        }
+
+        #[tokio::test]
+        async fn unresolved_locations() {
+            let handler = handler();
+            for table in ["view1", "relative1"] {
+                let allowed = ConstantPolicy::new(Decision::Allow);
+                assert!(can_read_table(&handler, &allowed, &table_ref(table), &())
+                    .await
+                    .unwrap());
+                let denied = ConstantPolicy::new(Decision::Deny);
+                assert!(!can_read_table(&handler, &denied, &table_ref(table), &())
+                    .await
+                    .unwrap());
+            }
+        }
+
+        /// Policy allowing to read a single resource only.
+        struct SinglePolicy(Resource);
+
+        #[async_trait::async_trait]
+        impl Policy for SinglePolicy {
+            type Recipient = ();
+
+            async fn authorize(
+                &self,
+                resource: Resource,
+                _: Permission,
+                _: &(),
+            ) -> Result<Decision> {
+                Ok(if resource == self.0 {
+                    Decision::Allow
+                } else {
+                    Decision::Deny
+                })
+            }
+        }
+
+        #[tokio::test]
+        async fn quoted_table_names() {
+            let handler = handler();
+            let policy = SinglePolicy(Resource::table("share1.schema1.`my.table`"));
+            assert!(
+                can_read_table(&handler, &policy, &table_ref("my.table"), &())
+                    .await
+                    .unwrap()
+            );
+            assert!(
+                !can_read_table(&handler, &policy, &table_ref("table1"), &())
+                    .await
+                    .unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn forbidden() {
+            let handler = handler();
+            let policy = ConstantPolicy::new(Decision::Deny);
+
+            assert!(!can_read_share(&handler, &policy, "share1", &())
+                .await
+                .unwrap());
+            assert!(
+                !can_read_table(&handler, &policy, &table_ref("table1"), &())
+                    .await
+                    .unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn nonexistent() {
+            let handler = handler();
+            let policy = ConstantPolicy::new(Decision::Allow);
+
+            let err = can_read_share(&handler, &policy, "share2", &())
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::NotFound(_)));
+            let err = can_read_table(&handler, &policy, &table_ref("table2"), &())
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::NotFound(_)));
+        }
+    }
 }
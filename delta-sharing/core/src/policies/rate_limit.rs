@@ -0,0 +1,178 @@
+//! Rate limiting for recipients.
+//!
+//! The [`RateLimiter`] throttles recipients that exceed the configured request rate, so
+//! that a single misbehaving recipient cannot starve others. It is charged once per request
+//! served, independent of how many resources the request touches.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::{DefaultRecipient, ProfileClaims};
+
+/// Configuration for a token-bucket rate limiter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    /// Number of requests a recipient may issue per second on average.
+    pub requests_per_second: f64,
+    /// Maximum number of requests a recipient may issue in a single burst.
+    pub burst: u32,
+}
+
+impl RateLimitConfig {
+    /// Create a new [`RateLimitConfig`].
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<Option<String>, Bucket>,
+    swept_at: Instant,
+}
+
+/// Per-recipient token-bucket rate limiter.
+///
+/// Recipients are identified by their profile fingerprint, while all anonymous recipients
+/// share a single bucket. Buckets of recipients that have been idle long enough to refill
+/// completely are evicted, so memory is bounded by the recipients active within that time.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Create a new [`RateLimiter`].
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Charge a single request of `recipient`, failing with [`Error::RateLimited`] if the
+    /// recipient exceeded its rate.
+    pub fn check<C: ProfileClaims>(&self, recipient: &DefaultRecipient<C>) -> Result<()> {
+        if !self.try_acquire(recipient.fingerprint(), Instant::now()) {
+            return Err(Error::RateLimited);
+        }
+        Ok(())
+    }
+
+    /// Time after which an unused bucket is full again.
+    fn refill_time(&self) -> Duration {
+        Duration::try_from_secs_f64(self.config.burst as f64 / self.config.requests_per_second)
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Number of buckets currently tracked.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buckets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .buckets
+            .len()
+    }
+
+    /// Take a token from the bucket identified by `key`, returning `false` if it is empty.
+    fn try_acquire(&self, key: Option<String>, now: Instant) -> bool {
+        let capacity = self.config.burst as f64;
+        let refill_time = self.refill_time();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        // NOTE: A bucket idle for the refill time is full, which is the same as no bucket.
+        if now.saturating_duration_since(buckets.swept_at) >= refill_time {
+            buckets
+                .buckets
+                .retain(|_, bucket| now.saturating_duration_since(bucket.updated_at) < refill_time);
+            buckets.swept_at = now;
+        }
+        let bucket = buckets.buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.config.requests_per_second).min(capacity);
+        bucket.updated_at = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeltaRecipient;
+
+    fn policy(requests_per_second: f64, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig::new(requests_per_second, burst))
+    }
+
+    #[test]
+    fn exceeding_rate_is_limited() {
+        let policy = policy(0.001, 2);
+        let recipient: DeltaRecipient = DeltaRecipient::Anonymous;
+
+        for _ in 0..2 {
+            policy.check(&recipient).unwrap();
+        }
+        let err = policy.check(&recipient).unwrap_err();
+        assert!(matches!(err, Error::RateLimited));
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let policy = policy(2.0, 1);
+        let now = Instant::now();
+
+        assert!(policy.try_acquire(None, now));
+        assert!(!policy.try_acquire(None, now));
+        assert!(!policy.try_acquire(None, now + Duration::from_millis(100)));
+        assert!(policy.try_acquire(None, now + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn buckets_are_per_recipient() {
+        let policy = policy(0.001, 1);
+        let now = Instant::now();
+
+        assert!(policy.try_acquire(Some("a".to_string()), now));
+        assert!(!policy.try_acquire(Some("a".to_string()), now));
+        assert!(policy.try_acquire(Some("b".to_string()), now));
+        assert!(policy.try_acquire(None, now));
+        assert!(!policy.try_acquire(None, now));
+    }
+
+    #[test]
+    fn idle_buckets_are_evicted() {
+        let policy = policy(10.0, 1);
+        let now = Instant::now();
+
+        for n in 0..100 {
+            assert!(policy.try_acquire(Some(n.to_string()), now));
+        }
+        assert_eq!(policy.len(), 100);
+        // all buckets are full again after the refill time, so they are dropped.
+        assert!(policy.try_acquire(None, now + Duration::from_millis(200)));
+        assert_eq!(policy.len(), 1);
+    }
+}
@@ -1,10 +1,13 @@
 use ring::digest;
 use serde::{Deserialize, Serialize};
 
-use crate::ProfileClaims;
+use crate::{Profile, ProfileClaims};
 
 mod tokens;
 
+/// Version of the profile file format defined by the Delta Sharing protocol.
+pub const DEFAULT_SHARE_CREDENTIALS_VERSION: i32 = 1;
+
 pub type DeltaRecipient<C = DefaultClaims> = DefaultRecipient<C>;
 
 /// Default recipient for delta sharing.
@@ -24,6 +27,16 @@ impl<C: ProfileClaims> DefaultRecipient<C> {
     }
 }
 
+impl DefaultRecipient<DefaultClaims> {
+    /// Whether the recipient's profile grants server admin rights.
+    pub fn is_admin(&self) -> bool {
+        match self {
+            DefaultRecipient::Anonymous => false,
+            DefaultRecipient::Profile(claims) => claims.admin.unwrap_or(false),
+        }
+    }
+}
+
 /// Default claims for delta sharing.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DefaultClaims {
@@ -57,3 +70,89 @@ impl ProfileClaims for DefaultClaims {
         hex::encode(digest.as_ref())
     }
 }
+
+/// Server settings that are embedded in the profiles handed out to recipients.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileConfig {
+    /// Public url of the sharing server.
+    pub endpoint: String,
+
+    /// File format version of the issued profiles.
+    #[serde(default = "default_share_credentials_version")]
+    pub share_credentials_version: i32,
+}
+
+fn default_share_credentials_version() -> i32 {
+    DEFAULT_SHARE_CREDENTIALS_VERSION
+}
+
+impl ProfileConfig {
+    /// Create a new [`ProfileConfig`] using the current profile file format version.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            share_credentials_version: DEFAULT_SHARE_CREDENTIALS_VERSION,
+        }
+    }
+
+    /// Set the profile file format version.
+    pub fn with_share_credentials_version(mut self, version: i32) -> Self {
+        self.share_credentials_version = version;
+        self
+    }
+
+    /// Create a profile for a bearer token issued by this server.
+    pub fn profile(
+        &self,
+        bearer_token: impl Into<String>,
+        expiration_time: Option<String>,
+    ) -> Profile {
+        Profile {
+            share_credentials_version: self.share_credentials_version,
+            endpoint: self.endpoint.clone(),
+            bearer_token: bearer_token.into(),
+            expiration_time: expiration_time.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_config() {
+        let config = ProfileConfig::new("https://sharing.example.com/delta-sharing");
+        let profile = config.profile("token", None);
+        assert_eq!(
+            profile.endpoint,
+            "https://sharing.example.com/delta-sharing"
+        );
+        assert_eq!(
+            profile.share_credentials_version,
+            DEFAULT_SHARE_CREDENTIALS_VERSION
+        );
+        assert_eq!(profile.bearer_token, "token");
+        assert!(profile.expiration_time.is_empty());
+
+        let config = config.with_share_credentials_version(2);
+        let profile = config.profile("token", Some("2024-01-01T00:00:00Z".to_string()));
+        assert_eq!(profile.share_credentials_version, 2);
+        assert_eq!(profile.expiration_time, "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_profile_config_deserialize() {
+        let config =
+            serde_json::from_str::<ProfileConfig>(r#"{"endpoint": "https://sharing.example.com"}"#)
+                .unwrap();
+        assert_eq!(config, ProfileConfig::new("https://sharing.example.com"));
+
+        let config = serde_json::from_str::<ProfileConfig>(
+            r#"{"endpoint": "https://sharing.example.com", "shareCredentialsVersion": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(config.share_credentials_version, 2);
+    }
+}
@@ -0,0 +1,198 @@
+//! Catalogs listing one table per record, e.g. one JSON object per line:
+//!
+//! ```json
+//! {"share": "share1", "schema": "schema1", "table": "table1", "location": "s3://bucket/table1"}
+//! {"share": "share1", "schema": "schema1", "table": "table2", "location": "s3://bucket/table2"}
+//! ```
+//!
+//! Adding or removing a table only touches its own line, which keeps diffs of the catalog
+//! small. The records are grouped into the shares, schemas and tables of an
+//! [`InMemoryConfig`], in the order they first appear.
+//!
+//! Schema and table names may be reused in several shares. Definitions differing from the
+//! first one with the same name are namespaced by the fully qualified name of the schema or
+//! table, see [`SchemaConfig`](crate::SchemaConfig) and [`TableConfig`]. A table of a schema
+//! may only be listed more than once if all its records are identical.
+
+use crate::error::Result;
+use crate::in_memory::{InMemoryConfig, ShareConfig, TableConfig};
+use crate::reference::ConfigBuilder;
+
+/// Table of a catalog, together with the share and schema it is shared in.
+#[derive(Debug)]
+pub struct TableRecord {
+    pub share: String,
+    pub schema: String,
+    pub table: TableConfig,
+}
+
+/// Values of `key` in `entries`, which are added if the key is missing.
+// NOTE: A vec is used instead of a map to keep the order in which keys first appear.
+fn entry<K: PartialEq, V: Default>(entries: &mut Vec<(K, V)>, key: K) -> &mut V {
+    let index = match entries.iter().position(|(k, _)| *k == key) {
+        Some(index) => index,
+        None => {
+            entries.push((key, V::default()));
+            entries.len() - 1
+        }
+    };
+    &mut entries[index].1
+}
+
+impl InMemoryConfig {
+    /// Group `records` into the shares, schemas and tables they belong to.
+    pub fn from_records(records: impl IntoIterator<Item = TableRecord>) -> Result<Self> {
+        let mut builder = ConfigBuilder::default();
+        let mut shares = Vec::<(String, Vec<String>)>::new();
+        let mut schemas = Vec::<((String, String), Vec<String>)>::new();
+        for record in records {
+            let schema_refs = entry(&mut shares, record.share.clone());
+            if !schema_refs.contains(&record.schema) {
+                schema_refs.push(record.schema.clone());
+            }
+            let table_refs = entry(&mut schemas, (record.share.clone(), record.schema.clone()));
+            if !table_refs.contains(&record.table.name) {
+                table_refs.push(record.table.name.clone());
+            }
+            builder.table(&record.share, &record.schema, record.table)?;
+        }
+        for ((share, schema), table_refs) in schemas {
+            builder.schema(&share, &schema, table_refs)?;
+        }
+        for (name, schema_refs) in shares {
+            builder.share(ShareConfig::new(name, schema_refs));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types as t;
+    use crate::{DefaultInMemoryHandler, DiscoveryHandler, TableLocationResover};
+
+    fn record(share: &str, schema: &str, table: &str) -> TableRecord {
+        TableRecord {
+            share: share.to_string(),
+            schema: schema.to_string(),
+            table: TableConfig::new(table, format!("s3://bucket/{}", table)),
+        }
+    }
+
+    #[test]
+    fn test_from_records() {
+        let config = InMemoryConfig::from_records([
+            record("share1", "schema1", "table1"),
+            record("share2", "schema2", "table3"),
+            record("share1", "schema1", "table2"),
+            record("share1", "schema2", "table3"),
+        ])
+        .unwrap();
+        config.validate().unwrap();
+
+        let shares = config
+            .shares
+            .iter()
+            .map(|share| (share.name.as_str(), share.schema_refs.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shares,
+            vec![
+                ("share1", vec!["schema1".to_string(), "schema2".to_string()]),
+                ("share2", vec!["schema2".to_string()]),
+            ]
+        );
+        let schemas = config
+            .schemas
+            .iter()
+            .map(|schema| (schema.name.as_str(), schema.table_refs.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            schemas,
+            vec![
+                ("schema1", vec!["table1".to_string(), "table2".to_string()]),
+                ("schema2", vec!["table3".to_string()]),
+            ]
+        );
+        let tables = config
+            .tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(tables, vec!["table1", "table3", "table2"]);
+    }
+
+    #[test]
+    fn test_from_records_conflicts() {
+        let mut moved = record("share1", "schema1", "table1");
+        moved.table.location = "s3://bucket/moved".to_string();
+        let result = InMemoryConfig::from_records([record("share1", "schema1", "table1"), moved]);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("table 'share1.schema1.table1'"));
+    }
+
+    #[tokio::test]
+    async fn test_from_records_reused_names() {
+        let mut moved = record("share2", "schema1", "table1");
+        moved.table.location = "s3://bucket/moved".to_string();
+        let config = InMemoryConfig::from_records([
+            record("share1", "schema1", "table1"),
+            record("share1", "schema1", "table2"),
+            moved,
+            record("share3", "schema1", "table3"),
+        ])
+        .unwrap();
+        config.validate().unwrap();
+        assert!(config.lint().is_empty(), "{:?}", config.lint());
+        let handler = DefaultInMemoryHandler::new(config);
+
+        // each share lists its own tables of schema1 under their plain names.
+        for (share, tables) in [
+            ("share1", vec!["table1", "table2"]),
+            ("share2", vec!["table1"]),
+            ("share3", vec!["table3"]),
+        ] {
+            let schemas = handler
+                .list_schemas(t::ListSchemasRequest {
+                    share: share.to_string(),
+                    max_results: None,
+                    page_token: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(schemas.items.len(), 1);
+            assert_eq!(schemas.items[0].name, "schema1");
+            let listed = handler
+                .list_schema_tables(t::ListSchemaTablesRequest {
+                    share: share.to_string(),
+                    schema: "schema1".to_string(),
+                    max_results: None,
+                    page_token: None,
+                })
+                .await
+                .unwrap();
+            let names = listed
+                .items
+                .iter()
+                .map(|table| table.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, tables);
+        }
+
+        for (share, location) in [
+            ("share1", "s3://bucket/table1"),
+            ("share2", "s3://bucket/moved"),
+        ] {
+            let table_ref = t::TableRef {
+                share: share.to_string(),
+                schema: "schema1".to_string(),
+                table: "table1".to_string(),
+            };
+            let url = handler.resolve(&table_ref).await.unwrap();
+            assert_eq!(url.as_str(), location);
+        }
+    }
+}
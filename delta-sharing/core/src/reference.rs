@@ -0,0 +1,417 @@
+//! Configuration files of the reference Delta Sharing server.
+//!
+//! The reference server nests schemas within shares and tables within schemas:
+//!
+//! ```yaml
+//! version: 1
+//! shares:
+//!   - name: share1
+//!     schemas:
+//!       - name: schema1
+//!         tables:
+//!           - name: table1
+//!             location: s3a://bucket/table1
+//!             id: 00000000-0000-0000-0000-000000000000
+//! authorization:
+//!   bearerToken: token
+//! port: 8080
+//! ```
+//!
+//! A [`ReferenceServerConfig`] converts into an [`InMemoryConfig`]. The `name` of shares,
+//! schemas and tables, the `location` of tables and their optional `id` are supported.
+//! Table settings without an equivalent (`historyShared`, `startVersion`, `cdfEnabled`) and
+//! the server settings (`version`, `authorization`, `ssl`, `host`, `port`, `endpoint`,
+//! `preSignedUrlTimeoutSeconds`, `deltaTableCacheSize`, `stalenessAcceptable`,
+//! `evaluatePredicateHints`, `evaluateJsonPredicateHints`, `evaluateJsonPredicateV2`,
+//! `requestTimeoutSeconds`, `queryTablePageSizeLimit`, `queryTablePageTokenTtlMs` and
+//! `refreshTokenTtlMs`) are accepted and ignored.
+//!
+//! Like in an [`InMemoryConfig`], table settings are also accepted in snake_case, e.g.
+//! `cdf_enabled`, and the `location` of a table as `storage_location`.
+//!
+//! Schemas and tables reused in several shares share a single config if all their
+//! definitions are identical. Otherwise the differing definitions are namespaced by the fully
+//! qualified name of the schema or table, see [`SchemaConfig`] and [`TableConfig`].
+
+use std::collections::HashMap;
+
+use serde::de::{Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::in_memory::{InMemoryConfig, SchemaConfig, ShareConfig, TableConfig};
+use crate::types as t;
+
+/// Setting of the reference server without an equivalent, which is accepted and dropped.
+///
+/// Unlike [`serde::de::IgnoredAny`], the value is consumed as a known field, so it is not
+/// reported when unknown fields of the configuration are tracked.
+#[derive(Debug)]
+struct Unused;
+
+impl<'de> Deserialize<'de> for Unused {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(UnusedVisitor)
+    }
+}
+
+struct UnusedVisitor;
+
+impl<'de> Visitor<'de> for UnusedVisitor {
+    type Value = Unused;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_none<E>(self) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Unused, D::Error> {
+        Unused::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Unused, E> {
+        Ok(Unused)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Unused, A::Error> {
+        while seq.next_element::<Unused>()?.is_some() {}
+        Ok(Unused)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Unused, A::Error> {
+        while map.next_entry::<Unused, Unused>()?.is_some() {}
+        Ok(Unused)
+    }
+}
+
+/// Table in the configuration of the reference server.
+// NOTE: Settings without an equivalent are only deserialized to accept them.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceTableConfig {
+    pub name: String,
+    #[serde(alias = "storage_location", alias = "storageLocation")]
+    pub location: String,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default, alias = "history_shared")]
+    history_shared: Option<Unused>,
+    #[serde(default, alias = "start_version")]
+    start_version: Option<Unused>,
+    #[serde(default, alias = "cdf_enabled")]
+    cdf_enabled: Option<Unused>,
+}
+
+/// Schema in the configuration of the reference server.
+#[derive(Debug, Deserialize)]
+pub struct ReferenceSchemaConfig {
+    pub name: String,
+    #[serde(default)]
+    pub tables: Vec<ReferenceTableConfig>,
+}
+
+/// Share in the configuration of the reference server.
+#[derive(Debug, Deserialize)]
+pub struct ReferenceShareConfig {
+    pub name: String,
+    #[serde(default)]
+    pub schemas: Vec<ReferenceSchemaConfig>,
+}
+
+/// Configuration file of the reference Delta Sharing server.
+// NOTE: Server settings are only deserialized to accept them.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceServerConfig {
+    #[serde(default)]
+    pub shares: Vec<ReferenceShareConfig>,
+    #[serde(default)]
+    version: Option<Unused>,
+    #[serde(default)]
+    authorization: Option<Unused>,
+    #[serde(default)]
+    ssl: Option<Unused>,
+    #[serde(default)]
+    host: Option<Unused>,
+    #[serde(default)]
+    port: Option<Unused>,
+    #[serde(default)]
+    endpoint: Option<Unused>,
+    #[serde(default)]
+    pre_signed_url_timeout_seconds: Option<Unused>,
+    #[serde(default)]
+    delta_table_cache_size: Option<Unused>,
+    #[serde(default)]
+    staleness_acceptable: Option<Unused>,
+    #[serde(default)]
+    evaluate_predicate_hints: Option<Unused>,
+    #[serde(default)]
+    evaluate_json_predicate_hints: Option<Unused>,
+    #[serde(default)]
+    evaluate_json_predicate_v2: Option<Unused>,
+    #[serde(default)]
+    request_timeout_seconds: Option<Unused>,
+    #[serde(default)]
+    query_table_page_size_limit: Option<Unused>,
+    #[serde(default)]
+    query_table_page_token_ttl_ms: Option<Unused>,
+    #[serde(default)]
+    refresh_token_ttl_ms: Option<Unused>,
+}
+
+pub(crate) fn conflict(kind: &str, name: &str) -> Error {
+    Error::invalid_config(
+        format!("{} '{}' is defined more than once", kind, name),
+        format!("the definitions of {} '{}' differ", kind, name),
+    )
+}
+
+/// Builder of the [`InMemoryConfig`] of a catalog defined share by share.
+///
+/// A schema or table name is used for all definitions identical to the first one with that
+/// name. Differing definitions are namespaced by the fully qualified name of the schema or
+/// table, so names can be reused across shares. Only defining the same schema or table of a
+/// share twice with differing definitions is a conflict.
+#[derive(Default)]
+pub(crate) struct ConfigBuilder {
+    config: InMemoryConfig,
+    // Definitions by the names of the configs.
+    schemas: HashMap<String, Vec<String>>,
+    tables: HashMap<String, TableConfig>,
+    // Definitions by the fully qualified names of the schemas and tables.
+    qualified_schemas: HashMap<String, Vec<String>>,
+    qualified_tables: HashMap<String, TableConfig>,
+}
+
+impl ConfigBuilder {
+    /// Add `table` to `schema` of `share`.
+    pub(crate) fn table(
+        &mut self,
+        share: &str,
+        schema: &str,
+        mut table: TableConfig,
+    ) -> Result<()> {
+        let qualified = t::table_fqn(share, schema, &table.name);
+        match self.qualified_tables.get(&qualified) {
+            Some(existing) if *existing != table => return Err(conflict("table", &qualified)),
+            Some(_) => return Ok(()),
+            None => self
+                .qualified_tables
+                .insert(qualified.clone(), table.clone()),
+        };
+        match self.tables.get(&table.name) {
+            Some(existing) if *existing == table => {}
+            Some(_) => {
+                table.name = qualified;
+                self.config.tables.push(table);
+            }
+            None => {
+                self.tables.insert(table.name.clone(), table.clone());
+                self.config.tables.push(table);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add `schema` containing the tables `table_refs` to `share`.
+    pub(crate) fn schema(
+        &mut self,
+        share: &str,
+        schema: &str,
+        table_refs: Vec<String>,
+    ) -> Result<()> {
+        let qualified = t::schema_fqn(share, schema);
+        match self.qualified_schemas.get(&qualified) {
+            Some(existing) if *existing != table_refs => {
+                return Err(conflict("schema", &qualified))
+            }
+            Some(_) => return Ok(()),
+            None => self
+                .qualified_schemas
+                .insert(qualified.clone(), table_refs.clone()),
+        };
+        let name = match self.schemas.get(schema) {
+            Some(existing) if *existing == table_refs => return Ok(()),
+            Some(_) => qualified,
+            None => {
+                self.schemas.insert(schema.to_string(), table_refs.clone());
+                schema.to_string()
+            }
+        };
+        self.config
+            .schemas
+            .push(SchemaConfig::new(name, table_refs));
+        Ok(())
+    }
+
+    /// Add `share`, whose schemas must be added with [`ConfigBuilder::schema`].
+    pub(crate) fn share(&mut self, share: ShareConfig) {
+        self.config.shares.push(share);
+    }
+
+    pub(crate) fn build(self) -> InMemoryConfig {
+        self.config
+    }
+}
+
+impl TryFrom<ReferenceServerConfig> for InMemoryConfig {
+    type Error = Error;
+
+    fn try_from(reference: ReferenceServerConfig) -> Result<Self> {
+        let mut builder = ConfigBuilder::default();
+        for share in reference.shares {
+            let mut schema_refs = Vec::with_capacity(share.schemas.len());
+            for schema in share.schemas {
+                let mut table_refs = Vec::with_capacity(schema.tables.len());
+                for table in schema.tables {
+                    let mut config = TableConfig::new(table.name.clone(), table.location);
+                    config.id = table.id;
+                    builder.table(&share.name, &schema.name, config)?;
+                    table_refs.push(table.name);
+                }
+                builder.schema(&share.name, &schema.name, table_refs)?;
+                schema_refs.push(schema.name);
+            }
+            builder.share(ShareConfig::new(share.name, schema_refs));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(value: serde_json::Value) -> Result<InMemoryConfig> {
+        serde_json::from_value::<ReferenceServerConfig>(value)
+            .unwrap()
+            .try_into()
+    }
+
+    #[test]
+    fn test_reference_config() {
+        let config = reference(serde_json::json!({
+            "version": 1,
+            "shares": [
+                {
+                    "name": "share1",
+                    "schemas": [{
+                        "name": "schema1",
+                        "tables": [
+                            {"name": "table1", "location": "s3a://bucket/table1", "id": "id1"},
+                            {"name": "table2", "location": "s3a://bucket/table2", "historyShared": true}
+                        ]
+                    }]
+                },
+                {
+                    "name": "share2",
+                    "schemas": [{
+                        "name": "schema1",
+                        "tables": [
+                            {"name": "table1", "location": "s3a://bucket/table1", "id": "id1"},
+                            {"name": "table2", "location": "s3a://bucket/table2"}
+                        ]
+                    }]
+                }
+            ],
+            "authorization": {"bearerToken": "token"},
+            "port": 8080
+        }))
+        .unwrap();
+
+        assert_eq!(config.shares.len(), 2);
+        assert_eq!(config.shares[1].schema_refs, vec!["schema1"]);
+        assert_eq!(config.schemas.len(), 1);
+        assert_eq!(config.schemas[0].table_refs, vec!["table1", "table2"]);
+        assert_eq!(config.tables.len(), 2);
+        assert_eq!(config.tables[0].location, "s3a://bucket/table1");
+        assert_eq!(config.tables[0].id.as_deref(), Some("id1"));
+        assert_eq!(config.tables[1].id, None);
+    }
+
+    #[test]
+    fn test_reference_config_reused_names() {
+        let share = |name: &str, table: &str, location: &str| {
+            serde_json::json!({
+                "name": name,
+                "schemas": [{"name": "schema1", "tables": [{"name": table, "location": location}]}]
+            })
+        };
+
+        // the second definition of table1 is namespaced by its share.
+        let config = reference(serde_json::json!({
+            "shares": [share("share1", "table1", "s3a://a"), share("share2", "table1", "s3a://b")]
+        }))
+        .unwrap();
+        assert_eq!(config.schemas.len(), 1);
+        let tables = config
+            .tables
+            .iter()
+            .map(|table| (table.name.as_str(), table.location.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            [("table1", "s3a://a"), ("share2.schema1.table1", "s3a://b")]
+        );
+
+        // so is the second definition of schema1.
+        let config = reference(serde_json::json!({
+            "shares": [share("share1", "table1", "s3a://a"), share("share2", "table2", "s3a://b")]
+        }))
+        .unwrap();
+        let schemas = config
+            .schemas
+            .iter()
+            .map(|schema| (schema.name.as_str(), schema.table_refs.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            schemas,
+            [
+                ("schema1", vec!["table1".to_string()]),
+                ("share2.schema1", vec!["table2".to_string()])
+            ]
+        );
+        assert_eq!(config.shares[1].schema_refs, vec!["schema1"]);
+    }
+
+    #[test]
+    fn test_reference_config_conflicts() {
+        let result = reference(serde_json::json!({
+            "shares": [{
+                "name": "share1",
+                "schemas": [
+                    {"name": "schema1", "tables": [{"name": "table1", "location": "s3a://a"}]},
+                    {"name": "schema1", "tables": [{"name": "table2", "location": "s3a://b"}]}
+                ]
+            }]
+        }));
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { message, .. }) if message.contains("schema 'share1.schema1'"))
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! Retries for discovery handlers backed by remote services.
+//!
+//! The [`RetryingHandler`] wraps another [`DiscoveryHandler`] and retries requests that fail
+//! with transient errors, using exponential backoff with jitter between attempts.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::tree::CatalogTree;
+use crate::types as t;
+use crate::{DiscoveryHandler, Page, Pagination};
+
+/// Configuration for retrying failed requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: usize,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the backoff between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Backoff before the given retry, with up to 50% random jitter removed.
+    fn backoff(&self, retry: usize) -> Duration {
+        let exponent = u32::try_from(retry).unwrap_or(u32::MAX).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff);
+        let jitter = RandomState::new().build_hasher().finish() % 1000;
+        backoff.saturating_sub(backoff.mul_f64(jitter as f64 / 2000.0))
+    }
+}
+
+/// Returns true if a request failing with `error` may succeed when retried.
+fn is_transient(error: &Error) -> bool {
+    matches!(error, Error::Unavailable(_) | Error::Generic(_))
+}
+
+/// Discovery handler that retries requests failing with transient errors.
+///
+/// Errors like [`Error::NotFound`] or [`Error::NotAllowed`] are returned immediately.
+pub struct RetryingHandler<H> {
+    handler: H,
+    config: RetryConfig,
+}
+
+impl<H> RetryingHandler<H> {
+    /// Create a new instance of [`RetryingHandler`] wrapping `handler`.
+    pub fn new(handler: H, config: RetryConfig) -> Self {
+        Self { handler, config }
+    }
+
+    async fn retry<F, Fut, R>(&self, mut f: F) -> Result<R>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
+        R: Send,
+    {
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Err(error) if is_transient(&error) && attempt < self.config.max_attempts => {
+                    let backoff = self.config.backoff(attempt - 1);
+                    tracing::warn!(
+                        "request failed (attempt {}), retrying in {:?}: {}",
+                        attempt,
+                        backoff,
+                        error
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<H> DiscoveryHandler for RetryingHandler<H>
+where
+    H: DiscoveryHandler,
+    H::Recipient: Clone + Sync,
+{
+    type Recipient = H::Recipient;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        self.retry(|| self.handler.list_shares(request.clone(), recipient.clone()))
+            .await
+    }
+
+    async fn list_share_names(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        self.retry(|| {
+            self.handler
+                .list_share_names(request.clone(), recipient.clone())
+        })
+        .await
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        self.retry(|| self.handler.get_share(request.clone())).await
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        self.retry(|| self.handler.list_schemas(request.clone()))
+            .await
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        self.retry(|| self.handler.list_schema_tables(request.clone()))
+            .await
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        self.retry(|| self.handler.list_share_tables(request.clone()))
+            .await
+    }
+
+    async fn get_table_ref(
+        &self,
+        table_ref: &t::TableRef,
+        recipient: Self::Recipient,
+    ) -> Result<t::Table>
+    where
+        Self::Recipient: Clone,
+    {
+        self.retry(|| self.handler.get_table_ref(table_ref, recipient.clone()))
+            .await
+    }
+
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        self.retry(|| {
+            self.handler
+                .search_tables(query, recipient.clone(), pagination.clone())
+        })
+        .await
+    }
+
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        self.retry(|| self.handler.describe(recipient.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Handler that fails `get_share` and `list_shares` with the given error a number of times
+    /// before succeeding, and answers all other requests with empty responses.
+    struct FlakyHandler {
+        failures: usize,
+        error: fn() -> Error,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyHandler {
+        fn new(failures: usize, error: fn() -> Error) -> Self {
+            Self {
+                failures,
+                error,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn attempt(&self) -> Result<usize> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err((self.error)());
+            }
+            Ok(call)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DiscoveryHandler for FlakyHandler {
+        type Recipient = ();
+
+        async fn list_shares(
+            &self,
+            _: t::ListSharesRequest,
+            _: Self::Recipient,
+        ) -> Result<t::ListSharesResponse> {
+            self.attempt()?;
+            Ok(t::ListSharesResponse::default())
+        }
+
+        async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+            let call = self.attempt()?;
+            Ok(t::GetShareResponse {
+                share: Some(t::Share {
+                    id: None,
+                    name: format!("{}-{}", request.share, call),
+                }),
+            })
+        }
+
+        async fn list_schemas(&self, _: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+            Ok(t::ListSchemasResponse::default())
+        }
+
+        async fn list_schema_tables(
+            &self,
+            _: t::ListSchemaTablesRequest,
+        ) -> Result<t::ListSchemaTablesResponse> {
+            Ok(t::ListSchemaTablesResponse::default())
+        }
+
+        async fn list_share_tables(
+            &self,
+            _: t::ListShareTablesRequest,
+        ) -> Result<t::ListShareTablesResponse> {
+            Ok(t::ListShareTablesResponse::default())
+        }
+    }
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    fn request() -> t::GetShareRequest {
+        t::GetShareRequest {
+            share: "share".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let handler = RetryingHandler::new(
+            FlakyHandler::new(2, || Error::Unavailable("timeout".to_string())),
+            config(),
+        );
+        let response = handler.get_share(request()).await.unwrap();
+        assert_eq!(response.share.unwrap().name, "share-2");
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let handler = RetryingHandler::new(
+            FlakyHandler::new(3, || Error::Unavailable("timeout".to_string())),
+            config(),
+        );
+        let err = handler.get_share(request()).await.unwrap_err();
+        assert!(matches!(err, Error::Unavailable(_)));
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_errors() {
+        let handler = RetryingHandler::new(FlakyHandler::new(1, || Error::NotAllowed), config());
+        let err = handler.get_share(request()).await.unwrap_err();
+        assert!(matches!(err, Error::NotAllowed));
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_provided_methods() {
+        let unavailable = || Error::Unavailable("timeout".to_string());
+        let handler = RetryingHandler::new(FlakyHandler::new(2, unavailable), config());
+        let names = handler
+            .list_share_names(t::ListSharesRequest::default(), ())
+            .await
+            .unwrap();
+        assert!(names.items.is_empty());
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 3);
+
+        let handler = RetryingHandler::new(FlakyHandler::new(2, unavailable), config());
+        let tree = handler.describe(()).await.unwrap();
+        assert!(tree.shares.is_empty());
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 3);
+
+        let handler = RetryingHandler::new(FlakyHandler::new(2, unavailable), config());
+        let page = handler
+            .search_tables("table", (), Pagination::default())
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 3);
+
+        let handler = RetryingHandler::new(FlakyHandler::new(1, || Error::NotAllowed), config());
+        let table_ref = t::TableRef {
+            share: "share".to_string(),
+            schema: "schema".to_string(),
+            table: "table".to_string(),
+        };
+        let err = handler.get_table_ref(&table_ref, ()).await.unwrap_err();
+        assert!(matches!(err, Error::NotAllowed));
+        assert_eq!(handler.handler.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_is_bounded() {
+        let config = RetryConfig {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        for retry in 0..10 {
+            let backoff = config.backoff(retry);
+            assert!(backoff <= Duration::from_secs(1));
+            assert!(backoff >= Duration::from_millis(50));
+        }
+    }
+}
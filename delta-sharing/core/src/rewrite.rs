@@ -0,0 +1,181 @@
+//! Rewriting of table locations.
+//!
+//! The [`LocationRewriteResolver`] wraps another [`TableLocationResover`] and rewrites the
+//! resolved locations with an ordered list of [`RewriteRule`]s. This allows moving tables
+//! to a different storage backend, e.g. `s3://old-bucket/...` to `s3://new-bucket/...`,
+//! without changing the location of every single table.
+
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::types::TableRef;
+use crate::TableLocationResover;
+
+/// Rule rewriting table locations.
+#[derive(Debug, Clone)]
+pub enum RewriteRule {
+    /// Replace the leading `prefix` of a location with `replacement`.
+    Prefix { prefix: String, replacement: String },
+    /// Replace the first match of `pattern` in a location with `replacement`.
+    ///
+    /// The replacement may refer to capture groups, e.g. `$1` or `${name}`.
+    Regex { pattern: Regex, replacement: String },
+}
+
+impl RewriteRule {
+    /// Create a rule replacing the leading `prefix` of a location with `replacement`.
+    pub fn prefix(prefix: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self::Prefix {
+            prefix: prefix.into(),
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Create a rule replacing the first match of `pattern` with `replacement`.
+    ///
+    /// Fails with [`Error::InvalidConfig`] if `pattern` is not a valid regular expression.
+    pub fn regex(pattern: &str, replacement: impl Into<String>) -> Result<Self> {
+        let pattern = Regex::new(pattern).map_err(|e| {
+            Error::invalid_config(format!("invalid location pattern '{}'", pattern), e)
+        })?;
+        Ok(Self::Regex {
+            pattern,
+            replacement: replacement.into(),
+        })
+    }
+
+    /// Rewrite `location`, or return `None` if the rule does not match.
+    fn apply(&self, location: &str) -> Option<String> {
+        match self {
+            Self::Prefix {
+                prefix,
+                replacement,
+            } => location
+                .strip_prefix(prefix.as_str())
+                .map(|rest| format!("{}{}", replacement, rest)),
+            Self::Regex {
+                pattern,
+                replacement,
+            } => pattern
+                .is_match(location)
+                .then(|| pattern.replace(location, replacement.as_str()).into_owned()),
+        }
+    }
+}
+
+/// Resolver that rewrites the locations returned by another resolver.
+///
+/// Rules are tried in order and only the first matching rule is applied. Locations
+/// not matched by any rule are returned unchanged.
+pub struct LocationRewriteResolver {
+    resolver: Arc<dyn TableLocationResover>,
+    rules: Vec<RewriteRule>,
+}
+
+impl LocationRewriteResolver {
+    /// Create a new instance of [`LocationRewriteResolver`] wrapping `resolver`.
+    pub fn new(resolver: Arc<dyn TableLocationResover>, rules: Vec<RewriteRule>) -> Self {
+        Self { resolver, rules }
+    }
+
+    /// Rewrite `location` with the first matching rule.
+    pub fn rewrite(&self, location: &str) -> Option<String> {
+        self.rules.iter().find_map(|rule| rule.apply(location))
+    }
+
+    fn rewrite_url(&self, location: url::Url) -> Result<url::Url> {
+        match self.rewrite(location.as_str()) {
+            Some(rewritten) => {
+                url::Url::parse(&rewritten).map_err(|_| Error::InvalidTableLocation(rewritten))
+            }
+            None => Ok(location),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TableLocationResover for LocationRewriteResolver {
+    async fn resolve(&self, table: &TableRef) -> Result<url::Url> {
+        let location = self.resolver.resolve(table).await?;
+        self.rewrite_url(location)
+    }
+
+    async fn resolve_all(&self, tables: &[TableRef]) -> Vec<Result<url::Url>> {
+        self.resolver
+            .resolve_all(tables)
+            .await
+            .into_iter()
+            .map(|location| self.rewrite_url(location?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolver using the table name as location.
+    struct NameResolver;
+
+    #[async_trait::async_trait]
+    impl TableLocationResover for NameResolver {
+        async fn resolve(&self, table: &TableRef) -> Result<url::Url> {
+            url::Url::parse(&table.table)
+                .map_err(|_| Error::InvalidTableLocation(table.table.clone()))
+        }
+    }
+
+    async fn resolve(resolver: &LocationRewriteResolver, location: &str) -> Result<String> {
+        let table = TableRef {
+            share: "share".to_string(),
+            schema: "schema".to_string(),
+            table: location.to_string(),
+        };
+        Ok(resolver.resolve(&table).await?.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_prefix() {
+        let resolver = LocationRewriteResolver::new(
+            Arc::new(NameResolver),
+            vec![RewriteRule::prefix("s3://old-bucket/", "s3://new-bucket/")],
+        );
+
+        let location = resolve(&resolver, "s3://old-bucket/sales/orders")
+            .await
+            .unwrap();
+        assert_eq!(location, "s3://new-bucket/sales/orders");
+
+        // non-matching locations pass through unchanged.
+        let location = resolve(&resolver, "s3://other-bucket/old-bucket/orders")
+            .await
+            .unwrap();
+        assert_eq!(location, "s3://other-bucket/old-bucket/orders");
+        let location = resolve(&resolver, "file:///tmp/orders").await.unwrap();
+        assert_eq!(location, "file:///tmp/orders");
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_first_matching_rule() {
+        let resolver = LocationRewriteResolver::new(
+            Arc::new(NameResolver),
+            vec![
+                RewriteRule::regex(r"^s3://([a-z-]+)-eu/", "s3://$1-us/").unwrap(),
+                RewriteRule::prefix("s3://", "gs://"),
+            ],
+        );
+
+        let location = resolve(&resolver, "s3://sales-eu/orders").await.unwrap();
+        assert_eq!(location, "s3://sales-us/orders");
+        let location = resolve(&resolver, "s3://sales/orders").await.unwrap();
+        assert_eq!(location, "gs://sales/orders");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let result = RewriteRule::regex("s3://(", "s3://");
+        assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+    }
+}
@@ -0,0 +1,165 @@
+//! Cheaply cloneable handles to a [`DiscoveryHandler`] behind dynamic dispatch.
+//!
+//! The [`SharedHandler`] wraps a handler in an [`Arc`], so the same handler can be held by
+//! multiple endpoints and tasks without boxing or wrapping it by hand.
+
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::tree::CatalogTree;
+use crate::types as t;
+use crate::{DiscoveryHandler, Page, Pagination};
+
+/// Shared [`DiscoveryHandler`], delegating all requests to the wrapped handler.
+pub struct SharedHandler<R> {
+    inner: Arc<dyn DiscoveryHandler<Recipient = R>>,
+}
+
+impl<R: Send> SharedHandler<R> {
+    /// Create a new [`SharedHandler`] wrapping `inner`.
+    pub fn new<H>(inner: H) -> Self
+    where
+        H: DiscoveryHandler<Recipient = R> + 'static,
+    {
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Returns the wrapped handler.
+    pub fn inner(&self) -> &Arc<dyn DiscoveryHandler<Recipient = R>> {
+        &self.inner
+    }
+}
+
+// NOTE: A blanket `From<H: DiscoveryHandler>` would conflict with `From<T> for T`, as the
+// shared handler is a discovery handler itself. Use [`SharedHandler::new`] instead.
+impl<R> From<Arc<dyn DiscoveryHandler<Recipient = R>>> for SharedHandler<R> {
+    fn from(inner: Arc<dyn DiscoveryHandler<Recipient = R>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R> Clone for SharedHandler<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<R: Send + Sync> DiscoveryHandler for SharedHandler<R> {
+    type Recipient = R;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        self.inner.list_shares(request, recipient).await
+    }
+
+    async fn list_share_names(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        self.inner.list_share_names(request, recipient).await
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        self.inner.get_share(request).await
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        self.inner.list_schemas(request).await
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        self.inner.list_schema_tables(request).await
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        self.inner.list_share_tables(request).await
+    }
+
+    async fn get_table_ref(
+        &self,
+        table_ref: &t::TableRef,
+        recipient: Self::Recipient,
+    ) -> Result<t::Table>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.get_table_ref(table_ref, recipient).await
+    }
+
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.search_tables(query, recipient, pagination).await
+    }
+
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        self.inner.describe(recipient).await
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+    use crate::{InMemoryConfig, InMemoryHandler, SchemaConfig, ShareConfig, TableConfig};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[tokio::test]
+    async fn test_shared_handler() {
+        assert_send_sync::<SharedHandler<String>>();
+
+        let mut config = [ShareConfig::new("share1", ["schema1"])]
+            .into_iter()
+            .collect::<InMemoryConfig>();
+        config
+            .schemas
+            .push(SchemaConfig::new("schema1", ["table1"]));
+        config
+            .tables
+            .push(TableConfig::new("table1", "s3://bucket/table1"));
+        let handler = SharedHandler::new(InMemoryHandler::<String>::new(config));
+        let cloned = handler.clone();
+
+        let (shares, tables) = tokio::join!(
+            tokio::spawn(async move {
+                handler
+                    .list_shares(t::ListSharesRequest::default(), "alice".to_string())
+                    .await
+            }),
+            tokio::spawn(async move {
+                let table_ref = t::TableRef {
+                    share: "share1".to_string(),
+                    schema: "schema1".to_string(),
+                    table: "table1".to_string(),
+                };
+                cloned.get_table_ref(&table_ref, "bob".to_string()).await
+            }),
+        );
+        assert_eq!(shares.unwrap().unwrap().items[0].name, "share1");
+        assert_eq!(tables.unwrap().unwrap().name, "table1");
+    }
+}
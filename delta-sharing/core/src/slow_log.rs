@@ -0,0 +1,286 @@
+//! Logging of slow discovery requests, e.g. to find pathological configurations.
+//!
+//! The [`SlowLogHandler`] times every request to the wrapped [`DiscoveryHandler`] and emits
+//! a warning for requests taking longer than a threshold. The arguments of a request are
+//! only formatted for the warning, so requests below the threshold add little overhead.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::tree::CatalogTree;
+use crate::types as t;
+use crate::{DiscoveryHandler, Page, Pagination};
+
+/// Handler that logs requests taking longer than a threshold.
+pub struct SlowLogHandler<H> {
+    inner: H,
+    threshold: Duration,
+}
+
+impl<H> SlowLogHandler<H> {
+    /// Create a new [`SlowLogHandler`] logging requests to `inner` slower than `threshold`.
+    pub fn new(inner: H, threshold: Duration) -> Self {
+        Self { inner, threshold }
+    }
+
+    /// Returns the wrapped handler.
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    async fn timed<F, R>(
+        &self,
+        method: &str,
+        args: impl FnOnce() -> String,
+        request: F,
+    ) -> Result<R>
+    where
+        F: Future<Output = Result<R>>,
+    {
+        let start = Instant::now();
+        let result = request.await;
+        let elapsed = start.elapsed();
+        if elapsed > self.threshold {
+            tracing::warn!(
+                "slow request {}({}) took {:?}, exceeding {:?}",
+                method,
+                args(),
+                elapsed,
+                self.threshold
+            );
+        }
+        result
+    }
+}
+
+fn page_args(max_results: Option<i32>, has_page_token: bool) -> String {
+    format!(
+        "max_results={:?}, page_token={}",
+        max_results, has_page_token
+    )
+}
+
+#[async_trait::async_trait]
+impl<H> DiscoveryHandler for SlowLogHandler<H>
+where
+    H: DiscoveryHandler,
+{
+    type Recipient = H::Recipient;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        let (max_results, has_token) = (request.max_results, request.page_token.is_some());
+        let args = move || page_args(max_results, has_token);
+        let response = self.inner.list_shares(request, recipient);
+        self.timed("list_shares", args, response).await
+    }
+
+    async fn list_share_names(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<Page<String>> {
+        let (max_results, has_token) = (request.max_results, request.page_token.is_some());
+        let args = move || page_args(max_results, has_token);
+        let response = self.inner.list_share_names(request, recipient);
+        self.timed("list_share_names", args, response).await
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        let share = request.share.clone();
+        let args = move || format!("share={}", share);
+        self.timed("get_share", args, self.inner.get_share(request))
+            .await
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        let share = request.share.clone();
+        let (max_results, has_token) = (request.max_results, request.page_token.is_some());
+        let args = move || format!("share={}, {}", share, page_args(max_results, has_token));
+        self.timed("list_schemas", args, self.inner.list_schemas(request))
+            .await
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        let (share, schema) = (request.share.clone(), request.schema.clone());
+        let (max_results, has_token) = (request.max_results, request.page_token.is_some());
+        let args = move || {
+            format!(
+                "share={}, schema={}, {}",
+                share,
+                schema,
+                page_args(max_results, has_token)
+            )
+        };
+        let response = self.inner.list_schema_tables(request);
+        self.timed("list_schema_tables", args, response).await
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        let share = request.share.clone();
+        let (max_results, has_token) = (request.max_results, request.page_token.is_some());
+        let args = move || format!("share={}, {}", share, page_args(max_results, has_token));
+        let response = self.inner.list_share_tables(request);
+        self.timed("list_share_tables", args, response).await
+    }
+
+    async fn get_table_ref(
+        &self,
+        table_ref: &t::TableRef,
+        recipient: Self::Recipient,
+    ) -> Result<t::Table>
+    where
+        Self::Recipient: Clone,
+    {
+        let table = table_ref.to_string();
+        let args = move || format!("table={}", table);
+        let response = self.inner.get_table_ref(table_ref, recipient);
+        self.timed("get_table_ref", args, response).await
+    }
+
+    async fn search_tables(
+        &self,
+        query: &str,
+        recipient: Self::Recipient,
+        pagination: Pagination,
+    ) -> Result<Page<t::Table>>
+    where
+        Self::Recipient: Clone,
+    {
+        let query_arg = query.to_string();
+        let (max_results, has_token) =
+            (pagination.max_results(), pagination.page_token().is_some());
+        let args = move || format!("query={}, {}", query_arg, page_args(max_results, has_token));
+        let response = self.inner.search_tables(query, recipient, pagination);
+        self.timed("search_tables", args, response).await
+    }
+
+    async fn describe(&self, recipient: Self::Recipient) -> Result<CatalogTree>
+    where
+        Self::Recipient: Clone,
+    {
+        self.timed("describe", String::new, self.inner.describe(recipient))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    use super::*;
+
+    /// Handler answering every request with an empty response after a delay.
+    struct DelayedHandler {
+        delay: Duration,
+    }
+
+    impl DelayedHandler {
+        async fn respond<R: Default>(&self) -> Result<R> {
+            tokio::time::sleep(self.delay).await;
+            Ok(R::default())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DiscoveryHandler for DelayedHandler {
+        type Recipient = ();
+
+        async fn list_shares(
+            &self,
+            _: t::ListSharesRequest,
+            _: Self::Recipient,
+        ) -> Result<t::ListSharesResponse> {
+            self.respond().await
+        }
+
+        async fn get_share(&self, _: t::GetShareRequest) -> Result<t::GetShareResponse> {
+            self.respond().await
+        }
+
+        async fn list_schemas(&self, _: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+            self.respond().await
+        }
+
+        async fn list_schema_tables(
+            &self,
+            _: t::ListSchemaTablesRequest,
+        ) -> Result<t::ListSchemaTablesResponse> {
+            self.respond().await
+        }
+
+        async fn list_share_tables(
+            &self,
+            _: t::ListShareTablesRequest,
+        ) -> Result<t::ListShareTablesResponse> {
+            self.respond().await
+        }
+    }
+
+    /// Subscriber counting the warnings that are emitted.
+    #[derive(Clone, Default)]
+    struct WarningCounter(Arc<AtomicUsize>);
+
+    impl Subscriber for WarningCounter {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            if *event.metadata().level() == Level::WARN {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    async fn warnings(delay: Duration) -> usize {
+        let counter = WarningCounter::default();
+        let _guard = tracing::subscriber::set_default(counter.clone());
+        let handler = SlowLogHandler::new(DelayedHandler { delay }, Duration::from_millis(20));
+        let request = t::GetShareRequest {
+            share: "share1".to_string(),
+        };
+        handler.get_share(request).await.unwrap();
+        handler
+            .list_shares(t::ListSharesRequest::default(), ())
+            .await
+            .unwrap();
+        counter.0.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_slow_requests_logged() {
+        assert_eq!(warnings(Duration::from_millis(50)).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_fast_requests_not_logged() {
+        assert_eq!(warnings(Duration::ZERO).await, 0);
+    }
+}
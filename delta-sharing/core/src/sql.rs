@@ -0,0 +1,797 @@
+//! Discovery handler backed by a SQL database.
+//!
+//! The [`SqlHandler`] reads shares, schemas and tables from the tables created by
+//! [`SqlHandler::init_schema`]:
+//!
+//! - `shares (id, name)`
+//! - `schemas (share_name, name)`
+//! - `tables (id, share_name, schema_name, name, location)`
+//! - `share_recipients (share_name, recipient)`
+//!
+//! A share is only listed for recipients with a matching row in `share_recipients`, where
+//! `recipient` is the fingerprint of the recipient's profile. Rows with the recipient
+//! [`ALL_RECIPIENTS`] grant access to every recipient, including anonymous ones.
+//!
+//! [`SqlHandler::list_share_recipients`] exposes the grants of a share to admin recipients,
+//! and [`SqlHandler::audit_all_tables`] lists every table regardless of the grants.
+//! Grants written by hand may differ in casing or whitespace from the fingerprints they
+//! refer to, see [`SqlHandler::normalize_recipients`].
+//!
+//! Listings are paginated on their ordering key, so pages stay consistent while rows are
+//! added or removed. Queries use `$N` placeholders, which are supported by the SQLite and
+//! Postgres drivers. The `sqlx` feature only enables the SQLite driver, enable the
+//! `postgres` feature to connect to Postgres. With the `postgres` feature the tests run
+//! against the database at `DATABASE_URL` if it is set.
+
+use serde::{Deserialize, Serialize};
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use crate::error::{Error, Result};
+use crate::pagination::{Pagination, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::profiles::DeltaRecipient;
+use crate::types as t;
+use crate::{DiscoveryHandler, TableLocationResover};
+
+/// Recipient granting access to a share to all recipients.
+pub const ALL_RECIPIENTS: &str = "*";
+
+/// Maximum number of tables returned by [`SqlHandler::audit_all_tables`].
+pub const MAX_AUDIT_TABLES: usize = 100_000;
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS shares (
+        id TEXT NOT NULL,
+        name TEXT NOT NULL PRIMARY KEY
+    )",
+    "CREATE TABLE IF NOT EXISTS schemas (
+        share_name TEXT NOT NULL REFERENCES shares (name),
+        name TEXT NOT NULL,
+        PRIMARY KEY (share_name, name)
+    )",
+    "CREATE TABLE IF NOT EXISTS tables (
+        id TEXT NOT NULL,
+        share_name TEXT NOT NULL,
+        schema_name TEXT NOT NULL,
+        name TEXT NOT NULL,
+        location TEXT NOT NULL,
+        PRIMARY KEY (share_name, schema_name, name),
+        FOREIGN KEY (share_name, schema_name) REFERENCES schemas (share_name, name)
+    )",
+    "CREATE TABLE IF NOT EXISTS share_recipients (
+        share_name TEXT NOT NULL REFERENCES shares (name),
+        recipient TEXT NOT NULL,
+        PRIMARY KEY (share_name, recipient)
+    )",
+];
+
+/// Normalization applied to recipients before they are compared with the grants of a share.
+///
+/// The default policy compares recipients as they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NormalizationPolicy {
+    /// Remove leading and trailing spaces.
+    pub trim: bool,
+    /// Convert ASCII letters to lowercase.
+    pub lowercase: bool,
+}
+
+impl NormalizationPolicy {
+    /// Policy that trims and lowercases recipients.
+    pub fn trim_lowercase() -> Self {
+        Self {
+            trim: true,
+            lowercase: true,
+        }
+    }
+
+    /// Normalize a recipient according to the policy.
+    pub fn normalize(&self, recipient: &str) -> String {
+        let recipient = if self.trim {
+            recipient.trim_matches(' ')
+        } else {
+            recipient
+        };
+        if self.lowercase {
+            recipient.to_ascii_lowercase()
+        } else {
+            recipient.to_string()
+        }
+    }
+
+    /// SQL expression normalizing the values of `column` like [`NormalizationPolicy::normalize`].
+    fn expression(&self, column: &str) -> String {
+        let mut expression = column.to_string();
+        if self.trim {
+            expression = format!("TRIM({})", expression);
+        }
+        if self.lowercase {
+            expression = format!("LOWER({})", expression);
+        }
+        expression
+    }
+}
+
+/// Discovery handler serving shares, schemas and tables stored in a SQL database.
+#[derive(Debug, Clone)]
+pub struct SqlHandler {
+    pool: AnyPool,
+    normalization: NormalizationPolicy,
+}
+
+impl SqlHandler {
+    /// Create a new [`SqlHandler`] using connections from `pool`.
+    pub fn new(pool: AnyPool) -> Self {
+        Self {
+            pool,
+            normalization: NormalizationPolicy::default(),
+        }
+    }
+
+    /// Normalize recipients before matching them against `share_recipients`.
+    ///
+    /// The policy applies to both the stored grants and the fingerprint of the requesting
+    /// recipient. Recipients are compared as they are by default.
+    pub fn normalize_recipients(mut self, policy: NormalizationPolicy) -> Self {
+        self.normalization = policy;
+        self
+    }
+
+    /// Create the tables read by the handler, if they do not exist yet.
+    pub async fn init_schema(&self) -> Result<()> {
+        for statement in SCHEMA {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    async fn ensure_share(&self, share: &str) -> Result<()> {
+        sqlx::query("SELECT 1 FROM shares WHERE name = $1")
+            .bind(share)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| Error::NotFound(format!("share '{}' does not exist", share)))
+    }
+
+    /// List the recipients a share is granted to.
+    ///
+    /// Recipients are returned as profile fingerprints in ascending order, with
+    /// [`ALL_RECIPIENTS`] marking shares that are granted to everyone. Only admin
+    /// recipients may list the grants of a share.
+    pub async fn list_share_recipients(
+        &self,
+        share: &str,
+        recipient: &DeltaRecipient,
+    ) -> Result<Vec<String>> {
+        if !recipient.is_admin() {
+            return Err(Error::NotAllowed);
+        }
+        self.ensure_share(share).await?;
+        let rows = sqlx::query(
+            "SELECT recipient FROM share_recipients WHERE share_name = $1 ORDER BY recipient",
+        )
+        .bind(share)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| Ok(row.try_get("recipient")?))
+            .collect()
+    }
+
+    /// List all tables of all shares, e.g. to export them for a compliance audit.
+    ///
+    /// The grants of the shares are ignored, so only admin recipients may list the tables.
+    /// Tables are returned in a single response ordered by share, schema and name. Catalogs
+    /// with more than [`MAX_AUDIT_TABLES`] tables are rejected rather than listed partially,
+    /// their tables must be listed share by share instead.
+    pub async fn audit_all_tables(&self, recipient: &DeltaRecipient) -> Result<Vec<t::Table>> {
+        if !recipient.is_admin() {
+            return Err(Error::NotAllowed);
+        }
+        let rows = sqlx::query(
+            "SELECT t.id, t.name, t.schema_name, t.share_name, s.id AS share_id FROM tables t
+             JOIN shares s ON s.name = t.share_name
+             ORDER BY t.share_name, t.schema_name, t.name
+             LIMIT $1",
+        )
+        .bind(MAX_AUDIT_TABLES as i64 + 1)
+        .fetch_all(&self.pool)
+        .await?;
+        if rows.len() > MAX_AUDIT_TABLES {
+            return Err(Error::Generic(format!(
+                "catalog contains more than {} tables, list the tables of each share instead",
+                MAX_AUDIT_TABLES
+            )));
+        }
+        rows.iter()
+            .map(|row| table_from_row(row, &row.try_get::<String, _>("share_name")?))
+            .collect()
+    }
+
+    async fn ensure_schema(&self, share: &str, schema: &str) -> Result<()> {
+        sqlx::query("SELECT 1 FROM schemas WHERE share_name = $1 AND name = $2")
+            .bind(share)
+            .bind(schema)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "schema '{}' does not exist",
+                    t::schema_fqn(share, schema)
+                ))
+            })
+    }
+}
+
+/// Position after the last item of a page, encoded as the page token.
+///
+/// The token holds the ordering key of the last returned item, so the next page starts
+/// right after it even if rows were inserted or deleted in the meantime.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cursor(Vec<String>);
+
+impl Cursor {
+    fn decode(pagination: &Pagination, len: usize) -> Result<Self> {
+        let Some(token) = pagination.page_token() else {
+            return Ok(Cursor(vec![String::new(); len]));
+        };
+        let malformed = || Error::MalformedPagination(format!("invalid page token '{}'", token));
+        let bytes = hex::decode(token).map_err(|_| malformed())?;
+        let cursor: Cursor = serde_json::from_slice(&bytes).map_err(|_| malformed())?;
+        if cursor.0.len() != len {
+            return Err(malformed());
+        }
+        Ok(cursor)
+    }
+
+    fn encode(&self) -> String {
+        hex::encode(serde_json::to_vec(&self.0).expect("cursor is serializable"))
+    }
+
+    fn key(&self, index: usize) -> &str {
+        &self.0[index]
+    }
+}
+
+/// Number of rows to request for a page, which includes one extra row to detect whether
+/// there is a next page.
+fn fetch_limit(pagination: &Pagination) -> (usize, i64) {
+    let limit = pagination.effective_limit(DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE);
+    (limit, limit as i64 + 1)
+}
+
+/// Truncate `items` to the page size and compute the token for the next page.
+fn into_page<T>(
+    mut items: Vec<T>,
+    limit: usize,
+    key: impl Fn(&T) -> Vec<String>,
+) -> (Vec<T>, Option<String>) {
+    if items.len() <= limit {
+        return (items, None);
+    }
+    items.truncate(limit);
+    let next_page_token = items.last().map(|item| Cursor(key(item)).encode());
+    (items, next_page_token)
+}
+
+fn table_from_row(row: &AnyRow, share: &str) -> Result<t::Table> {
+    Ok(t::Table {
+        id: Some(row.try_get("id")?),
+        name: row.try_get("name")?,
+        schema: row.try_get("schema_name")?,
+        share: share.to_string(),
+        share_id: Some(row.try_get("share_id")?),
+        last_updated: None,
+    })
+}
+
+#[async_trait::async_trait]
+impl DiscoveryHandler for SqlHandler {
+    type Recipient = DeltaRecipient;
+
+    async fn list_shares(
+        &self,
+        request: t::ListSharesRequest,
+        recipient: Self::Recipient,
+    ) -> Result<t::ListSharesResponse> {
+        let pagination = Pagination::new(request.max_results, request.page_token);
+        let cursor = Cursor::decode(&pagination, 1)?;
+        let (limit, fetch) = fetch_limit(&pagination);
+        let recipient = recipient
+            .fingerprint()
+            .map(|fingerprint| self.normalization.normalize(&fingerprint))
+            .unwrap_or_else(|| ALL_RECIPIENTS.to_string());
+        let query = format!(
+            "SELECT DISTINCT s.id, s.name FROM shares s
+             JOIN share_recipients r ON r.share_name = s.name
+             WHERE {} IN ($1, $2) AND s.name > $3
+             ORDER BY s.name
+             LIMIT $4",
+            self.normalization.expression("r.recipient")
+        );
+        let rows = sqlx::query(&query)
+            .bind(recipient)
+            .bind(ALL_RECIPIENTS)
+            .bind(cursor.key(0))
+            .bind(fetch)
+            .fetch_all(&self.pool)
+            .await?;
+        let shares = rows
+            .iter()
+            .map(|row| {
+                Ok(t::Share {
+                    id: Some(row.try_get("id")?),
+                    name: row.try_get("name")?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (items, next_page_token) = into_page(shares, limit, |share| vec![share.name.clone()]);
+        Ok(t::ListSharesResponse {
+            items,
+            next_page_token,
+        })
+    }
+
+    async fn get_share(&self, request: t::GetShareRequest) -> Result<t::GetShareResponse> {
+        let row = sqlx::query("SELECT id, name FROM shares WHERE name = $1")
+            .bind(&request.share)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("share '{}' does not exist", request.share)))?;
+        Ok(t::GetShareResponse {
+            share: Some(t::Share {
+                id: Some(row.try_get("id")?),
+                name: row.try_get("name")?,
+            }),
+        })
+    }
+
+    async fn list_schemas(&self, request: t::ListSchemasRequest) -> Result<t::ListSchemasResponse> {
+        let pagination = Pagination::new(request.max_results, request.page_token);
+        let cursor = Cursor::decode(&pagination, 1)?;
+        let (limit, fetch) = fetch_limit(&pagination);
+        self.ensure_share(&request.share).await?;
+        let rows = sqlx::query(
+            "SELECT name FROM schemas
+             WHERE share_name = $1 AND name > $2
+             ORDER BY name
+             LIMIT $3",
+        )
+        .bind(&request.share)
+        .bind(cursor.key(0))
+        .bind(fetch)
+        .fetch_all(&self.pool)
+        .await?;
+        let schemas = rows
+            .iter()
+            .map(|row| {
+                Ok(t::Schema {
+                    name: row.try_get("name")?,
+                    share: request.share.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (items, next_page_token) =
+            into_page(schemas, limit, |schema| vec![schema.name.clone()]);
+        Ok(t::ListSchemasResponse {
+            items,
+            next_page_token,
+        })
+    }
+
+    async fn list_schema_tables(
+        &self,
+        request: t::ListSchemaTablesRequest,
+    ) -> Result<t::ListSchemaTablesResponse> {
+        let pagination = Pagination::new(request.max_results, request.page_token);
+        let cursor = Cursor::decode(&pagination, 1)?;
+        let (limit, fetch) = fetch_limit(&pagination);
+        self.ensure_schema(&request.share, &request.schema).await?;
+        let rows = sqlx::query(
+            "SELECT t.id, t.name, t.schema_name, s.id AS share_id FROM tables t
+             JOIN shares s ON s.name = t.share_name
+             WHERE t.share_name = $1 AND t.schema_name = $2 AND t.name > $3
+             ORDER BY t.name
+             LIMIT $4",
+        )
+        .bind(&request.share)
+        .bind(&request.schema)
+        .bind(cursor.key(0))
+        .bind(fetch)
+        .fetch_all(&self.pool)
+        .await?;
+        let tables = rows
+            .iter()
+            .map(|row| table_from_row(row, &request.share))
+            .collect::<Result<Vec<_>>>()?;
+        let (items, next_page_token) = into_page(tables, limit, |table| vec![table.name.clone()]);
+        Ok(t::ListSchemaTablesResponse {
+            items,
+            next_page_token,
+        })
+    }
+
+    async fn list_share_tables(
+        &self,
+        request: t::ListShareTablesRequest,
+    ) -> Result<t::ListShareTablesResponse> {
+        let pagination = Pagination::new(request.max_results, request.page_token);
+        let cursor = Cursor::decode(&pagination, 2)?;
+        let (limit, fetch) = fetch_limit(&pagination);
+        self.ensure_share(&request.share).await?;
+        let rows = sqlx::query(
+            "SELECT t.id, t.name, t.schema_name, s.id AS share_id FROM tables t
+             JOIN shares s ON s.name = t.share_name
+             WHERE t.share_name = $1
+               AND (t.schema_name > $2 OR (t.schema_name = $2 AND t.name > $3))
+             ORDER BY t.schema_name, t.name
+             LIMIT $4",
+        )
+        .bind(&request.share)
+        .bind(cursor.key(0))
+        .bind(cursor.key(1))
+        .bind(fetch)
+        .fetch_all(&self.pool)
+        .await?;
+        let tables = rows
+            .iter()
+            .map(|row| table_from_row(row, &request.share))
+            .collect::<Result<Vec<_>>>()?;
+        let (items, next_page_token) = into_page(tables, limit, |table| {
+            vec![table.schema.clone(), table.name.clone()]
+        });
+        Ok(t::ListShareTablesResponse {
+            items,
+            next_page_token,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TableLocationResover for SqlHandler {
+    async fn resolve(&self, table_ref: &t::TableRef) -> Result<url::Url> {
+        let row = sqlx::query(
+            "SELECT location FROM tables
+             WHERE share_name = $1 AND schema_name = $2 AND name = $3",
+        )
+        .bind(&table_ref.share)
+        .bind(&table_ref.schema)
+        .bind(&table_ref.table)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("table '{}' does not exist", table_ref)))?;
+        let location: String = row.try_get("location")?;
+        url::Url::parse(&location).map_err(|_| Error::InvalidTableLocation(location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::any::AnyPoolOptions;
+
+    use super::*;
+    use crate::profiles::DefaultClaims;
+
+    /// Connect to the database the tests run against.
+    ///
+    /// Tests run against Postgres if the `postgres` feature is enabled and `DATABASE_URL` is
+    /// set, with every pool using a schema of its own, and against in-memory SQLite otherwise.
+    async fn test_pool() -> AnyPool {
+        sqlx::any::install_default_drivers();
+        // every connection to an in-memory database opens a new database, so the pool
+        // must hold on to a single connection.
+        let options = AnyPoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None);
+        #[cfg(feature = "postgres")]
+        if let Ok(url) = std::env::var("DATABASE_URL") {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            use sqlx::Executor;
+
+            static NEXT_SCHEMA: AtomicUsize = AtomicUsize::new(0);
+            let schema = format!(
+                "test_{}_{}",
+                std::process::id(),
+                NEXT_SCHEMA.fetch_add(1, Ordering::SeqCst)
+            );
+            let setup = format!(
+                "DROP SCHEMA IF EXISTS {0} CASCADE; CREATE SCHEMA {0}; SET search_path TO {0}",
+                schema
+            );
+            return options
+                .after_connect(move |conn, _| {
+                    let setup = setup.clone();
+                    Box::pin(async move {
+                        conn.execute(setup.as_str()).await?;
+                        Ok(())
+                    })
+                })
+                .connect(&url)
+                .await
+                .unwrap();
+        }
+        options.connect("sqlite::memory:").await.unwrap()
+    }
+
+    async fn test_handler() -> SqlHandler {
+        let pool = test_pool().await;
+        let handler = SqlHandler::new(pool);
+        handler.init_schema().await.unwrap();
+
+        let statements = [
+            "INSERT INTO shares (id, name) VALUES
+                ('id1', 'share1'), ('id2', 'share2'), ('id3', 'share3'), ('id4', 'share4')",
+            "INSERT INTO share_recipients (share_name, recipient) VALUES
+                ('share1', '*'), ('share2', 'fingerprint'), ('share3', '*'),
+                ('share3', 'fingerprint'), ('share4', 'other')",
+            "INSERT INTO schemas (share_name, name) VALUES
+                ('share1', 'schema1'), ('share1', 'schema2'), ('share1', 'schema3')",
+            "INSERT INTO tables (id, share_name, schema_name, name, location) VALUES
+                ('t1', 'share1', 'schema1', 'table1', 'file:///tmp/table1'),
+                ('t2', 'share1', 'schema1', 'table2', 'file:///tmp/table2'),
+                ('t3', 'share1', 'schema2', 'table1', 'file:///tmp/table3'),
+                ('t4', 'share1', 'schema3', 'table1', 'not a url')",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&handler.pool).await.unwrap();
+        }
+        handler
+    }
+
+    fn profile() -> DeltaRecipient {
+        DeltaRecipient::Profile(DefaultClaims {
+            email: "recipient@example.com".to_string(),
+            issued_at: 0,
+            shares: vec![],
+            expiration: None,
+            admin: None,
+        })
+    }
+
+    fn admin() -> DeltaRecipient {
+        DeltaRecipient::Profile(DefaultClaims {
+            email: "admin@example.com".to_string(),
+            issued_at: 0,
+            shares: vec![],
+            expiration: None,
+            admin: Some(true),
+        })
+    }
+
+    async fn share_names(handler: &SqlHandler, recipient: DeltaRecipient) -> Vec<String> {
+        let request = t::ListSharesRequest::default();
+        let response = handler.list_shares(request, recipient).await.unwrap();
+        response.items.into_iter().map(|share| share.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_acl() {
+        let handler = test_handler().await;
+
+        let names = share_names(&handler, DeltaRecipient::Anonymous).await;
+        assert_eq!(names, vec!["share1", "share3"]);
+
+        let recipient = profile();
+        let fingerprint = recipient.fingerprint().unwrap();
+        sqlx::query("UPDATE share_recipients SET recipient = $1 WHERE recipient = 'fingerprint'")
+            .bind(&fingerprint)
+            .execute(&handler.pool)
+            .await
+            .unwrap();
+        let names = share_names(&handler, recipient).await;
+        assert_eq!(names, vec!["share1", "share2", "share3"]);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_recipients() {
+        let recipient = profile();
+        let fingerprint = recipient.fingerprint().unwrap();
+        for (policy, expected) in [
+            (NormalizationPolicy::default(), vec!["share1", "share3"]),
+            (
+                NormalizationPolicy::trim_lowercase(),
+                vec!["share1", "share2", "share3"],
+            ),
+        ] {
+            let handler = test_handler().await.normalize_recipients(policy);
+            sqlx::query("UPDATE share_recipients SET recipient = $1 WHERE share_name = 'share2'")
+                .bind(format!(" {} ", fingerprint.to_uppercase()))
+                .execute(&handler.pool)
+                .await
+                .unwrap();
+            let names = share_names(&handler, recipient.clone()).await;
+            assert_eq!(names, expected);
+        }
+
+        let policy = NormalizationPolicy::trim_lowercase();
+        assert_eq!(policy.normalize("Alice "), "alice");
+        assert_eq!(NormalizationPolicy::default().normalize("Alice "), "Alice ");
+    }
+
+    #[tokio::test]
+    async fn test_list_share_recipients() {
+        let handler = test_handler().await;
+
+        let recipients = handler
+            .list_share_recipients("share3", &admin())
+            .await
+            .unwrap();
+        assert_eq!(recipients, vec!["*", "fingerprint"]);
+        let recipients = handler
+            .list_share_recipients("share4", &admin())
+            .await
+            .unwrap();
+        assert_eq!(recipients, vec!["other"]);
+
+        let result = handler.list_share_recipients("missing", &admin()).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+
+        for recipient in [profile(), DeltaRecipient::Anonymous] {
+            let result = handler.list_share_recipients("share3", &recipient).await;
+            assert!(matches!(result, Err(Error::NotAllowed)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_all_tables() {
+        let handler = test_handler().await;
+        let statements = [
+            "INSERT INTO schemas (share_name, name) VALUES ('share4', 'schema1')",
+            "INSERT INTO tables (id, share_name, schema_name, name, location) VALUES
+                ('t5', 'share4', 'schema1', 'table1', 'file:///tmp/table5')",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&handler.pool).await.unwrap();
+        }
+
+        // share4 is not granted to the admin, but its tables are listed regardless.
+        let tables = handler.audit_all_tables(&admin()).await.unwrap();
+        let declared: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tables")
+            .fetch_one(&handler.pool)
+            .await
+            .unwrap()
+            .get("count");
+        assert_eq!(tables.len() as i64, declared);
+        let last = tables.last().unwrap();
+        assert_eq!(last.share, "share4");
+        assert_eq!(last.share_id.as_deref(), Some("id4"));
+        assert_eq!(last.id.as_deref(), Some("t5"));
+
+        for recipient in [profile(), DeltaRecipient::Anonymous] {
+            let result = handler.audit_all_tables(&recipient).await;
+            assert!(matches!(result, Err(Error::NotAllowed)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_schemas() {
+        let handler = test_handler().await;
+
+        let request = t::ListSchemasRequest {
+            share: "share1".to_string(),
+            ..Default::default()
+        };
+        let response = handler.list_schemas(request).await.unwrap();
+        let names = response
+            .items
+            .iter()
+            .map(|schema| schema.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["schema1", "schema2", "schema3"]);
+        assert!(response.next_page_token.is_none());
+
+        let request = t::ListSchemasRequest {
+            share: "missing".to_string(),
+            ..Default::default()
+        };
+        let result = handler.list_schemas(request).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_share_tables_pagination() {
+        let handler = test_handler().await;
+
+        let mut tables = Vec::new();
+        let mut page_token = None;
+        loop {
+            let request = t::ListShareTablesRequest {
+                share: "share1".to_string(),
+                max_results: Some(1),
+                page_token,
+            };
+            let response = handler.list_share_tables(request).await.unwrap();
+            assert!(response.items.len() <= 1);
+            tables.extend(
+                response
+                    .items
+                    .into_iter()
+                    .map(|table| format!("{}.{}", table.schema, table.name)),
+            );
+            page_token = response.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            tables,
+            vec![
+                "schema1.table1",
+                "schema1.table2",
+                "schema2.table1",
+                "schema3.table1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pagination_is_stable_under_inserts() {
+        let handler = test_handler().await;
+
+        let request = t::ListSchemaTablesRequest {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            max_results: Some(1),
+            page_token: None,
+        };
+        let response = handler.list_schema_tables(request).await.unwrap();
+        assert_eq!(response.items[0].name, "table1");
+        assert_eq!(response.items[0].share_id.as_deref(), Some("id1"));
+
+        // rows inserted before the cursor do not shift the next page.
+        sqlx::query(
+            "INSERT INTO tables (id, share_name, schema_name, name, location)
+             VALUES ('t0', 'share1', 'schema1', 'table0', 'file:///tmp/table0')",
+        )
+        .execute(&handler.pool)
+        .await
+        .unwrap();
+        let request = t::ListSchemaTablesRequest {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            max_results: Some(1),
+            page_token: response.next_page_token,
+        };
+        let response = handler.list_schema_tables(request).await.unwrap();
+        assert_eq!(response.items[0].name, "table2");
+        assert!(response.next_page_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_page_token() {
+        let handler = test_handler().await;
+
+        let request = t::ListSchemasRequest {
+            share: "share1".to_string(),
+            page_token: Some("garbage".to_string()),
+            ..Default::default()
+        };
+        let result = handler.list_schemas(request).await;
+        assert!(matches!(result, Err(Error::MalformedPagination(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let handler = test_handler().await;
+
+        let table_ref = t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema1".to_string(),
+            table: "table2".to_string(),
+        };
+        let location = handler.resolve(&table_ref).await.unwrap();
+        assert_eq!(location.as_str(), "file:///tmp/table2");
+
+        let table_ref = t::TableRef {
+            share: "share1".to_string(),
+            schema: "schema3".to_string(),
+            table: "table1".to_string(),
+        };
+        let result = handler.resolve(&table_ref).await;
+        assert!(matches!(result, Err(Error::InvalidTableLocation(_))));
+    }
+}
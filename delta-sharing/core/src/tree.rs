@@ -0,0 +1,409 @@
+//! Nested view of the shares, schemas, and tables accessible to a recipient.
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream};
+use futures::{future, TryStreamExt};
+
+use crate::error::{Error, Result};
+use crate::pagination::MAX_PAGE_SIZE;
+use crate::types as t;
+use crate::DiscoveryHandler;
+
+/// All shares, schemas, and tables accessible to a recipient.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CatalogTree {
+    pub shares: Vec<ShareNode>,
+}
+
+/// A share together with the schemas it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareNode {
+    pub share: t::Share,
+    pub schemas: Vec<SchemaNode>,
+}
+
+/// A schema together with the tables it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaNode {
+    pub schema: t::Schema,
+    pub tables: Vec<t::Table>,
+}
+
+/// Build the [`CatalogTree`] for a recipient by walking all pages of the list APIs.
+pub(crate) async fn describe<H>(handler: &H, recipient: H::Recipient) -> Result<CatalogTree>
+where
+    H: DiscoveryHandler + ?Sized,
+    H::Recipient: Clone,
+{
+    let mut shares = Vec::new();
+    let mut page_token = None;
+    loop {
+        let request = t::ListSharesRequest {
+            max_results: None,
+            page_token,
+        };
+        let response = handler.list_shares(request, recipient.clone()).await?;
+        for share in response.items {
+            let schemas = describe_share(handler, &share.name).await?;
+            shares.push(ShareNode { share, schemas });
+        }
+        page_token = match response.next_page_token {
+            Some(token) => Some(token),
+            None => break,
+        };
+    }
+    Ok(CatalogTree { shares })
+}
+
+/// Find the table `table_ref` refers to by walking the pages of the list APIs.
+pub(crate) async fn get_table<H>(
+    handler: &H,
+    table_ref: &t::TableRef,
+    recipient: H::Recipient,
+) -> Result<t::Table>
+where
+    H: DiscoveryHandler + ?Sized,
+    H::Recipient: Clone,
+{
+    let mut page_token = None;
+    loop {
+        let request = t::ListSharesRequest {
+            max_results: None,
+            page_token,
+        };
+        let response = handler.list_share_names(request, recipient.clone()).await?;
+        if response.items.contains(&table_ref.share) {
+            break;
+        }
+        page_token = match response.next_page_token {
+            Some(token) => Some(token),
+            None => {
+                return Err(Error::NotFound(format!(
+                    "share '{}' does not exist",
+                    table_ref.share
+                )))
+            }
+        };
+    }
+    describe_schema(handler, &table_ref.share, &table_ref.schema)
+        .await?
+        .into_iter()
+        .find(|table| table.name == table_ref.table)
+        .ok_or_else(|| Error::NotFound(format!("table '{}' does not exist", table_ref)))
+}
+
+/// Returns true if the lowercase `query` is contained in the name of the table, its schema
+/// or its share, ignoring case.
+pub(crate) fn table_matches(table: &t::Table, query: &str) -> bool {
+    [&table.share, &table.schema, &table.name]
+        .iter()
+        .any(|name| name.to_lowercase().contains(query))
+}
+
+/// Find the tables matching `query` by walking all pages of the list APIs.
+///
+/// Tables are returned in the order of [`list_all_tables`].
+pub(crate) async fn search_tables<H>(
+    handler: &H,
+    query: &str,
+    recipient: H::Recipient,
+) -> Result<Vec<t::Table>>
+where
+    H: DiscoveryHandler + ?Sized,
+    H::Recipient: Clone,
+{
+    let query = query.to_lowercase();
+    list_all_tables(handler, recipient, MAX_PAGE_SIZE as i32)
+        .try_filter(|table| future::ready(table_matches(table, &query)))
+        .try_collect()
+        .await
+}
+
+async fn describe_share<H>(handler: &H, share: &str) -> Result<Vec<SchemaNode>>
+where
+    H: DiscoveryHandler + ?Sized,
+{
+    let mut schemas = Vec::new();
+    let mut page_token = None;
+    loop {
+        let request = t::ListSchemasRequest {
+            share: share.to_string(),
+            max_results: None,
+            page_token,
+        };
+        let response = handler.list_schemas(request).await?;
+        for schema in response.items {
+            let tables = describe_schema(handler, share, &schema.name).await?;
+            schemas.push(SchemaNode { schema, tables });
+        }
+        page_token = match response.next_page_token {
+            Some(token) => Some(token),
+            None => break,
+        };
+    }
+    Ok(schemas)
+}
+
+async fn describe_schema<H>(handler: &H, share: &str, schema: &str) -> Result<Vec<t::Table>>
+where
+    H: DiscoveryHandler + ?Sized,
+{
+    let mut tables = Vec::new();
+    let mut page_token = None;
+    loop {
+        let request = t::ListSchemaTablesRequest {
+            share: share.to_string(),
+            schema: schema.to_string(),
+            max_results: None,
+            page_token,
+        };
+        let response = handler.list_schema_tables(request).await?;
+        tables.extend(response.items);
+        page_token = match response.next_page_token {
+            Some(token) => Some(token),
+            None => break,
+        };
+    }
+    Ok(tables)
+}
+
+/// Position of [`list_all_tables_from`] after a table, handed out as an opaque page token.
+///
+/// The token identifies the share and schema of the table together with the number of
+/// tables of that schema listed so far. It is formatted as `<share>.<schema>.<offset>` with
+/// the names hex encoded, so that names containing dots do not break the format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TablesToken {
+    share: String,
+    schema: String,
+    offset: usize,
+}
+
+impl std::fmt::Display for TablesToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            hex_encode(&self.share),
+            hex_encode(&self.schema),
+            self.offset
+        )
+    }
+}
+
+impl std::str::FromStr for TablesToken {
+    type Err = Error;
+
+    fn from_str(token: &str) -> Result<Self> {
+        let invalid = || Error::MalformedPagination(format!("invalid page token '{}'", token));
+        let mut parts = token.splitn(3, '.');
+        let (Some(share), Some(schema), Some(offset)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            share: hex_decode(share).ok_or_else(invalid)?,
+            schema: hex_decode(schema).ok_or_else(invalid)?,
+            offset: offset.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl TablesToken {
+    fn stale(&self) -> Error {
+        Error::MalformedPagination(format!(
+            "page token '{}' is stale, the listing changed since it was issued",
+            self
+        ))
+    }
+}
+
+fn hex_encode(name: &str) -> String {
+    name.bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hex_decode(hex: &str) -> Option<String> {
+    let bytes = hex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => u8::from_str_radix(std::str::from_utf8(&[*high, *low]).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Position of [`list_all_tables_from`] in the catalog.
+///
+/// Page tokens of the underlying list APIs are `Some(None)` before the first page is
+/// fetched and `None` once all pages were fetched.
+struct TablesCursor<R> {
+    recipient: R,
+    /// Page token of the listing to resume, parsed when the first table is requested.
+    page_token: Option<String>,
+    /// Position to resume from, cleared once the listing caught up with it.
+    resume: Option<TablesToken>,
+    share_page_token: Option<Option<String>>,
+    /// Shares from the current page whose schemas were not yet listed.
+    shares: VecDeque<String>,
+    /// Share currently being listed.
+    share: Option<String>,
+    schema_page_token: Option<Option<String>>,
+    /// Schemas of the current share from the current page whose tables were not yet listed.
+    schemas: VecDeque<String>,
+    /// Schema currently being listed.
+    schema: Option<String>,
+    table_page_token: Option<Option<String>>,
+    /// Tables fetched from the current page that were not yet yielded.
+    tables: VecDeque<t::Table>,
+    /// Number of tables of the current schema listed so far.
+    offset: usize,
+    /// Number of tables of the current schema listed before the resumed position.
+    skip: usize,
+}
+
+/// Stream all tables the recipient is allowed to read.
+///
+/// Shares, schemas and tables are fetched lazily one page at a time, requesting at most
+/// `page_size` items per page, so that no more than one page of each is buffered at any time.
+pub fn list_all_tables<H>(
+    handler: &H,
+    recipient: H::Recipient,
+    page_size: i32,
+) -> impl Stream<Item = Result<t::Table>> + Send + '_
+where
+    H: DiscoveryHandler + ?Sized,
+    H::Recipient: Clone,
+{
+    list_all_tables_from(handler, recipient, page_size, None).map_ok(|(table, _)| table)
+}
+
+/// Stream all tables the recipient is allowed to read, resuming after `page_token`.
+///
+/// Each table is yielded together with the opaque page token that resumes the listing
+/// right after it, so a listing interrupted at any table can be continued by passing the
+/// last token back in. Tables are buffered like in [`list_all_tables`].
+///
+/// Malformed tokens and tokens referring to a share or schema that no longer exists yield
+/// [`Error::MalformedPagination`].
+pub fn list_all_tables_from<H>(
+    handler: &H,
+    recipient: H::Recipient,
+    page_size: i32,
+    page_token: Option<String>,
+) -> impl Stream<Item = Result<(t::Table, String)>> + Send + '_
+where
+    H: DiscoveryHandler + ?Sized,
+    H::Recipient: Clone,
+{
+    let cursor = TablesCursor {
+        recipient,
+        page_token,
+        resume: None,
+        share_page_token: Some(None),
+        shares: VecDeque::new(),
+        share: None,
+        schema_page_token: None,
+        schemas: VecDeque::new(),
+        schema: None,
+        table_page_token: None,
+        tables: VecDeque::new(),
+        offset: 0,
+        skip: 0,
+    };
+    stream::try_unfold(cursor, move |mut cursor| async move {
+        if let Some(token) = cursor.page_token.take() {
+            cursor.resume = Some(token.parse()?);
+        }
+        loop {
+            if let Some(table) = cursor.tables.pop_front() {
+                cursor.offset += 1;
+                if cursor.offset <= cursor.skip {
+                    continue;
+                }
+                let token = TablesToken {
+                    share: table.share.clone(),
+                    schema: table.schema.clone(),
+                    offset: cursor.offset,
+                };
+                return Ok(Some(((table, token.to_string()), cursor)));
+            }
+            if let (Some(share), Some(schema)) = (&cursor.share, &cursor.schema) {
+                if let Some(page_token) = cursor.table_page_token.take() {
+                    let request = t::ListSchemaTablesRequest {
+                        share: share.clone(),
+                        schema: schema.clone(),
+                        max_results: Some(page_size),
+                        page_token,
+                    };
+                    let response = handler.list_schema_tables(request).await?;
+                    cursor.tables.extend(response.items);
+                    cursor.table_page_token = response.next_page_token.map(Some);
+                    continue;
+                }
+            }
+            if let Some(schema) = cursor.schemas.pop_front() {
+                if let Some(resume) = &cursor.resume {
+                    if resume.schema != schema {
+                        continue;
+                    }
+                    cursor.skip = resume.offset;
+                    cursor.resume = None;
+                } else {
+                    cursor.skip = 0;
+                }
+                cursor.schema = Some(schema);
+                cursor.table_page_token = Some(None);
+                cursor.offset = 0;
+                continue;
+            }
+            cursor.schema = None;
+            if let Some(share) = &cursor.share {
+                if let Some(page_token) = cursor.schema_page_token.take() {
+                    let request = t::ListSchemasRequest {
+                        share: share.clone(),
+                        max_results: Some(page_size),
+                        page_token,
+                    };
+                    let response = handler.list_schemas(request).await?;
+                    cursor
+                        .schemas
+                        .extend(response.items.into_iter().map(|schema| schema.name));
+                    cursor.schema_page_token = response.next_page_token.map(Some);
+                    continue;
+                }
+                if let Some(resume) = &cursor.resume {
+                    return Err(resume.stale());
+                }
+            }
+            if let Some(share) = cursor.shares.pop_front() {
+                if matches!(&cursor.resume, Some(resume) if resume.share != share) {
+                    continue;
+                }
+                cursor.share = Some(share);
+                cursor.schema_page_token = Some(None);
+                continue;
+            }
+            cursor.share = None;
+            let Some(page_token) = cursor.share_page_token.take() else {
+                if let Some(resume) = &cursor.resume {
+                    return Err(resume.stale());
+                }
+                return Ok(None);
+            };
+            let request = t::ListSharesRequest {
+                max_results: Some(page_size),
+                page_token,
+            };
+            let response = handler
+                .list_shares(request, cursor.recipient.clone())
+                .await?;
+            cursor
+                .shares
+                .extend(response.items.into_iter().map(|share| share.name));
+            cursor.share_page_token = response.next_page_token.map(Some);
+        }
+    })
+}
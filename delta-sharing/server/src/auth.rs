@@ -1,13 +1,19 @@
 //! Authentication middleware for Delta Sharing server.
 
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use axum::extract::Request;
+use axum::http::header;
 use axum::response::{IntoResponse, Response};
-use delta_sharing_core::{Authenticator, DeltaRecipient, Error as CoreError};
+use chrono::{DateTime, Utc};
+use delta_sharing_core::{Authenticator, DefaultClaims, DeltaRecipient, Error as CoreError};
 use futures_util::{future::BoxFuture, FutureExt};
+use serde::Deserialize;
 use tower::{Layer, Service};
 
+use crate::clock::{Clock, SystemClock};
 use crate::error::{Error, Result};
 
 /// Authenticator that always marks the recipient as anonymous.
@@ -23,6 +29,187 @@ impl Authenticator for AnonymousAuthenticator {
     }
 }
 
+/// Authenticator that assigns a default recipient to requests without a bearer token.
+///
+/// Requests that carry a bearer token are authenticated by the wrapped [`Authenticator`].
+#[derive(Clone)]
+pub struct DefaultRecipientAuthenticator<T, R> {
+    inner: T,
+    recipient: R,
+}
+
+impl<T, R> DefaultRecipientAuthenticator<T, R> {
+    /// Create a new [`DefaultRecipientAuthenticator`] wrapping `inner`.
+    pub fn new(inner: T, recipient: R) -> Self {
+        Self { inner, recipient }
+    }
+}
+
+impl<T> DefaultRecipientAuthenticator<T, DeltaRecipient> {
+    /// Create a new [`DefaultRecipientAuthenticator`] for the recipient called `name`.
+    ///
+    /// Falls back to [`DeltaRecipient::Anonymous`] if no name is given.
+    pub fn named(inner: T, name: Option<String>) -> Self {
+        let recipient = name.map_or(DeltaRecipient::Anonymous, named_recipient);
+        Self::new(inner, recipient)
+    }
+}
+
+/// Recipient identified by `name` rather than by a profile issued by the server.
+fn named_recipient(name: String) -> DeltaRecipient {
+    DeltaRecipient::Profile(DefaultClaims {
+        email: name,
+        issued_at: 0,
+        shares: vec![],
+        expiration: None,
+        admin: None,
+    })
+}
+
+impl<T, R> Authenticator for DefaultRecipientAuthenticator<T, R>
+where
+    T: Authenticator<Request = Request, Recipient = R>,
+    R: Clone + Send + Sync,
+{
+    type Request = Request;
+    type Recipient = R;
+
+    fn authenticate(&self, request: &Self::Request) -> Result<Self::Recipient, CoreError> {
+        let has_token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| !token.trim().is_empty());
+        if has_token {
+            self.inner.authenticate(request)
+        } else {
+            Ok(self.recipient.clone())
+        }
+    }
+}
+
+/// Bearer token that authenticates a recipient.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenConfig {
+    pub token: String,
+    /// Name of the recipient the token authenticates.
+    pub recipient: String,
+    /// Time after which the token is rejected.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Bearer tokens accepted by the server.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TokensConfig {
+    #[serde(default)]
+    pub tokens: Vec<TokenConfig>,
+}
+
+/// Authenticator that maps configured bearer tokens to recipients.
+///
+/// Several tokens may map to the same recipient, so tokens can be rotated while the old
+/// token is still in use. Requests with a missing, unknown or expired token are rejected.
+#[derive(Clone)]
+pub struct TokenAuthenticator {
+    tokens: Arc<HashMap<String, TokenConfig>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl TokenAuthenticator {
+    /// Create a new [`TokenAuthenticator`] accepting the given tokens.
+    pub fn new(config: TokensConfig) -> Self {
+        Self::with_clock(config, SystemClock)
+    }
+
+    /// Create a new [`TokenAuthenticator`] telling whether tokens have expired by `clock`.
+    pub fn with_clock(config: TokensConfig, clock: impl Clock) -> Self {
+        let tokens = config
+            .tokens
+            .into_iter()
+            .map(|token| (token.token.clone(), token))
+            .collect();
+        Self {
+            tokens: Arc::new(tokens),
+            clock: Arc::new(clock),
+        }
+    }
+}
+
+impl Authenticator for TokenAuthenticator {
+    type Request = Request;
+    type Recipient = DeltaRecipient;
+
+    fn authenticate(&self, request: &Self::Request) -> Result<Self::Recipient, CoreError> {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(CoreError::Unauthenticated)?;
+        let config = self
+            .tokens
+            .get(token.trim())
+            .ok_or(CoreError::Unauthenticated)?;
+        if config
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= self.clock.now())
+        {
+            return Err(CoreError::Unauthenticated);
+        }
+        Ok(named_recipient(config.recipient.clone()))
+    }
+}
+
+/// Header naming the recipient of a request for [`TestRecipientAuthenticator`].
+#[cfg(any(test, all(feature = "test-auth", debug_assertions)))]
+pub const TEST_RECIPIENT_HEADER: &str = "x-test-recipient";
+
+/// Authenticator that takes the recipient from the [`TEST_RECIPIENT_HEADER`].
+///
+/// This allows tests to act as any recipient without issuing profiles. Requests without
+/// the header are authenticated by the wrapped [`Authenticator`]. Only available in debug
+/// builds with the `test-auth` feature enabled.
+#[cfg(any(test, all(feature = "test-auth", debug_assertions)))]
+#[derive(Clone)]
+pub struct TestRecipientAuthenticator<T> {
+    inner: T,
+}
+
+#[cfg(any(test, all(feature = "test-auth", debug_assertions)))]
+impl<T> TestRecipientAuthenticator<T> {
+    /// Create a new [`TestRecipientAuthenticator`] wrapping `inner`.
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+#[cfg(any(test, all(feature = "test-auth", debug_assertions)))]
+impl<T> Authenticator for TestRecipientAuthenticator<T>
+where
+    T: Authenticator<Request = Request, Recipient = DeltaRecipient>,
+{
+    type Request = Request;
+    type Recipient = DeltaRecipient;
+
+    fn authenticate(&self, request: &Self::Request) -> Result<Self::Recipient, CoreError> {
+        match request.headers().get(TEST_RECIPIENT_HEADER) {
+            Some(name) => {
+                let name = name.to_str().map_err(|_| {
+                    CoreError::InvalidArgument(format!(
+                        "malformed {} header",
+                        TEST_RECIPIENT_HEADER
+                    ))
+                })?;
+                Ok(named_recipient(name.to_string()))
+            }
+            None => self.inner.authenticate(request),
+        }
+    }
+}
+
 /// Middleware that authenticates requests using the given [`Authenticator`].
 #[derive(Clone)]
 pub struct AuthenticationMiddleware<S, T> {
@@ -105,6 +292,7 @@ mod tests {
     use tower::{ServiceBuilder, ServiceExt};
 
     use super::*;
+    use crate::clock::FixedClock;
     use crate::error::Result;
 
     async fn check_recipient(req: Request) -> Result<Response<Body>> {
@@ -134,4 +322,96 @@ mod tests {
         let response = service.ready().await.unwrap().call(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[test]
+    fn test_default_recipient_authenticator() {
+        let authenticator =
+            DefaultRecipientAuthenticator::named(AnonymousAuthenticator, Some("guest".into()));
+
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        let recipient = authenticator.authenticate(&request).unwrap();
+        assert!(matches!(recipient, DeltaRecipient::Profile(claims) if claims.email == "guest"));
+
+        let request = Request::get("/")
+            .header(header::AUTHORIZATION, "Bearer foo")
+            .body(Body::empty())
+            .unwrap();
+        let recipient = authenticator.authenticate(&request).unwrap();
+        assert_eq!(recipient, DeltaRecipient::Anonymous);
+
+        let authenticator = DefaultRecipientAuthenticator::named(AnonymousAuthenticator, None);
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        let recipient = authenticator.authenticate(&request).unwrap();
+        assert_eq!(recipient, DeltaRecipient::Anonymous);
+    }
+
+    #[tokio::test]
+    async fn test_token_authenticator() {
+        let config = serde_json::from_value::<TokensConfig>(serde_json::json!({
+            "tokens": [
+                {"token": "old", "recipient": "alice", "expiresAt": "2020-01-01T00:00:00Z"},
+                {"token": "new", "recipient": "alice", "expiresAt": "2999-01-01T00:00:00Z"},
+                {"token": "other", "recipient": "bob"}
+            ]
+        }))
+        .unwrap();
+        let authenticator = TokenAuthenticator::new(config);
+        let bearer = |token: &str| {
+            Request::get("/")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for (token, name) in [("new", "alice"), ("other", "bob")] {
+            let recipient = authenticator.authenticate(&bearer(token)).unwrap();
+            assert!(matches!(recipient, DeltaRecipient::Profile(claims) if claims.email == name));
+        }
+        for token in ["old", "unknown"] {
+            let result = authenticator.authenticate(&bearer(token));
+            assert!(matches!(result, Err(CoreError::Unauthenticated)));
+        }
+
+        let mut service = ServiceBuilder::new()
+            .layer(AuthorizationLayer::new(authenticator))
+            .service_fn(|req: Request| async { Ok::<_, Error>(Response::new(req.into_body())) });
+        for (token, status) in [("new", StatusCode::OK), ("old", StatusCode::UNAUTHORIZED)] {
+            let response = service
+                .ready()
+                .await
+                .unwrap()
+                .call(bearer(token))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status);
+        }
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_token_expiry() {
+        let t = "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let config = TokensConfig {
+            tokens: vec![TokenConfig {
+                token: "token".into(),
+                recipient: "alice".into(),
+                expires_at: Some(t + chrono::Duration::seconds(1)),
+            }],
+        };
+        let request = Request::get("/")
+            .header(header::AUTHORIZATION, "Bearer token")
+            .body(Body::empty())
+            .unwrap();
+
+        let authenticator = TokenAuthenticator::with_clock(config.clone(), FixedClock(t));
+        let recipient = authenticator.authenticate(&request).unwrap();
+        assert!(matches!(recipient, DeltaRecipient::Profile(claims) if claims.email == "alice"));
+
+        let later = t + chrono::Duration::seconds(2);
+        let authenticator = TokenAuthenticator::with_clock(config, FixedClock(later));
+        let result = authenticator.authenticate(&request);
+        assert!(matches!(result, Err(CoreError::Unauthenticated)));
+    }
 }
@@ -1,14 +1,16 @@
 use axum::extract::Json;
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use delta_sharing_core::{Error as CoreError, ErrorResponse};
-use tracing::error;
+use tracing::{debug, error};
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug)]
 pub enum Error {
     Core(CoreError),
+    /// None of the media types accepted by the client can be produced.
+    NotAcceptable(String),
 }
 
 impl From<CoreError> for Error {
@@ -17,26 +19,86 @@ impl From<CoreError> for Error {
     }
 }
 
-const INTERNAL_ERROR: (StatusCode, &'static str) = (
+/// Error codes returned in the `errorCode` field of error responses, as defined by the
+/// Delta Sharing protocol.
+mod code {
+    pub const INVALID_PARAMETER_VALUE: &str = "INVALID_PARAMETER_VALUE";
+    pub const RESOURCE_DOES_NOT_EXIST: &str = "RESOURCE_DOES_NOT_EXIST";
+    pub const PERMISSION_DENIED: &str = "PERMISSION_DENIED";
+    pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
+    pub const REQUEST_LIMIT_EXCEEDED: &str = "REQUEST_LIMIT_EXCEEDED";
+    pub const TEMPORARILY_UNAVAILABLE: &str = "TEMPORARILY_UNAVAILABLE";
+    pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+}
+
+/// Seconds clients are asked to wait before retrying requests rejected as unavailable.
+const RETRY_AFTER_SECONDS: u64 = 60;
+
+const INTERNAL_ERROR: (StatusCode, &str, &str) = (
     StatusCode::INTERNAL_SERVER_ERROR,
+    code::INTERNAL_ERROR,
     "The request is not handled correctly due to a server error.",
 );
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            Error::Core(CoreError::NotFound) => (
-                StatusCode::NOT_FOUND,
-                "The requested resource does not exist.",
-            ),
+        let (status, code, message) = match self {
+            Error::NotAcceptable(accept) => {
+                debug!("Not acceptable: {}", accept);
+                return error_response(
+                    StatusCode::NOT_ACCEPTABLE,
+                    code::INVALID_PARAMETER_VALUE,
+                    format!("Cannot produce a response for media types '{}'.", accept),
+                );
+            }
+            Error::Core(CoreError::NotFound(message)) => {
+                debug!("Not found: {}", message);
+                return error_response(
+                    StatusCode::NOT_FOUND,
+                    code::RESOURCE_DOES_NOT_EXIST,
+                    message,
+                );
+            }
             Error::Core(CoreError::NotAllowed) => (
                 StatusCode::FORBIDDEN,
+                code::PERMISSION_DENIED,
                 "The request is forbidden from being fulfilled.",
             ),
             Error::Core(CoreError::Unauthenticated) => (
                 StatusCode::UNAUTHORIZED,
+                code::UNAUTHENTICATED,
                 "The request is unauthenticated. The bearer token is missing or incorrect.",
             ),
+            Error::Core(CoreError::RateLimited) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                code::REQUEST_LIMIT_EXCEEDED,
+                "The request was rejected because the recipient exceeded the rate limit.",
+            ),
+            Error::Core(CoreError::Unavailable(message)) => {
+                error!("Service unavailable: {}", message);
+                let response = error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    code::TEMPORARILY_UNAVAILABLE,
+                    "The service is temporarily unavailable, please retry later.",
+                );
+                return ([(header::RETRY_AFTER, RETRY_AFTER_SECONDS)], response).into_response();
+            }
+            Error::Core(CoreError::MalformedPagination(message)) => {
+                debug!("Malformed pagination: {}", message);
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    code::INVALID_PARAMETER_VALUE,
+                    message,
+                );
+            }
+            Error::Core(CoreError::InvalidArgument(message)) => {
+                debug!("Invalid argument: {}", message);
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    code::INVALID_PARAMETER_VALUE,
+                    message,
+                );
+            }
             Error::Core(CoreError::Kernel(error)) => {
                 let message = format!("Kernel error: {}", error);
                 error!("delta-kernel error: {}", message);
@@ -47,19 +109,83 @@ impl IntoResponse for Error {
                 error!("{}", message);
                 INTERNAL_ERROR
             }
+            Error::Core(error @ CoreError::InvalidConfig { .. }) => {
+                error!("{}", error);
+                INTERNAL_ERROR
+            }
             Error::Core(CoreError::Generic(message)) => {
                 error!("Generic error: {}", message);
                 INTERNAL_ERROR
             }
         };
 
-        (
-            status,
-            Json(ErrorResponse {
-                error_code: status.to_string(),
-                message: message.to_string(),
-            }),
-        )
-            .into_response()
+        error_response(status, code, message)
+    }
+}
+
+fn error_response(status: StatusCode, code: &str, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            error_code: code.to_string(),
+            message: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::BodyExt;
+
+    use super::*;
+
+    async fn response_body(error: Error) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_hides_details() {
+        let error = Error::Core(CoreError::Generic("secret detail".to_string()));
+        let (status, body) = response_body(error).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["errorCode"], "INTERNAL_ERROR");
+        assert!(!body["message"].as_str().unwrap().contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_error_codes() {
+        let cases = [
+            (CoreError::NotAllowed, "PERMISSION_DENIED"),
+            (CoreError::Unauthenticated, "UNAUTHENTICATED"),
+            (CoreError::RateLimited, "REQUEST_LIMIT_EXCEEDED"),
+            (
+                CoreError::Unavailable("down".to_string()),
+                "TEMPORARILY_UNAVAILABLE",
+            ),
+        ];
+        for (error, code) in cases {
+            let (_, body) = response_body(Error::Core(error)).await;
+            assert_eq!(body["errorCode"], code);
+        }
+    }
+
+    #[test]
+    fn test_unavailable_retry_after() {
+        let response = Error::Core(CoreError::Unavailable("down".to_string())).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "60");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_argument() {
+        let error = CoreError::InvalidArgument("table 'view1' is a view".to_string());
+        let (status, body) = response_body(Error::Core(error)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["errorCode"], "INVALID_PARAMETER_VALUE");
+        assert_eq!(body["message"], "table 'view1' is a view");
     }
 }
@@ -1,11 +1,16 @@
 use std::ops::Deref;
 
 use axum::extract::FromRequestParts;
+use axum::response::{IntoResponseParts, ResponseParts};
 
-use delta_sharing_core::capabilities::Capabilities as CoreCapabilities;
+use delta_sharing_core::capabilities::{
+    Capabilities as CoreCapabilities, DELTA_SHARING_CAPABILITIES,
+};
 use http::request::Parts;
+use http::HeaderValue;
 
 use crate::error::Error;
+use delta_sharing_core::Error as CoreError;
 
 #[derive(Debug)]
 pub struct Capabilities(pub CoreCapabilities);
@@ -20,6 +25,19 @@ impl<S> FromRequestParts<S> for Capabilities {
     }
 }
 
+/// Echo the negotiated capabilities in the `delta-sharing-capabilities` response header.
+impl IntoResponseParts for Capabilities {
+    type Error = Error;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let value = HeaderValue::from_str(&self.header_value()).map_err(|e| {
+            CoreError::Generic(format!("Failed to encode capabilities header: {}", e))
+        })?;
+        res.headers_mut().insert(DELTA_SHARING_CAPABILITIES, value);
+        Ok(res)
+    }
+}
+
 impl Deref for Capabilities {
     type Target = CoreCapabilities;
 
@@ -28,9 +46,61 @@ impl Deref for Capabilities {
     }
 }
 
+/// Media type of newline delimited JSON responses.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Format of list responses, negotiated via the `Accept` header.
+///
+/// Requests without an `Accept` header receive JSON responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseMode {
+    #[default]
+    Json,
+    NdJson,
+}
+
+impl ResponseMode {
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/json" | "application/*" | "*/*" => Some(Self::Json),
+            NDJSON_CONTENT_TYPE => Some(Self::NdJson),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ResponseMode {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(accept) = parts.headers.get(http::header::ACCEPT) else {
+            return Ok(Self::default());
+        };
+        let accept = accept
+            .to_str()
+            .map_err(|_| Error::NotAcceptable("<invalid header>".to_string()))?;
+        if accept.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        accept
+            .split(',')
+            .filter_map(|media_type| {
+                let media_type = media_type.split(';').next().unwrap_or_default();
+                Self::from_media_type(&media_type.trim().to_ascii_lowercase())
+            })
+            .next()
+            .ok_or_else(|| Error::NotAcceptable(accept.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
     use delta_sharing_core::capabilities::ResponseFormat;
+    use tower::ServiceExt;
 
     use super::*;
 
@@ -58,4 +128,53 @@ mod test {
             vec!["feature1".to_string(), "feature2".to_string()]
         );
     }
+
+    #[tokio::test]
+    async fn test_capabilities_negotiation() {
+        async fn handler(capabilities: Capabilities) -> (Capabilities, &'static str) {
+            let supported =
+                CoreCapabilities::new(vec![ResponseFormat::Parquet, ResponseFormat::Delta], vec![]);
+            (Capabilities(capabilities.negotiate(&supported)), "ok")
+        }
+        let app = Router::new().route("/", get(handler));
+
+        let request = http::Request::builder()
+            .uri("/")
+            .header("delta-sharing-capabilities", "responseformat=delta")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert!(response.status().is_success());
+        assert_eq!(
+            response
+                .headers()
+                .get("delta-sharing-capabilities")
+                .unwrap(),
+            "responseformat=delta"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_mode() {
+        for (accept, expected) in [
+            (None, Some(ResponseMode::Json)),
+            (Some("application/json"), Some(ResponseMode::Json)),
+            (Some("*/*"), Some(ResponseMode::Json)),
+            (Some("application/x-ndjson"), Some(ResponseMode::NdJson)),
+            (
+                Some("text/html, application/x-ndjson;q=0.9"),
+                Some(ResponseMode::NdJson),
+            ),
+            (Some("text/csv"), None),
+        ] {
+            let mut builder = http::Request::builder();
+            if let Some(accept) = accept {
+                builder = builder.header(http::header::ACCEPT, accept);
+            }
+            let (mut parts, _) = builder.body(()).unwrap().into_parts();
+            let mode = ResponseMode::from_request_parts(&mut parts, &()).await;
+            assert_eq!(mode.ok(), expected, "accept: {:?}", accept);
+        }
+    }
 }
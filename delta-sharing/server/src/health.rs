@@ -0,0 +1,145 @@
+//! Health checks reporting whether the server is serving a valid configuration.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The last attempt to load the configuration succeeded.
+    Ok,
+    /// The last attempt to reload the configuration failed, the previously loaded
+    /// configuration is still being served.
+    Stale,
+}
+
+/// Health of the configuration served by the server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    pub status: HealthStatus,
+    /// Number of shares in the configuration being served.
+    pub share_count: usize,
+    /// Time the configuration being served was loaded.
+    pub last_loaded_at: DateTime<Utc>,
+}
+
+/// Tracks the outcome of loading the server configuration.
+#[derive(Debug)]
+pub struct CatalogHealth {
+    health: RwLock<Health>,
+}
+
+impl CatalogHealth {
+    /// Create a new [`CatalogHealth`] for a configuration with `share_count` shares that was
+    /// just loaded.
+    pub fn new(share_count: usize) -> Self {
+        Self {
+            health: RwLock::new(Health {
+                status: HealthStatus::Ok,
+                share_count,
+                last_loaded_at: Utc::now(),
+            }),
+        }
+    }
+
+    /// Record that a configuration with `share_count` shares was loaded.
+    pub fn loaded(&self, share_count: usize) {
+        *self.health.write().unwrap_or_else(PoisonError::into_inner) = Health {
+            status: HealthStatus::Ok,
+            share_count,
+            last_loaded_at: Utc::now(),
+        };
+    }
+
+    /// Record that reloading the configuration failed.
+    pub fn failed(&self) {
+        self.health
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .status = HealthStatus::Stale;
+    }
+
+    /// Get the current health.
+    pub fn health(&self) -> Health {
+        self.health
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+async fn check(State(health): State<Arc<CatalogHealth>>) -> Response {
+    let health = health.health();
+    let status = match health.status {
+        HealthStatus::Ok => StatusCode::OK,
+        HealthStatus::Stale => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(health)).into_response()
+}
+
+/// Router serving the health checks.
+///
+/// Both `/healthz` and `/readyz` respond with 200 while the last load of the configuration
+/// succeeded, and with 503 while a failed reload leaves stale data being served.
+pub fn get_health_router(health: Arc<CatalogHealth>) -> Router {
+    Router::new()
+        .route("/healthz", get(check))
+        .route("/readyz", get(check))
+        .with_state(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get_health(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_health() {
+        let health = Arc::new(CatalogHealth::new(2));
+
+        for uri in ["/healthz", "/readyz"] {
+            let (status, body) = get_health(get_health_router(health.clone()), uri).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["status"], "ok");
+            assert_eq!(body["shareCount"], 2);
+            assert!(body["lastLoadedAt"].is_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_after_failed_reload() {
+        let health = Arc::new(CatalogHealth::new(2));
+        let loaded_at = health.health().last_loaded_at;
+        health.failed();
+
+        let (status, body) = get_health(get_health_router(health.clone()), "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "stale");
+        assert_eq!(body["shareCount"], 2);
+        assert_eq!(health.health().last_loaded_at, loaded_at);
+
+        health.loaded(3);
+        let (status, body) = get_health(get_health_router(health), "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["shareCount"], 3);
+    }
+}
@@ -1,18 +1,37 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use axum::extract::Request;
+use axum::Router;
 use clap::Parser;
-use delta_sharing_core::policies::ConstantPolicy;
-use delta_sharing_core::{DeltaRecipient, InMemoryConfig, InMemoryHandler, KernelQueryHandler};
+use delta_sharing_core::policies::{ConstantPolicy, RateLimitConfig, RateLimiter};
+use delta_sharing_core::{
+    Authenticator, DeltaRecipient, Error as CoreError, InMemoryConfig, InMemoryHandler,
+    KernelQueryHandler, MaintenanceHandler, MaintenanceMode, ReferenceServerConfig, TableConfig,
+    TableRecord,
+};
+use serde::de::DeserializeOwned;
 use tokio::net::TcpListener;
 use tokio::signal;
-use tower_http::trace::TraceLayer;
 
-use self::auth::{AnonymousAuthenticator, AuthorizationLayer};
+use self::auth::{
+    AnonymousAuthenticator, AuthorizationLayer, DefaultRecipientAuthenticator, TokenAuthenticator,
+    TokensConfig,
+};
+use self::health::{get_health_router, CatalogHealth};
+use self::maintenance::get_maintenance_router;
+use self::rate_limit::RateLimitLayer;
+use self::request_id::with_request_id;
 use self::server::{get_router, DeltaSharingState};
 
 mod auth;
+mod clock;
 mod error;
 pub mod extractors;
+mod health;
+mod maintenance;
+mod rate_limit;
+mod request_id;
 mod server;
 
 #[derive(Parser)]
@@ -26,25 +45,90 @@ struct Cli {
 
     #[arg(short, long, default_value = "config.yaml")]
     config: String,
+
+    /// Reject configuration files containing unknown fields.
+    #[arg(long)]
+    strict_config: bool,
+
+    /// Read the configuration file in the format of the reference Delta Sharing server.
+    #[arg(long)]
+    reference_config: bool,
+
+    /// Check that the storage locations of all tables can be reached before serving.
+    #[arg(long)]
+    validate_storage: bool,
+
+    /// Recipient assigned to requests without a bearer token.
+    ///
+    /// Requests without a token are treated as anonymous if not set.
+    #[arg(long)]
+    default_recipient: Option<String>,
+
+    /// File listing the bearer tokens of recipients.
+    ///
+    /// Requests with an unknown or expired token are rejected. Bearer tokens are not
+    /// checked if not set.
+    #[arg(long)]
+    tokens: Option<String>,
+
+    /// Requests per second each recipient may issue on average.
+    ///
+    /// Requests are not rate limited if not set.
+    #[arg(long)]
+    rate_limit: Option<f64>,
+
+    /// Requests each recipient may issue in a single burst when rate limited.
+    #[arg(long, default_value_t = 10)]
+    rate_limit_burst: u32,
 }
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Cli::parse();
 
-    let config = std::fs::read_to_string(args.config)?;
-    let config = serde_yml::from_str::<InMemoryConfig>(&config)?;
-    let discovery = Arc::new(InMemoryHandler::new(config));
+    let loader = ConfigLoader::new(&args.config)
+        .strict(args.strict_config)
+        .reference(args.reference_config);
+    let config = loader.load()?;
+    let health = Arc::new(CatalogHealth::new(config.shares.len()));
+    let policy = Arc::new(ConstantPolicy::<DeltaRecipient>::default());
+    let discovery = Arc::new(InMemoryHandler::new(config).with_policy(policy.clone()));
+    if args.validate_storage {
+        discovery.validate_storage().await?;
+    }
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(discovery.clone(), health.clone(), loader));
+    // queries are rejected while the maintenance mode is enabled, listings are still served.
+    let maintenance = MaintenanceMode::new();
+    let query = KernelQueryHandler::new_multi_thread(discovery.clone(), Default::default());
     let state = DeltaSharingState {
-        query: KernelQueryHandler::new_multi_thread(discovery.clone(), Default::default()),
+        query: Arc::new(MaintenanceHandler::new(query, maintenance.clone())),
         discovery,
-        policy: Arc::new(ConstantPolicy::<DeltaRecipient>::default()),
+        policy,
     };
 
     let listener = TcpListener::bind(format!("{}:{}", args.host, args.port)).await?;
-    let server = get_router(state)
-        .layer(AuthorizationLayer::new(AnonymousAuthenticator))
-        .layer(TraceLayer::new_for_http());
+    let router = get_router(state).merge(get_maintenance_router(maintenance));
+    // the limiter is charged once per request, after the recipient is authenticated.
+    let router = match args.rate_limit {
+        Some(requests_per_second) => router.layer(RateLimitLayer::new(RateLimiter::new(
+            RateLimitConfig::new(requests_per_second, args.rate_limit_burst),
+        ))),
+        None => router,
+    };
+    let router = match &args.tokens {
+        Some(path) => {
+            let tokens: TokensConfig = ConfigLoader::new(path)
+                .strict(args.strict_config)
+                .load_as()?;
+            let authenticator = TokenAuthenticator::new(tokens);
+            authenticated(router, authenticator, args.default_recipient)
+        }
+        None => authenticated(router, AnonymousAuthenticator, args.default_recipient),
+    };
+    // health checks are served without authentication, so orchestrators can probe them.
+    let server = router.merge(get_health_router(health));
+    let server = with_request_id(server);
     axum::serve(listener, server)
         .with_graceful_shutdown(shutdown_signal())
         .await?;
@@ -52,6 +136,275 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Authenticate requests to `router` with `authenticator`.
+///
+/// Requests without a bearer token are assigned the recipient called `default_recipient`.
+fn authenticated<T>(router: Router, authenticator: T, default_recipient: Option<String>) -> Router
+where
+    T: Authenticator<Request = Request, Recipient = DeltaRecipient> + Clone + 'static,
+{
+    let authenticator = DefaultRecipientAuthenticator::named(authenticator, default_recipient);
+    #[cfg(all(feature = "test-auth", debug_assertions))]
+    let authenticator = self::auth::TestRecipientAuthenticator::new(authenticator);
+    router.layer(AuthorizationLayer::new(authenticator))
+}
+
+/// Byte order mark that some editors prepend to UTF-8 encoded files.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Format of a configuration file, detected from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileFormat {
+    Json,
+    /// One JSON object per line, each describing a table of the catalog.
+    Ndjson,
+    Toml,
+    Yaml,
+}
+
+impl FileFormat {
+    fn detect(path: &str) -> Self {
+        match path.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("json") => Self::Json,
+            Some("ndjson") => Self::Ndjson,
+            Some("toml") => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Loads the in-memory configuration from a file.
+///
+/// Files with a `.toml` extension are parsed as TOML, files with a `.json` extension as JSON,
+/// all other files as YAML. Files must be UTF-8 encoded, a leading byte order mark is ignored.
+///
+/// Catalogs may also list one table per line in a file with a `.ndjson` extension, see
+/// [`ConfigLoader::load_ndjson`].
+///
+/// JSON files are deserialized directly from the file reader instead of being read into a
+/// string first, TOML and YAML files are read into memory as a whole.
+///
+/// There is no lazy load mode: every format is deserialized into a complete [`InMemoryConfig`],
+/// since the [`InMemoryHandler`] indexes all shares of the catalog when it is loaded. Peak
+/// memory while loading therefore grows with the size of the catalog.
+///
+/// Files in the format of the reference server are read as a [`ReferenceServerConfig`].
+///
+/// Relative paths are resolved against the working directory when the loader is created, so
+/// reloads read the same file. Symlinks are followed on every load, so swapping the target
+/// of a symlinked file takes effect on the next reload.
+#[derive(Debug, Clone)]
+struct ConfigLoader {
+    /// Path of the file as given, which is used in messages.
+    path: String,
+    absolute_path: PathBuf,
+    strict: bool,
+    reference: bool,
+}
+
+impl ConfigLoader {
+    fn new(path: impl Into<String>) -> Self {
+        let path = path.into();
+        let absolute_path = std::path::absolute(&path).unwrap_or_else(|_| PathBuf::from(&path));
+        Self {
+            path,
+            absolute_path,
+            strict: false,
+            reference: false,
+        }
+    }
+
+    /// Resolve the file to load, following symlinks.
+    fn resolve(&self) -> Result<PathBuf, CoreError> {
+        let resolved = std::fs::canonicalize(&self.absolute_path).map_err(|e| {
+            CoreError::invalid_config(format!("could not resolve '{}'", self.path), e)
+        })?;
+        tracing::debug!("resolved '{}' to '{}'", self.path, resolved.display());
+        Ok(resolved)
+    }
+
+    /// Reject configuration files containing unknown fields.
+    ///
+    /// By default unknown fields are ignored with a warning.
+    fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Read the file in the format of the reference Delta Sharing server.
+    fn reference(mut self, reference: bool) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    fn load(&self) -> Result<InMemoryConfig, CoreError> {
+        let config: InMemoryConfig = if self.reference {
+            self.load_as::<ReferenceServerConfig>()?.try_into()?
+        } else if FileFormat::detect(&self.path) == FileFormat::Ndjson {
+            let file = std::fs::File::open(self.resolve()?).map_err(|e| {
+                CoreError::invalid_config(format!("could not read '{}'", self.path), e)
+            })?;
+            self.load_ndjson(std::io::BufReader::new(file))?
+        } else {
+            self.load_as()?
+        };
+        config.validate()?;
+        for warning in config.lint() {
+            tracing::warn!("{} in '{}'", warning, self.path);
+        }
+        Ok(config)
+    }
+
+    /// Load the file as any kind of configuration, e.g. the [`TokensConfig`] of recipients.
+    fn load_as<C: DeserializeOwned>(&self) -> Result<C, CoreError> {
+        let path = &self.path;
+        let read_error = |e| CoreError::invalid_config(format!("could not read '{}'", path), e);
+        let format = FileFormat::detect(path);
+        match format {
+            FileFormat::Json => {
+                let file = std::fs::File::open(self.resolve()?).map_err(read_error)?;
+                return self.load_json(std::io::BufReader::new(file));
+            }
+            FileFormat::Ndjson => {
+                return Err(self.parse_error("only catalogs may be listed line by line".into()));
+            }
+            FileFormat::Toml | FileFormat::Yaml => {}
+        }
+        let bytes = std::fs::read(self.resolve()?).map_err(read_error)?;
+        let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(&bytes);
+        let config = std::str::from_utf8(bytes)
+            .map_err(|e| CoreError::invalid_config(format!("'{}' is not valid UTF-8", path), e))?;
+        let mut unknown = Vec::new();
+        let on_unknown = |field: serde_ignored::Path<'_>| unknown.push(field.to_string());
+        let config = if format == FileFormat::Toml {
+            serde_ignored::deserialize(toml::Deserializer::new(config), on_unknown)
+                .map_err(|e| self.parse_error(e.into()))?
+        } else {
+            serde_ignored::deserialize(serde_yml::Deserializer::from_str(config), on_unknown)
+                .map_err(|e| self.parse_error(e.into()))?
+        };
+        self.check_unknown(unknown)?;
+        Ok(config)
+    }
+
+    /// Deserialize a JSON configuration directly from `reader`.
+    fn load_json<C: DeserializeOwned>(
+        &self,
+        mut reader: impl std::io::BufRead,
+    ) -> Result<C, CoreError> {
+        let has_bom = reader
+            .fill_buf()
+            .map_err(|e| CoreError::invalid_config(format!("could not read '{}'", self.path), e))?
+            .starts_with(UTF8_BOM);
+        if has_bom {
+            reader.consume(UTF8_BOM.len());
+        }
+        let mut unknown = Vec::new();
+        let on_unknown = |field: serde_ignored::Path<'_>| unknown.push(field.to_string());
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let config = serde_ignored::deserialize(&mut deserializer, on_unknown)
+            .and_then(|config| deserializer.end().map(|_| config))
+            .map_err(|e| self.parse_error(e.into()))?;
+        self.check_unknown(unknown)?;
+        Ok(config)
+    }
+
+    /// Read a catalog listing one table per line from `reader`.
+    ///
+    /// Each line is a JSON object with the `share` and `schema` of the table, its name as
+    /// `table` and the other settings of a table, e.g. `location`. Blank lines are skipped.
+    fn load_ndjson(&self, reader: impl std::io::BufRead) -> Result<InMemoryConfig, CoreError> {
+        let mut unknown = Vec::new();
+        let mut records = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| {
+                CoreError::invalid_config(format!("could not read '{}'", self.path), e)
+            })?;
+            let line = line.strip_prefix('\u{feff}').unwrap_or(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line_error = |message: String| {
+                self.parse_error(format!("line {}: {}", index + 1, message).into())
+            };
+            let mut record = serde_json::from_str::<serde_json::Map<_, _>>(line)
+                .map_err(|e| line_error(e.to_string()))?;
+            let mut field = |key: &str| match record.remove(key) {
+                Some(serde_json::Value::String(value)) => Ok(value),
+                Some(_) => Err(line_error(format!("'{}' must be a string", key))),
+                None => Err(line_error(format!("missing field '{}'", key))),
+            };
+            let (share, schema, name) = (field("share")?, field("schema")?, field("table")?);
+            if record.contains_key("name") {
+                return Err(line_error("the table is named by 'table'".into()));
+            }
+            record.insert("name".into(), name.into());
+            let on_unknown = |field: serde_ignored::Path<'_>| {
+                unknown.push(format!("{} (line {})", field, index + 1))
+            };
+            let table: TableConfig =
+                serde_ignored::deserialize(serde_json::Value::Object(record), on_unknown)
+                    .map_err(|e| line_error(e.to_string()))?;
+            records.push(TableRecord {
+                share,
+                schema,
+                table,
+            });
+        }
+        self.check_unknown(unknown)?;
+        InMemoryConfig::from_records(records)
+    }
+
+    fn parse_error(&self, e: Box<dyn std::error::Error + Send + Sync>) -> CoreError {
+        CoreError::invalid_config(format!("could not parse '{}'", self.path), e)
+    }
+
+    /// Report unknown fields, rejecting the configuration in strict mode.
+    fn check_unknown(&self, unknown: Vec<String>) -> Result<(), CoreError> {
+        let path = &self.path;
+        if let Some(field) = unknown.first() {
+            if self.strict {
+                return Err(self.parse_error(format!("unknown field '{}'", field).into()));
+            }
+            for field in &unknown {
+                tracing::warn!("ignoring unknown field '{}' in '{}'", field, path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reload the configuration of `handler` from the file, recording the outcome in `health`.
+    ///
+    /// If the file cannot be loaded, the handler keeps serving the previous configuration.
+    fn reload<T: Send + Sync>(
+        &self,
+        handler: &InMemoryHandler<T>,
+        health: &CatalogHealth,
+    ) -> Result<(), CoreError> {
+        let config = self.load().inspect_err(|_| health.failed())?;
+        health.loaded(config.shares.len());
+        handler.reload(config);
+        Ok(())
+    }
+}
+
+/// Reload the configuration whenever the process receives `SIGHUP`.
+#[cfg(unix)]
+async fn reload_on_hangup<T: Send + Sync>(
+    handler: Arc<InMemoryHandler<T>>,
+    health: Arc<CatalogHealth>,
+    loader: ConfigLoader,
+) {
+    let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())
+        .expect("failed to install signal handler");
+    while hangup.recv().await.is_some() {
+        match loader.reload(&handler, &health) {
+            Ok(()) => tracing::info!("reloaded configuration from '{}'", loader.path),
+            Err(e) => tracing::error!("failed to reload configuration: {}", e),
+        }
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -80,26 +433,415 @@ async fn shutdown_signal() {
 mod tests {
     use delta_sharing_core::{
         DefaultInMemoryHandler, InMemoryConfig, SchemaConfig, ShareConfig, TableConfig,
+        TableFormat, TableKind,
     };
 
+    use crate::health::HealthStatus;
+
     pub(crate) fn test_config() -> InMemoryConfig {
         InMemoryConfig {
             shares: vec![ShareConfig {
                 name: "share1".to_string(),
                 schema_refs: vec!["schema1".to_string()],
+                properties: Default::default(),
+                default_schema: None,
+                created_at: None,
             }],
             schemas: vec![SchemaConfig {
                 name: "schema1".to_string(),
                 table_refs: vec!["table1".to_string()],
+                created_at: None,
             }],
             tables: vec![TableConfig {
+                id: None,
                 name: "table1".to_string(),
+                aliases: vec![],
                 location: "file:///tmp".to_string(),
+                format: TableFormat::Parquet,
+                kind: TableKind::Table,
+                partition_columns: vec![],
+                last_updated: None,
+                created_at: None,
+                properties: Default::default(),
             }],
+            storage_root: None,
+            storage_options: Default::default(),
         }
     }
 
     pub(crate) fn test_handler() -> DefaultInMemoryHandler {
         DefaultInMemoryHandler::new(test_config())
     }
+
+    #[test]
+    fn test_load_config_malformed() {
+        let path = std::env::temp_dir().join(format!("malformed-{}.yaml", std::process::id()));
+        std::fs::write(&path, "shares: [").unwrap();
+
+        let err = super::ConfigLoader::new(path.to_str().unwrap())
+            .load()
+            .unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(err.to_string().contains("could not parse"));
+        let source = std::error::Error::source(&err).expect("error should have a source");
+        assert!(source.downcast_ref::<serde_yml::Error>().is_some());
+    }
+
+    #[test]
+    fn test_load_config_bom() {
+        for extension in ["yaml", "toml"] {
+            let path =
+                std::env::temp_dir().join(format!("bom-{}.{}", std::process::id(), extension));
+            let config = match extension {
+                "toml" => "[[shares]]\nname = \"share1\"\nschemaRefs = []\n",
+                _ => "shares:\n  - name: share1\n    schemaRefs: []\n",
+            };
+            std::fs::write(&path, [super::UTF8_BOM, config.as_bytes()].concat()).unwrap();
+
+            let config = super::ConfigLoader::new(path.to_str().unwrap()).load();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(config.unwrap().shares[0].name, "share1");
+        }
+    }
+
+    #[test]
+    fn test_load_config_not_utf8() {
+        let path = std::env::temp_dir().join(format!("latin1-{}.yaml", std::process::id()));
+        // "shares: [café]" encoded as latin-1.
+        std::fs::write(&path, b"shares: [caf\xE9]\n").unwrap();
+
+        let err = super::ConfigLoader::new(path.to_str().unwrap())
+            .load()
+            .unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(err.to_string().contains("is not valid UTF-8"));
+        let source = std::error::Error::source(&err).expect("error should have a source");
+        assert!(source.downcast_ref::<std::str::Utf8Error>().is_some());
+    }
+
+    #[test]
+    fn test_load_config_toml() {
+        let path = std::env::temp_dir().join(format!("config-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+[[shares]]
+name = "share1"
+schemaRefs = ["schema1"]
+
+[[schemas]]
+name = "schema1"
+tableRefs = ["table1", "table2"]
+
+[[tables]]
+name = "table1"
+location = "file:///tmp/table1"
+
+[[tables]]
+name = "table2"
+location = "file:///tmp/table2"
+format = "delta"
+"#,
+        )
+        .unwrap();
+
+        let config = super::ConfigLoader::new(path.to_str().unwrap())
+            .load()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.shares.len(), 1);
+        assert_eq!(config.shares[0].schema_refs, vec!["schema1"]);
+        assert_eq!(config.schemas.len(), 1);
+        assert_eq!(config.schemas[0].table_refs, vec!["table1", "table2"]);
+        assert_eq!(config.tables.len(), 2);
+        assert_eq!(config.tables[1].location, "file:///tmp/table2");
+        assert_eq!(config.tables[1].format, TableFormat::Delta);
+    }
+
+    #[tokio::test]
+    async fn test_reload_config() {
+        use delta_sharing_core::types as t;
+        use delta_sharing_core::{DeltaRecipient, DiscoveryHandler};
+
+        let path = std::env::temp_dir().join(format!("reload-{}.yaml", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(&path, "shares:\n  - name: share1\n    schemaRefs: []\n").unwrap();
+        let handler =
+            DefaultInMemoryHandler::new(super::ConfigLoader::new(path_str).load().unwrap());
+        let health = super::CatalogHealth::new(1);
+
+        std::fs::write(
+            &path,
+            "shares:\n  - name: share1\n    schemaRefs: []\n  - name: share2\n    schemaRefs: []\n",
+        )
+        .unwrap();
+        super::ConfigLoader::new(path_str)
+            .reload(&handler, &health)
+            .unwrap();
+        assert_eq!(health.health().share_count, 2);
+
+        // a broken config leaves the previously loaded shares in place.
+        std::fs::write(&path, "shares: [").unwrap();
+        assert!(super::ConfigLoader::new(path_str)
+            .reload(&handler, &health)
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(health.health().status, HealthStatus::Stale);
+        assert_eq!(health.health().share_count, 2);
+
+        let shares = handler
+            .list_shares(t::ListSharesRequest::default(), DeltaRecipient::Anonymous)
+            .await
+            .unwrap();
+        let names = shares
+            .items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share1", "share2"]);
+    }
+
+    #[test]
+    fn test_load_config_json() {
+        let path = std::env::temp_dir().join(format!("large-{}.json", std::process::id()));
+        let shares = (0..10_000)
+            .map(|i| {
+                serde_json::json!({
+                    "name": format!("share{}", i),
+                    "schemaRefs": [format!("schema{}", i)],
+                })
+            })
+            .collect::<Vec<_>>();
+        let schemas = (0..10_000)
+            .map(|i| {
+                serde_json::json!({
+                    "name": format!("schema{}", i),
+                    "tableRefs": [format!("table{}", i)],
+                })
+            })
+            .collect::<Vec<_>>();
+        let tables = (0..10_000)
+            .map(|i| {
+                serde_json::json!({
+                    "name": format!("table{}", i),
+                    "location": format!("file:///tmp/table{}", i),
+                })
+            })
+            .collect::<Vec<_>>();
+        let config = serde_json::json!({ "shares": shares, "schemas": schemas, "tables": tables });
+        let file = std::fs::File::create(&path).unwrap();
+        serde_json::to_writer(std::io::BufWriter::new(file), &config).unwrap();
+
+        let config = super::ConfigLoader::new(path.to_str().unwrap())
+            .load()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.shares.len(), 10_000);
+        assert_eq!(config.schemas.len(), 10_000);
+        assert_eq!(config.tables.len(), 10_000);
+        assert_eq!(config.shares[9_999].name, "share9999");
+        assert_eq!(config.schemas[42].table_refs, vec!["table42"]);
+        assert_eq!(config.tables[7].location, "file:///tmp/table7");
+    }
+
+    #[test]
+    fn test_load_config_json_bom() {
+        let config = [
+            super::UTF8_BOM,
+            br#"{"shares": [{"name": "share1", "schemaRefs": []}]}"#,
+        ]
+        .concat();
+        let config = super::ConfigLoader::new("config.json")
+            .load_json::<InMemoryConfig>(config.as_slice())
+            .unwrap();
+        assert_eq!(config.shares[0].name, "share1");
+
+        let err = super::ConfigLoader::new("config.json")
+            .load_json::<InMemoryConfig>(br#"{"shares": []} trailing"#.as_slice())
+            .unwrap_err();
+        assert!(err.to_string().contains("could not parse"));
+    }
+
+    #[test]
+    fn test_load_config_ndjson() {
+        let path = std::env::temp_dir().join(format!("catalog-{}.ndjson", std::process::id()));
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"share": "share1", "schema": "schema1", "table": "table1", "location": "file:///tmp/table1"}"#,
+                "\n",
+                r#"{"share": "share1", "schema": "schema1", "table": "table2", "location": "file:///tmp/table2", "partitionColumns": ["date"]}"#,
+                "\n\n",
+                r#"{"share": "share2", "schema": "schema2", "table": "table1", "location": "file:///tmp/table1"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let config = super::ConfigLoader::new(path.to_str().unwrap())
+            .load()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let shares = config
+            .shares
+            .iter()
+            .map(|share| (share.name.as_str(), share.schema_refs.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shares,
+            vec![
+                ("share1", vec!["schema1".to_string()]),
+                ("share2", vec!["schema2".to_string()]),
+            ]
+        );
+        assert_eq!(config.schemas[0].table_refs, vec!["table1", "table2"]);
+        assert_eq!(config.schemas[1].table_refs, vec!["table1"]);
+        assert_eq!(config.tables.len(), 2);
+        assert_eq!(config.tables[1].partition_columns, vec!["date"]);
+
+        let err = super::ConfigLoader::new("catalog.ndjson")
+            .load_ndjson(r#"{"share": "share1", "table": "table1"}"#.as_bytes())
+            .unwrap_err();
+        assert!(err.to_string().contains("could not parse"));
+        let err = super::ConfigLoader::new("tokens.ndjson")
+            .load_as::<super::TokensConfig>()
+            .unwrap_err();
+        assert!(err.to_string().contains("could not parse"));
+    }
+
+    #[test]
+    fn test_load_config_relative_path() {
+        let path = std::env::temp_dir()
+            .canonicalize()
+            .unwrap()
+            .join(format!("relative-{}.yaml", std::process::id()));
+        std::fs::write(&path, "shares:\n  - name: share1\n    schemaRefs: []\n").unwrap();
+        // a path relative to the working directory, climbing up to the root first.
+        let cwd = std::env::current_dir().unwrap();
+        let relative = cwd
+            .components()
+            .skip(1)
+            .map(|_| "..")
+            .collect::<std::path::PathBuf>()
+            .join(path.strip_prefix("/").unwrap());
+
+        let loader = super::ConfigLoader::new(relative.to_str().unwrap());
+        assert_eq!(loader.path, relative.to_str().unwrap());
+        assert_eq!(loader.resolve().unwrap(), path);
+        let config = loader.load().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.shares[0].name, "share1");
+
+        let err = loader.load().unwrap_err();
+        assert!(err.to_string().contains("could not resolve"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_config_symlink() {
+        let dir = std::env::temp_dir()
+            .canonicalize()
+            .unwrap()
+            .join(format!("symlink-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for share in ["share1", "share2"] {
+            std::fs::write(
+                dir.join(format!("{}.yaml", share)),
+                format!("shares:\n  - name: {}\n    schemaRefs: []\n", share),
+            )
+            .unwrap();
+        }
+        let link = dir.join("config.yaml");
+        std::os::unix::fs::symlink(dir.join("share1.yaml"), &link).unwrap();
+
+        let loader = super::ConfigLoader::new(link.to_str().unwrap());
+        assert_eq!(loader.resolve().unwrap(), dir.join("share1.yaml"));
+        assert_eq!(loader.load().unwrap().shares[0].name, "share1");
+
+        // the target of the symlink is swapped, e.g. when a mounted config map is updated.
+        std::fs::remove_file(&link).unwrap();
+        std::os::unix::fs::symlink(dir.join("share2.yaml"), &link).unwrap();
+        assert_eq!(loader.resolve().unwrap(), dir.join("share2.yaml"));
+        assert_eq!(loader.load().unwrap().shares[0].name, "share2");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_config_strict() {
+        let path = std::env::temp_dir().join(format!("strict-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "shares:\n  - name: share1\n    schemaRefs: []\n    recipeints: [all]\n",
+        )
+        .unwrap();
+        let loader = super::ConfigLoader::new(path.to_str().unwrap());
+
+        let lenient = loader.load();
+        let strict = loader.clone().strict(true).load();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lenient.unwrap().shares[0].name, "share1");
+        let err = strict.unwrap_err();
+        let source = std::error::Error::source(&err).expect("error should have a source");
+        assert_eq!(source.to_string(), "unknown field 'shares.0.recipeints'");
+    }
+
+    #[test]
+    fn test_load_reference_config() {
+        let path = std::env::temp_dir().join(format!("reference-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+version: 1
+shares:
+- name: "share1"
+  schemas:
+  - name: "schema1"
+    tables:
+    - name: "table1"
+      location: "s3a://bucket/table1"
+      id: "00000000-0000-0000-0000-000000000000"
+      cdfEnabled: true
+    - name: "table2"
+      storage_location: "s3a://bucket/table2"
+      history_shared: true
+      cdf_enabled: true
+- name: "share2"
+  schemas:
+  - name: "schema2"
+    tables: []
+authorization:
+  bearerToken: "token"
+host: "localhost"
+port: 8080
+endpoint: "/delta-sharing"
+preSignedUrlTimeoutSeconds: 3600
+"#,
+        )
+        .unwrap();
+        let config = super::ConfigLoader::new(path.to_str().unwrap())
+            .strict(true)
+            .reference(true)
+            .load();
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        let shares = config.shares.iter().map(|s| &s.name).collect::<Vec<_>>();
+        assert_eq!(shares, vec!["share1", "share2"]);
+        assert_eq!(config.shares[0].schema_refs, vec!["schema1"]);
+        assert_eq!(config.schemas[0].table_refs, vec!["table1", "table2"]);
+        assert!(config.schemas[1].table_refs.is_empty());
+        assert_eq!(config.tables[0].location, "s3a://bucket/table1");
+        assert_eq!(
+            config.tables[0].id.as_deref(),
+            Some("00000000-0000-0000-0000-000000000000")
+        );
+        assert_eq!(config.tables[1].name, "table2");
+        assert_eq!(config.tables[1].location, "s3a://bucket/table2");
+    }
 }
@@ -0,0 +1,118 @@
+//! Admin endpoint switching the server into read-only maintenance mode.
+
+use axum::extract::{Extension, State};
+use axum::{routing::get, Json, Router};
+use delta_sharing_core::{DeltaRecipient, Error as CoreError, MaintenanceMode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Whether the server is in maintenance mode.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Maintenance {
+    pub enabled: bool,
+}
+
+fn check_admin(recipient: &DeltaRecipient) -> Result<()> {
+    if !recipient.is_admin() {
+        return Err(CoreError::NotAllowed.into());
+    }
+    Ok(())
+}
+
+async fn get_maintenance(
+    State(mode): State<MaintenanceMode>,
+    Extension(recipient): Extension<DeltaRecipient>,
+) -> Result<Json<Maintenance>> {
+    check_admin(&recipient)?;
+    Ok(Json(Maintenance {
+        enabled: mode.is_enabled(),
+    }))
+}
+
+async fn put_maintenance(
+    State(mode): State<MaintenanceMode>,
+    Extension(recipient): Extension<DeltaRecipient>,
+    Json(maintenance): Json<Maintenance>,
+) -> Result<Json<Maintenance>> {
+    check_admin(&recipient)?;
+    mode.set_enabled(maintenance.enabled);
+    tracing::info!("maintenance mode enabled: {}", maintenance.enabled);
+    Ok(Json(maintenance))
+}
+
+/// Router serving the maintenance mode at `/admin/maintenance`.
+///
+/// `GET` reports whether the maintenance mode is enabled, `PUT` with a body like
+/// `{"enabled": true}` switches it. Both are restricted to admin recipients, so the router
+/// must be served behind authentication.
+pub fn get_maintenance_router(mode: MaintenanceMode) -> Router {
+    Router::new()
+        .route(
+            "/admin/maintenance",
+            get(get_maintenance).put(put_maintenance),
+        )
+        .with_state(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use delta_sharing_core::DefaultClaims;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn recipient(admin: bool) -> DeltaRecipient {
+        DeltaRecipient::Profile(DefaultClaims {
+            email: "operator@example.com".to_string(),
+            issued_at: 0,
+            shares: vec![],
+            expiration: None,
+            admin: Some(admin),
+        })
+    }
+
+    async fn put(mode: &MaintenanceMode, recipient: DeltaRecipient, enabled: bool) -> StatusCode {
+        let request = Request::put("/admin/maintenance")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(format!(r#"{{"enabled": {}}}"#, enabled)))
+            .unwrap();
+        get_maintenance_router(mode.clone())
+            .layer(Extension(recipient))
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_toggle_maintenance() {
+        let mode = MaintenanceMode::new();
+
+        assert_eq!(put(&mode, recipient(true), true).await, StatusCode::OK);
+        assert!(mode.is_enabled());
+        let request = Request::get("/admin/maintenance")
+            .body(Body::empty())
+            .unwrap();
+        let response = get_maintenance_router(mode.clone())
+            .layer(Extension(recipient(true)))
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(put(&mode, recipient(true), false).await, StatusCode::OK);
+        assert!(!mode.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_toggle_maintenance_requires_admin() {
+        let mode = MaintenanceMode::new();
+        for recipient in [recipient(false), DeltaRecipient::Anonymous] {
+            assert_eq!(put(&mode, recipient, true).await, StatusCode::FORBIDDEN);
+        }
+        assert!(!mode.is_enabled());
+    }
+}
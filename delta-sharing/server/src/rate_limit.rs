@@ -0,0 +1,126 @@
+//! Per-recipient rate limiting of requests.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::extract::Request;
+use axum::response::{IntoResponse, Response};
+use delta_sharing_core::policies::RateLimiter;
+use delta_sharing_core::DeltaRecipient;
+use futures_util::{future::BoxFuture, FutureExt};
+use tower::{Layer, Service};
+
+use crate::error::Error;
+
+/// Middleware that charges the [`RateLimiter`] once for every request.
+///
+/// The recipient is taken from the request extensions, so the middleware must be applied
+/// within the [`AuthorizationLayer`](crate::auth::AuthorizationLayer).
+#[derive(Clone)]
+pub struct RateLimitMiddleware<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request> for RateLimitMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let recipient = req
+            .extensions()
+            .get::<DeltaRecipient>()
+            .unwrap_or(&DeltaRecipient::Anonymous);
+        match self.limiter.check(recipient) {
+            Ok(()) => self.inner.call(req).boxed(),
+            Err(e) => async { Ok(Error::from(e).into_response()) }.boxed(),
+        }
+    }
+}
+
+/// Layer that applies the [`RateLimitMiddleware`].
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    /// Create a new [`RateLimitLayer`] charging `limiter`.
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self {
+            limiter: Arc::new(limiter),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitMiddleware {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::Router;
+    use delta_sharing_core::policies::{ConstantPolicy, RateLimitConfig};
+    use delta_sharing_core::{DefaultInMemoryHandler, KernelQueryHandler, ShareConfig};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::auth::{AnonymousAuthenticator, AuthorizationLayer};
+    use crate::server::{get_router, DeltaSharingState};
+    use crate::tests::test_config;
+
+    fn get_app(burst: u32) -> Router {
+        let mut config = test_config();
+        for name in ["share2", "share3", "share4"] {
+            config
+                .shares
+                .push(ShareConfig::new(name, Vec::<String>::new()));
+        }
+        let discovery = Arc::new(DefaultInMemoryHandler::new(config));
+        let state = DeltaSharingState {
+            query: KernelQueryHandler::new_background(discovery.clone(), Default::default()),
+            discovery,
+            policy: Arc::new(ConstantPolicy::<DeltaRecipient>::default()),
+        };
+        let limiter = RateLimiter::new(RateLimitConfig::new(0.001, burst));
+        get_router(state)
+            .layer(RateLimitLayer::new(limiter))
+            .layer(AuthorizationLayer::new(AnonymousAuthenticator))
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_per_request() {
+        let app = get_app(2);
+        for _ in 0..2 {
+            let request = Request::get("/shares").body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            // listing more shares than the burst is charged as a single request.
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+            assert_eq!(result["items"].as_array().unwrap().len(), 4);
+        }
+        let request = Request::get("/shares").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,85 @@
+//! Request ids correlating client reports with server logs.
+
+use axum::body::Body;
+use axum::http::{HeaderName, Request};
+use axum::Router;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::TraceLayer;
+
+/// Header carrying the id of a request.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Trace requests to `router`, tagged with their request id.
+///
+/// Requests without an [`REQUEST_ID_HEADER`] are assigned a random UUID. The id is recorded
+/// on the tracing span of the request and echoed on every response, including errors.
+pub fn with_request_id(router: Router) -> Router {
+    let trace = TraceLayer::new_for_http().make_span_with(|request: &Request<Body>| {
+        let request_id = request
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %request.uri(),
+            request_id,
+        )
+    });
+    router
+        .layer(trace)
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn get_app() -> Router {
+        with_request_id(
+            Router::new()
+                .route("/ok", get(|| async { StatusCode::OK }))
+                .route("/error", get(|| async { StatusCode::NOT_FOUND })),
+        )
+    }
+
+    async fn request_id(uri: &str, request_id: Option<&str>) -> (StatusCode, Option<String>) {
+        let mut request = Request::builder().uri(uri);
+        if let Some(request_id) = request_id {
+            request = request.header(&REQUEST_ID_HEADER, request_id);
+        }
+        let response = get_app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let request_id = response
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .map(|value| value.to_str().unwrap().to_string());
+        (response.status(), request_id)
+    }
+
+    #[tokio::test]
+    async fn test_request_id_echoed() {
+        for (uri, status) in [("/ok", StatusCode::OK), ("/error", StatusCode::NOT_FOUND)] {
+            let response = request_id(uri, Some("client-id-1")).await;
+            assert_eq!(response, (status, Some("client-id-1".to_string())));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated() {
+        let (_, first) = request_id("/ok", None).await;
+        let (_, second) = request_id("/error", None).await;
+        let first = first.expect("request id should be generated");
+        let second = second.expect("request id should be generated");
+        assert_eq!(first.len(), 36);
+        assert_ne!(first, second);
+    }
+}
@@ -1,14 +1,18 @@
 use std::sync::Arc;
 
 use axum::extract::{Extension, Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
 use axum::{routing::get, Json, Router};
 use delta_sharing_core::types as t;
 use delta_sharing_core::{
-    Decision, DiscoveryHandler, Error as CoreError, Permission, Policy, Resource, TableQueryHandler,
+    Decision, DiscoveryHandler, Error as CoreError, Limit, Pagination as CorePagination,
+    Permission, Policy, Resource, TableQueryHandler,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
+use crate::extractors::{ResponseMode, NDJSON_CONTENT_TYPE};
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +21,95 @@ struct Pagination {
     page_token: Option<String>,
 }
 
+impl Pagination {
+    /// Reject malformed pagination parameters before calling into the handlers.
+    fn validate(&self) -> Result<()> {
+        CorePagination::try_new(self.max_results, self.page_token.clone())?;
+        Ok(())
+    }
+}
+
+/// A list response of the discovery endpoints.
+trait ListResponse: Serialize {
+    type Item: Serialize;
+
+    fn items(&self) -> &[Self::Item];
+
+    fn next_page_token(&self) -> Option<&str>;
+
+    fn next_page_token_mut(&mut self) -> &mut Option<String>;
+}
+
+macro_rules! impl_list_response {
+    ($response:ty, $item:ty) => {
+        impl ListResponse for $response {
+            type Item = $item;
+
+            fn items(&self) -> &[Self::Item] {
+                &self.items
+            }
+
+            fn next_page_token(&self) -> Option<&str> {
+                self.next_page_token.as_deref()
+            }
+
+            fn next_page_token_mut(&mut self) -> &mut Option<String> {
+                &mut self.next_page_token
+            }
+        }
+    };
+}
+
+impl_list_response!(t::ListSharesResponse, t::Share);
+impl_list_response!(t::ListSchemasResponse, t::Schema);
+impl_list_response!(t::ListShareTablesResponse, t::Table);
+impl_list_response!(t::ListSchemaTablesResponse, t::Table);
+
+/// Serializes a list response in the [`ResponseMode`] requested by the client.
+///
+/// JSON responses are objects of the form `{ "items": [...], "nextPageToken": "..." }`.
+/// NDJSON responses contain one item per line, followed by a line with the `nextPageToken`
+/// if there are more pages. The `nextPageToken` is omitted on the last page, handlers
+/// reporting an empty token are treated as having no more pages.
+struct Listing<R> {
+    mode: ResponseMode,
+    response: R,
+}
+
+impl<R: ListResponse> IntoResponse for Listing<R> {
+    fn into_response(mut self) -> Response {
+        let token = self.response.next_page_token_mut();
+        if token.as_deref() == Some("") {
+            *token = None;
+        }
+        match self.mode {
+            ResponseMode::Json => Json(self.response).into_response(),
+            ResponseMode::NdJson => {
+                let mut body = Vec::new();
+                let lines = self
+                    .response
+                    .items()
+                    .iter()
+                    .map(serde_json::to_value)
+                    .chain(
+                        self.response
+                            .next_page_token()
+                            .map(|token| Ok(serde_json::json!({ "nextPageToken": token }))),
+                    );
+                for line in lines {
+                    let written = line.and_then(|line| serde_json::to_writer(&mut body, &line));
+                    if let Err(e) = written {
+                        let error = CoreError::Generic(format!("Failed to serialize item: {}", e));
+                        return crate::error::Error::from(error).into_response();
+                    }
+                    body.push(b'\n');
+                }
+                ([(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)], body).into_response()
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DeltaSharingState<T: Send + Sync> {
     pub discovery: Arc<dyn DiscoveryHandler<Recipient = T>>,
@@ -24,17 +117,54 @@ pub struct DeltaSharingState<T: Send + Sync> {
     pub policy: Arc<dyn Policy<Recipient = T>>,
 }
 
-async fn list_shares<T: Send + Sync>(
+async fn list_shares<T: Send + Sync + Clone>(
     State(state): State<DeltaSharingState<T>>,
     Extension(recipient): Extension<T>,
+    mode: ResponseMode,
     pagination: Query<Pagination>,
-) -> Result<Json<t::ListSharesResponse>> {
-    let request = t::ListSharesRequest {
-        max_results: pagination.0.max_results,
-        page_token: pagination.0.page_token,
+) -> Result<Listing<t::ListSharesResponse>> {
+    pagination.validate()?;
+    let max_results = pagination
+        .0
+        .max_results
+        .map(Limit::max_results)
+        .transpose()?
+        .map(|limit| limit.get());
+    // only return the shares the recipient is allowed to read. Shares are filtered before
+    // pagination: further pages are requested for the remaining number of shares until the
+    // page is full, so the next page token resumes right after the last returned share.
+    let mut items = Vec::new();
+    let mut page_token = pagination.0.page_token;
+    loop {
+        let request = t::ListSharesRequest {
+            max_results: max_results.map(|max| (max - items.len()) as i32),
+            page_token,
+        };
+        let response = state
+            .discovery
+            .list_shares(request, recipient.clone())
+            .await?;
+        for share in response.items {
+            let decision = state
+                .policy
+                .authorize(Resource::share(&share.name), Permission::Read, &recipient)
+                .await?;
+            if decision == Decision::Allow {
+                items.push(share);
+            }
+        }
+        page_token = response.next_page_token.filter(|token| !token.is_empty());
+        // without maxResults the handler picks the page size, only empty pages are skipped.
+        let full = max_results.map_or(!items.is_empty(), |max| items.len() >= max);
+        if full || page_token.is_none() {
+            break;
+        }
+    }
+    let response = t::ListSharesResponse {
+        items,
+        next_page_token: page_token,
     };
-    // TODO: should we check the permission for all returned shares?
-    Ok(Json(state.discovery.list_shares(request, recipient).await?))
+    Ok(Listing { mode, response })
 }
 
 async fn get_share<T: Send + Sync>(
@@ -45,46 +175,57 @@ async fn get_share<T: Send + Sync>(
     let request = t::GetShareRequest {
         share: share.to_ascii_lowercase(),
     };
+    // resolve the share before authorizing, so that missing shares are reported as not found
+    // and existing shares the recipient may not read are reported as forbidden.
+    let response = state.discovery.get_share(request).await?;
     check_read_share_permission(state.policy.as_ref(), share, &recipient).await?;
-    Ok(Json(state.discovery.get_share(request).await?))
+    Ok(Json(response))
 }
 
 async fn list_schemas<T: Send + Sync>(
     State(state): State<DeltaSharingState<T>>,
     Extension(recipient): Extension<T>,
+    mode: ResponseMode,
     pagination: Query<Pagination>,
     Path(share): Path<String>,
-) -> Result<Json<t::ListSchemasResponse>> {
+) -> Result<Listing<t::ListSchemasResponse>> {
+    pagination.validate()?;
     let request = t::ListSchemasRequest {
         max_results: pagination.0.max_results,
         page_token: pagination.0.page_token,
         share: share.to_ascii_lowercase(),
     };
     check_read_share_permission(state.policy.as_ref(), share, &recipient).await?;
-    Ok(Json(state.discovery.list_schemas(request).await?))
+    let response = state.discovery.list_schemas(request).await?;
+    Ok(Listing { mode, response })
 }
 
 async fn list_share_tables<T: Send + Sync>(
     State(state): State<DeltaSharingState<T>>,
     Extension(recipient): Extension<T>,
+    mode: ResponseMode,
     pagination: Query<Pagination>,
     Path(share): Path<String>,
-) -> Result<Json<t::ListShareTablesResponse>> {
+) -> Result<Listing<t::ListShareTablesResponse>> {
+    pagination.validate()?;
     let request = t::ListShareTablesRequest {
         max_results: pagination.0.max_results,
         page_token: pagination.0.page_token,
         share: share.to_ascii_lowercase(),
     };
     check_read_share_permission(state.policy.as_ref(), share, &recipient).await?;
-    Ok(Json(state.discovery.list_share_tables(request).await?))
+    let response = state.discovery.list_share_tables(request).await?;
+    Ok(Listing { mode, response })
 }
 
 async fn list_schema_tables<T: Send + Sync>(
     State(state): State<DeltaSharingState<T>>,
     Extension(recipient): Extension<T>,
+    mode: ResponseMode,
     pagination: Query<Pagination>,
     Path((share, schema)): Path<(String, String)>,
-) -> Result<Json<t::ListSchemaTablesResponse>> {
+) -> Result<Listing<t::ListSchemaTablesResponse>> {
+    pagination.validate()?;
     let request = t::ListSchemaTablesRequest {
         max_results: pagination.0.max_results,
         page_token: pagination.0.page_token,
@@ -92,7 +233,8 @@ async fn list_schema_tables<T: Send + Sync>(
         schema: schema.to_ascii_lowercase(),
     };
     check_read_share_permission(state.policy.as_ref(), share, &recipient).await?;
-    Ok(Json(state.discovery.list_schema_tables(request).await?))
+    let response = state.discovery.list_schema_tables(request).await?;
+    Ok(Listing { mode, response })
 }
 
 async fn check_read_share_permission<T: Send + Sync>(
@@ -126,14 +268,19 @@ pub fn get_router<T: Send + Sync + Clone + 'static>(state: DeltaSharingState<T>)
 mod tests {
     use axum::body::Body;
     use axum::http::{header, HeaderValue, Request, StatusCode};
-    use delta_sharing_core::policies::ConstantPolicy;
-    use delta_sharing_core::{DeltaRecipient, KernelQueryHandler};
+    use delta_sharing_core::policies::{AnonymousAccessPolicy, ConstantPolicy};
+    use delta_sharing_core::{
+        DefaultInMemoryHandler, DeltaRecipient, KernelQueryHandler, ShareConfig,
+    };
     use http_body_util::BodyExt;
     use tower::ServiceExt;
 
     use super::*;
-    use crate::auth::{AnonymousAuthenticator, AuthorizationLayer};
-    use crate::tests::test_handler;
+    use crate::auth::{
+        AnonymousAuthenticator, AuthorizationLayer, DefaultRecipientAuthenticator,
+        TestRecipientAuthenticator, TEST_RECIPIENT_HEADER,
+    };
+    use crate::tests::{test_config, test_handler};
 
     fn get_state() -> DeltaSharingState<DeltaRecipient> {
         let discovery = Arc::new(test_handler());
@@ -148,6 +295,14 @@ mod tests {
         get_router(get_state()).layer(AuthorizationLayer::new(AnonymousAuthenticator))
     }
 
+    fn get_denying_router() -> Router {
+        let state = DeltaSharingState {
+            policy: Arc::new(ConstantPolicy::<DeltaRecipient>::new(Decision::Deny)),
+            ..get_state()
+        };
+        get_router(state).layer(AuthorizationLayer::new(AnonymousAuthenticator))
+    }
+
     #[tokio::test]
     async fn test_list_shares() {
         let app = get_anonymous_router();
@@ -169,6 +324,389 @@ mod tests {
         assert_eq!(result.items.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_list_shares_anonymous_disabled() {
+        let policy = AnonymousAccessPolicy::new(ConstantPolicy::<DeltaRecipient>::default())
+            .allow_anonymous(false);
+        let state = DeltaSharingState {
+            policy: Arc::new(policy),
+            ..get_state()
+        };
+        let app = get_router(state).layer(AuthorizationLayer::new(AnonymousAuthenticator));
+
+        let request = Request::builder()
+            .uri("/shares")
+            .header(
+                header::AUTHORIZATION,
+                HeaderValue::from_str("Bearer token").unwrap(),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert!(response.status().is_success());
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<t::ListSharesResponse>(&body).unwrap();
+        assert!(result.items.is_empty());
+    }
+
+    /// Policy that lets the recipient `guest` read `share2` and denies everything else.
+    struct GuestPolicy;
+
+    #[async_trait::async_trait]
+    impl Policy for GuestPolicy {
+        type Recipient = DeltaRecipient;
+
+        async fn authorize(
+            &self,
+            resource: Resource,
+            _: Permission,
+            recipient: &Self::Recipient,
+        ) -> std::result::Result<Decision, CoreError> {
+            let allowed = match recipient {
+                DeltaRecipient::Profile(claims) => {
+                    claims.email == "guest" && resource == Resource::share("share2")
+                }
+                DeltaRecipient::Anonymous => false,
+            };
+            Ok(if allowed {
+                Decision::Allow
+            } else {
+                Decision::Deny
+            })
+        }
+    }
+
+    async fn list_share_names(app: Router, token: Option<&str>) -> Vec<String> {
+        let mut request = Request::builder().uri("/shares");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<t::ListSharesResponse>(&body).unwrap();
+        result.items.into_iter().map(|share| share.name).collect()
+    }
+
+    /// Policy that denies reading `share1` and allows everything else.
+    struct HiddenSharePolicy;
+
+    #[async_trait::async_trait]
+    impl Policy for HiddenSharePolicy {
+        type Recipient = DeltaRecipient;
+
+        async fn authorize(
+            &self,
+            resource: Resource,
+            _: Permission,
+            _: &Self::Recipient,
+        ) -> std::result::Result<Decision, CoreError> {
+            Ok(if resource == Resource::share("share1") {
+                Decision::Deny
+            } else {
+                Decision::Allow
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_filtered_pages() {
+        let mut config = test_config();
+        for name in ["share2", "share3"] {
+            config
+                .shares
+                .push(ShareConfig::new(name, Vec::<String>::new()));
+        }
+        let state = DeltaSharingState {
+            discovery: Arc::new(DefaultInMemoryHandler::new(config)),
+            policy: Arc::new(HiddenSharePolicy),
+            ..get_state()
+        };
+        let app = get_router(state).layer(AuthorizationLayer::new(AnonymousAuthenticator));
+
+        // denied shares do not take up room in a page.
+        let response = list_shares_from(app.clone(), "/shares?maxResults=1").await;
+        let names = response
+            .items
+            .iter()
+            .map(|share| share.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share2"]);
+        let token = response.next_page_token.expect("more shares should follow");
+
+        let uri = format!("/shares?maxResults=1&pageToken={}", token);
+        let response = list_shares_from(app.clone(), &uri).await;
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.items[0].name, "share3");
+        assert!(response.next_page_token.is_none());
+
+        let response = list_shares_from(app, "/shares?maxResults=5").await;
+        let names = response
+            .items
+            .iter()
+            .map(|share| share.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share2", "share3"]);
+        assert!(response.next_page_token.is_none());
+    }
+
+    async fn list_shares_from(app: Router, uri: &str) -> t::ListSharesResponse {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert!(response.status().is_success());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_default_recipient() {
+        let mut config = test_config();
+        config
+            .shares
+            .push(ShareConfig::new("share2", Vec::<String>::new()));
+        let state = DeltaSharingState {
+            discovery: Arc::new(DefaultInMemoryHandler::new(config)),
+            policy: Arc::new(GuestPolicy),
+            ..get_state()
+        };
+        let router = |name: Option<&str>| {
+            let authenticator = DefaultRecipientAuthenticator::named(
+                AnonymousAuthenticator,
+                name.map(String::from),
+            );
+            get_router(state.clone()).layer(AuthorizationLayer::new(authenticator))
+        };
+
+        // tokenless requests are made on behalf of the default recipient.
+        let names = list_share_names(router(Some("guest")), None).await;
+        assert_eq!(names, vec!["share2"]);
+
+        // requests with a token are authenticated as before.
+        let names = list_share_names(router(Some("guest")), Some("token")).await;
+        assert!(names.is_empty());
+
+        // without a default recipient tokenless requests remain anonymous.
+        let names = list_share_names(router(None), None).await;
+        assert!(names.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_test_recipient() {
+        let mut config = test_config();
+        config
+            .shares
+            .push(ShareConfig::new("share2", Vec::<String>::new()));
+        let state = DeltaSharingState {
+            discovery: Arc::new(DefaultInMemoryHandler::new(config)),
+            policy: Arc::new(GuestPolicy),
+            ..get_state()
+        };
+        let authenticator = TestRecipientAuthenticator::new(AnonymousAuthenticator);
+        let app = get_router(state).layer(AuthorizationLayer::new(authenticator));
+
+        let request = Request::builder()
+            .uri("/shares")
+            .header(TEST_RECIPIENT_HEADER, "guest")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<t::ListSharesResponse>(&body).unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].name, "share2");
+
+        let request = Request::builder()
+            .uri("/shares/share2")
+            .header(TEST_RECIPIENT_HEADER, "guest")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // without the header, requests are authenticated by the wrapped authenticator.
+        let names = list_share_names(app, None).await;
+        assert!(names.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_default_recipient_anonymous_disabled() {
+        let policy = AnonymousAccessPolicy::new(ConstantPolicy::<DeltaRecipient>::default())
+            .allow_anonymous(false);
+        let state = DeltaSharingState {
+            policy: Arc::new(policy),
+            ..get_state()
+        };
+        let authenticator = DefaultRecipientAuthenticator::named(AnonymousAuthenticator, None);
+        let app = get_router(state).layer(AuthorizationLayer::new(authenticator));
+
+        let request = Request::builder()
+            .uri("/shares/share1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_malformed_page_token() {
+        let app = get_anonymous_router();
+
+        let request = Request::builder()
+            .uri("/shares?pageToken=garbage")
+            .header(
+                header::AUTHORIZATION,
+                HeaderValue::from_str("Bearer token").unwrap(),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(result["errorCode"], "INVALID_PARAMETER_VALUE");
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_zero_max_results() {
+        let app = get_anonymous_router();
+
+        let request = Request::builder()
+            .uri("/shares?maxResults=0")
+            .header(
+                header::AUTHORIZATION,
+                HeaderValue::from_str("Bearer token").unwrap(),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(result["errorCode"], "INVALID_PARAMETER_VALUE");
+    }
+
+    fn get_multi_share_router() -> Router {
+        let mut config = test_config();
+        for name in ["share2", "share3"] {
+            config.shares.push(ShareConfig {
+                name: name.to_string(),
+                schema_refs: vec![],
+                properties: Default::default(),
+                default_schema: None,
+                created_at: None,
+            });
+        }
+        let state = DeltaSharingState {
+            discovery: Arc::new(DefaultInMemoryHandler::new(config)),
+            ..get_state()
+        };
+        get_router(state).layer(AuthorizationLayer::new(AnonymousAuthenticator))
+    }
+
+    async fn list_shares_with(uri: &str, accept: Option<&str>) -> axum::response::Response {
+        let mut request = Request::builder().uri(uri).header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str("Bearer token").unwrap(),
+        );
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        get_multi_share_router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_json() {
+        let response = list_shares_with("/shares?maxResults=2", None).await;
+        assert!(response.status().is_success());
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        let names = result["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|share| share["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["share1", "share2"]);
+        let token = result["nextPageToken"].as_str().unwrap();
+
+        let uri = format!("/shares?maxResults=2&pageToken={}", token);
+        let response = list_shares_with(&uri, Some("application/json")).await;
+        assert!(response.status().is_success());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(result["items"].as_array().unwrap().len(), 1);
+        assert_eq!(result["items"][0]["name"], "share3");
+        assert!(result.get("nextPageToken").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_ndjson() {
+        let response = list_shares_with("/shares?maxResults=2", Some("application/x-ndjson")).await;
+        assert!(response.status().is_success());
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/x-ndjson"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let lines = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["name"], "share1");
+        assert_eq!(lines[1]["name"], "share2");
+        assert!(lines[2]["nextPageToken"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_listing_empty_page_token() {
+        for mode in [ResponseMode::Json, ResponseMode::NdJson] {
+            let response = t::ListSharesResponse {
+                items: vec![],
+                next_page_token: Some(String::new()),
+            };
+            let response = Listing { mode, response }.into_response();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            assert!(!std::str::from_utf8(&body)
+                .unwrap()
+                .contains("nextPageToken"));
+        }
+
+        let response = t::ListSharesResponse {
+            items: vec![],
+            next_page_token: None,
+        };
+        let mode = ResponseMode::Json;
+        let response = Listing { mode, response }.into_response();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert!(result.get("nextPageToken").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_shares_not_acceptable() {
+        let response = list_shares_with("/shares", Some("text/csv")).await;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
     #[tokio::test]
     async fn test_get_share() {
         let app = get_anonymous_router();
@@ -205,6 +743,50 @@ mod tests {
 
         let response = app.oneshot(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(result["errorCode"], "RESOURCE_DOES_NOT_EXIST");
+        assert!(result["message"].as_str().unwrap().contains("nonexistent"));
+    }
+
+    #[tokio::test]
+    async fn test_get_share_forbidden() {
+        let app = get_denying_router();
+
+        let request: Request<Body> = Request::builder()
+            .uri("/shares/share1")
+            .header(
+                header::AUTHORIZATION,
+                HeaderValue::from_str("Bearer token").unwrap(),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_get_share_not_found_when_forbidden() {
+        let app = get_denying_router();
+
+        let request: Request<Body> = Request::builder()
+            .uri("/shares/nonexistent")
+            .header(
+                header::AUTHORIZATION,
+                HeaderValue::from_str("Bearer token").unwrap(),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let result = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        assert_eq!(result["errorCode"], "RESOURCE_DOES_NOT_EXIST");
+        assert!(result["message"].as_str().unwrap().contains("nonexistent"));
     }
 
     #[tokio::test]
@@ -0,0 +1,2 @@
+ALTER TABLE "table"
+ADD COLUMN read_only BOOLEAN NOT NULL DEFAULT FALSE;
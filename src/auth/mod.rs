@@ -17,6 +17,7 @@
 //! server to determine the access control policy for the request.
 
 use std::fmt::Display;
+use std::str::FromStr;
 
 pub mod public;
 
@@ -111,6 +112,65 @@ impl RecipientId {
     pub fn is_known(&self) -> bool {
         matches!(self, RecipientId::Known(_))
     }
+
+    /// Parse a [`RecipientId`] from an authenticated principal, falling back to an
+    /// anonymous recipient if the principal is empty or whitespace-only.
+    ///
+    /// # Example
+    /// ```
+    /// use delta_sharing::auth::RecipientId;
+    ///
+    /// assert_eq!(RecipientId::parse_or_anonymous(" foo "), RecipientId::known("foo"));
+    /// assert_eq!(RecipientId::parse_or_anonymous("  "), RecipientId::anonymous());
+    /// ```
+    pub fn parse_or_anonymous(principal: &str) -> Self {
+        principal.parse().unwrap_or_default()
+    }
+}
+
+/// Error returned when parsing an empty or whitespace-only recipient identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecipientIdError;
+
+impl Display for ParseRecipientIdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("recipient identifier must not be empty")
+    }
+}
+
+impl std::error::Error for ParseRecipientIdError {}
+
+impl FromStr for RecipientId {
+    type Err = ParseRecipientIdError;
+
+    /// Parse a known [`RecipientId`] from an authenticated principal.
+    ///
+    /// Surrounding whitespace is trimmed, and empty or whitespace-only principals
+    /// are rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use delta_sharing::auth::RecipientId;
+    ///
+    /// let recipient_id: RecipientId = "foo".parse().unwrap();
+    /// assert_eq!(recipient_id, RecipientId::known("foo"));
+    /// assert!("".parse::<RecipientId>().is_err());
+    /// ```
+    fn from_str(principal: &str) -> Result<Self, Self::Err> {
+        let principal = principal.trim();
+        if principal.is_empty() {
+            return Err(ParseRecipientIdError);
+        }
+        Ok(Self::known(principal))
+    }
+}
+
+impl TryFrom<&str> for RecipientId {
+    type Error = ParseRecipientIdError;
+
+    fn try_from(principal: &str) -> Result<Self, Self::Error> {
+        principal.parse()
+    }
 }
 
 impl Default for RecipientId {
@@ -154,4 +214,34 @@ mod tests {
             String::from("my_recipient_id")
         );
     }
+
+    #[test]
+    fn parse_valid_recipient_id() {
+        assert_eq!(
+            "my_recipient_id".parse::<RecipientId>(),
+            Ok(RecipientId::known("my_recipient_id"))
+        );
+        assert_eq!(
+            RecipientId::try_from("  my_recipient_id\t"),
+            Ok(RecipientId::known("my_recipient_id"))
+        );
+    }
+
+    #[test]
+    fn parse_empty_recipient_id() {
+        assert_eq!("".parse::<RecipientId>(), Err(ParseRecipientIdError));
+        assert_eq!(
+            RecipientId::parse_or_anonymous(""),
+            RecipientId::anonymous()
+        );
+    }
+
+    #[test]
+    fn parse_whitespace_recipient_id() {
+        assert_eq!(" \t\n".parse::<RecipientId>(), Err(ParseRecipientIdError));
+        assert_eq!(
+            RecipientId::parse_or_anonymous(" \t\n"),
+            RecipientId::anonymous()
+        );
+    }
 }
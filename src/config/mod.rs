@@ -1,18 +1,77 @@
 mod fetcher;
 
+use std::collections::HashMap;
+
 use once_cell::sync::Lazy;
 
 use crate::server::utilities::bootstrap::JwtKeys;
+use crate::server::utilities::credentials::{Credentials, Provider as CredentialProvider};
 
 pub(crate) static AWS_PROFILE: &str = "default";
 
 pub(crate) static AWS_REGION: &str = "us-east-1";
 
+pub(crate) static TABLE_TIMEOUT: u64 = 30;
+
+pub(crate) static TABLE_CACHE_CAPACITY: usize = 16;
+
+pub(crate) static TABLE_CACHE_TTL: u64 = 60;
+
+pub(crate) static MAX_TABLE_OPENS: usize = 16;
+
+pub(crate) static SHUTDOWN_TIMEOUT: u64 = 30;
+
+pub(crate) static SIGNED_URL_REFRESH_WINDOW: u64 = 60;
+
+pub(crate) static MAX_FILE_ACTIONS: usize = 100_000;
+
+pub(crate) static MAX_RESPONSE_BYTES: usize = 256 * 1024 * 1024;
+
+pub(crate) static MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+pub(crate) static MAX_ARROW_BYTES: usize = 16 * 1024 * 1024;
+
+pub(crate) static STORAGE_CREDENTIALS: Lazy<CredentialProvider> = Lazy::new(|| {
+    let credentials = fetcher::CONFIG
+        .get::<HashMap<String, Credentials>>("storage_credentials")
+        .unwrap_or_default();
+    CredentialProvider::new(credentials)
+});
+
 pub(crate) static JWT_SECRET: Lazy<JwtKeys> = Lazy::new(|| {
     let secret = fetch::<String>("jwt_secret");
     JwtKeys::new(secret.as_bytes())
 });
 
+/// Fetch the optional configuration flag `flag`, falling back to `default` if it is not set.
+///
+/// A flag which is set but cannot be parsed is reported with the name of its environment
+/// variable instead of being replaced by the default.
+pub fn fetch_or<T>(flag: &str, default: T) -> anyhow::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = fetch::<String>(flag);
+    if value.is_empty() {
+        return Ok(default);
+    }
+    value.parse::<T>().map_err(|e| {
+        anyhow::anyhow!(
+            r#"invalid value "{}" for configuration flag "{}" ({}): {}"#,
+            value,
+            flag,
+            env_var(flag),
+            e
+        )
+    })
+}
+
+/// Name of the environment variable setting the configuration flag `flag`.
+pub fn env_var(flag: &str) -> String {
+    format!("DELTA_SHARING_RS_{}", flag.to_uppercase())
+}
+
 pub fn fetch<T>(flag: &str) -> T
 where
     fetcher::Flag<String>: fetcher::Fetch<T>,
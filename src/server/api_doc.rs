@@ -1,6 +1,6 @@
 use utoipa::OpenApi;
 
-use crate::server::routers::{admin, shares};
+use crate::server::routers::{admin, proxy, shares};
 use crate::server::services::{account, error, profile, schema, share, table};
 use crate::server::utilities::{deltalake, json};
 
@@ -23,6 +23,7 @@ use crate::server::utilities::{deltalake, json};
         shares::schemas::tables::version::get,
         shares::schemas::tables::metadata::get,
         shares::schemas::tables::query::post,
+        proxy::get,
     ),
     components(
 	schemas(
@@ -50,7 +51,7 @@ use crate::server::utilities::{deltalake, json};
         schemas(shares::all_tables::SharesAllTablesListResponse),
         schemas(shares::schemas::SharesSchemasListResponse),
         schemas(shares::schemas::tables::SharesSchemasTablesListResponse),
-        schemas(shares::schemas::tables::query::SharesSchemasTablesQueryPostRequest),
+        schemas(shares::schemas::tables::query::SharesSchemasTablesQueryPostRequest, shares::schemas::tables::query::QueryFormat),
     ),
     tags(
         (name = "Delta Sharing", description = "Delta Sharing API")
@@ -8,6 +8,7 @@ use validator::Validate;
 use crate::server::entities::account::Id as AccountId;
 use crate::server::entities::schema::Id as SchemaId;
 use crate::server::repositories::table::Repository;
+use crate::server::utilities::location::StorageLocation;
 use crate::{impl_string_property, impl_uuid_property};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -43,6 +44,9 @@ pub struct Entity {
     location: Location,
     #[getset(get = "pub")]
     created_by: AccountId,
+    /// Whether only snapshots of the table are shared, rejecting queries for its changes.
+    #[getset(get_copy = "pub", set = "pub")]
+    read_only: bool,
 }
 
 impl Entity {
@@ -59,9 +63,15 @@ impl Entity {
             schema_id: SchemaId::try_from(schema_id)?,
             location: Location::try_new(location)?,
             created_by: AccountId::try_from(created_by)?,
+            read_only: false,
         })
     }
 
+    /// Parse the location of the table into its storage components.
+    pub fn parsed_location(&self) -> Result<StorageLocation> {
+        self.location.as_str().parse()
+    }
+
     pub async fn load(schema_id: &SchemaId, name: &Name, pg_pool: &PgPool) -> Result<Option<Self>> {
         match Repository::select_by_name(schema_id, name, pg_pool).await? {
             Some(row) => Ok(Self {
@@ -70,6 +80,7 @@ impl Entity {
                 schema_id: SchemaId::new(row.schema_id),
                 location: Location::try_new(row.location)?,
                 created_by: AccountId::new(row.created_by),
+                read_only: row.read_only,
             }
             .into()),
             _ => Ok(None),
@@ -114,4 +125,19 @@ mod tests {
     fn test_invalid_location() {
         assert!(Location::try_new("").is_err());
     }
+
+    #[test]
+    fn test_parsed_location() {
+        let table = Entity::new(
+            None,
+            testutils::rand::string(10),
+            testutils::rand::uuid(),
+            "s3://delta-sharing-test/covid".into(),
+            testutils::rand::uuid(),
+        )
+        .expect("table should be created");
+        let location = table.parsed_location().expect("location should be parsed");
+        assert_eq!(location.bucket(), Some("delta-sharing-test"));
+        assert_eq!(location.as_str(), table.location().as_str());
+    }
 }
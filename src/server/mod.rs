@@ -40,6 +40,16 @@ pub struct Server {
 
 impl Server {
     pub async fn new() -> Result<Self> {
+        services::deltalake::Budget::from_config().context("invalid response budget")?;
+        routers::shares::schemas::tables::query::body_limit()
+            .context("invalid request size limit")?;
+        routers::shares::schemas::tables::query::arrow_limit()
+            .context("invalid arrow size limit")?;
+        routers::shutdown_timeout().context("invalid shutdown timeout")?;
+        utilities::deltalake::Utility::timeout().context("invalid table timeout")?;
+        utilities::deltalake::TableCache::<()>::from_config().context("invalid table cache")?;
+        utilities::deltalake::OpenLimit::from_config().context("invalid table open limit")?;
+        utilities::signed_url::SignedUrlCache::from_config().context("invalid signed URL cache")?;
         let pg_pool = bootstrap::new_pg_pool()
             .await
             .context("failed to create postgres connection pool")?;
@@ -14,6 +14,7 @@ pub struct Row {
     pub schema_id: Uuid,
     pub location: String,
     pub created_by: Uuid,
+    pub read_only: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -32,20 +33,23 @@ impl Repository {
                    name,
                    schema_id,
                    location,
-                   created_by
-               ) VALUES ($1, $2, $3, $4, $5)
+                   created_by,
+                   read_only
+               ) VALUES ($1, $2, $3, $4, $5, $6)
                ON CONFLICT(id)
                DO UPDATE
                SET name = $2,
                    schema_id = $3,
                    location = $4,
-                   created_by = $5"#,
+                   created_by = $5,
+                   read_only = $6"#,
         )
         .bind(table.id())
         .bind(table.name())
         .bind(table.schema_id())
         .bind(table.location())
         .bind(table.created_by())
+        .bind(table.read_only())
         .execute(&mut *conn)
         .await
         .context(format!(
@@ -71,6 +75,7 @@ impl Repository {
                    schema_id,
                    location,
                    created_by,
+                   read_only,
                    created_at,
                    updated_at
                FROM "table"
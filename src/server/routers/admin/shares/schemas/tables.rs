@@ -28,6 +28,8 @@ pub struct AdminSharesSchemasTablesPostParams {
 pub struct AdminSharesSchemasTablesPostRequest {
     pub name: String,
     pub location: String,
+    /// Whether only snapshots of the table are shared, rejecting queries for its changes.
+    pub read_only: Option<bool>,
 }
 
 #[derive(serde::Serialize, ToSchema)]
@@ -91,7 +93,7 @@ pub async fn post(
         tracing::error!("requested table data is malformed");
         return Err(Error::ValidationFailed);
     };
-    let Ok(table) = TableEntity::new(
+    let Ok(mut table) = TableEntity::new(
         None,
         table_name.to_string(),
         schema.id().to_string(),
@@ -101,6 +103,7 @@ pub async fn post(
         tracing::error!("requested schema data is malformed");
         return Err(Error::ValidationFailed);
     };
+    table.set_read_only(payload.read_only.unwrap_or(false));
     match PostgresUtility::error(table.save(&state.pg_pool).await)? {
         Ok(_) => {
             tracing::info!("table was successfully registered");
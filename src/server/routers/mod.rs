@@ -1,10 +1,13 @@
 pub mod admin;
+pub mod proxy;
 pub mod shares;
 
+use std::future::{Future, IntoFuture};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use axum::extract::Extension;
+use axum::extract::{DefaultBodyLimit, Extension};
 use axum::http::{header, Method, Uri};
 use axum::middleware;
 use axum::response::Response;
@@ -113,7 +116,9 @@ async fn route(
         )
         .route(
             "/shares/:share/schemas/:schema/tables/:table/query",
-            post(self::shares::schemas::tables::query::post),
+            post(self::shares::schemas::tables::query::post).layer(DefaultBodyLimit::max(
+                self::shares::schemas::tables::query::body_limit()?,
+            )),
         )
         .route_layer(middleware::from_fn(jwt::as_guest))
         .layer(Extension(state.clone()))
@@ -129,10 +134,16 @@ async fn route(
                 .allow_credentials(true),
         );
 
+    // NOTE: Proxied files are fetched like signed URLs, so the proxy token replaces the bearer token.
+    let proxy = Router::new()
+        .route("/proxy/:token", get(self::proxy::get))
+        .layer(Extension(state.clone()));
+
     let app = Router::new()
         .merge(swagger)
         .merge(admin)
         .merge(guest)
+        .merge(proxy)
         .fallback(bad_request);
 
     Ok(app)
@@ -155,8 +166,140 @@ pub async fn bind(
     let addr = config::fetch::<String>("server_bind");
     tracing::info!("delta sharing server listening on {}", addr);
     let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app.into_make_service())
+    serve(listener, app, shutdown_signal(), shutdown_timeout()?)
         .await
         .context(format!(r#"failed to bind "{}" to hyper::Server"#, addr))?;
     Ok(())
 }
+
+pub(crate) fn shutdown_timeout() -> Result<Duration> {
+    let timeout = config::fetch_or("shutdown_timeout", config::SHUTDOWN_TIMEOUT)?;
+    Ok(Duration::from_secs(timeout))
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("shutdown signal received, draining in-flight requests");
+}
+
+/// Serve `app` until `signal` resolves.
+///
+/// Once signaled the server stops accepting connections and waits for in-flight responses,
+/// like streamed query results, to finish. Responses still running after `grace_period`
+/// are cut off.
+async fn serve(
+    listener: TcpListener,
+    app: Router,
+    signal: impl Future<Output = ()> + Send + 'static,
+    grace_period: Duration,
+) -> std::io::Result<()> {
+    let (signaled_tx, signaled_rx) = tokio::sync::oneshot::channel::<()>();
+    let server =
+        axum::serve(listener, app.into_make_service()).with_graceful_shutdown(async move {
+            signal.await;
+            let _ = signaled_tx.send(());
+        });
+    let deadline = async move {
+        if signaled_rx.await.is_err() {
+            std::future::pending::<()>().await;
+        }
+        tokio::time::sleep(grace_period).await;
+    };
+    tokio::select! {
+        result = server.into_future() => result,
+        _ = deadline => {
+            tracing::warn!("in-flight requests did not finish within {:?} after shutdown", grace_period);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use axum_extra::json_lines::JsonLines;
+    use futures::stream::{self, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    async fn stream_lines(
+    ) -> JsonLines<impl futures::Stream<Item = Result<serde_json::Value, Infallible>>> {
+        let lines = stream::iter(0..5).then(|n| async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(serde_json::json!({ "line": n }))
+        });
+        JsonLines::new(lines)
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_streamed_responses() {
+        let app = Router::new().route("/stream", get(stream_lines));
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should be bound");
+        let addr = listener
+            .local_addr()
+            .expect("listener should have an address");
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(
+            listener,
+            app,
+            async {
+                let _ = shutdown_rx.await;
+            },
+            Duration::from_secs(5),
+        ));
+
+        let mut stream = TcpStream::connect(addr)
+            .await
+            .expect("server should accept connections");
+        stream
+            .write_all(b"GET /stream HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("request should be sent");
+        let mut buf = [0; 1024];
+        let read = stream.read(&mut buf).await.expect("response should start");
+        assert!(read > 0);
+
+        // signal shutdown while the response is still being streamed.
+        shutdown_tx.send(()).expect("server should be running");
+        let mut response = buf[..read].to_vec();
+        stream
+            .read_to_end(&mut response)
+            .await
+            .expect("response should be read");
+        let response = String::from_utf8(response).expect("response should be UTF-8");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        for n in 0..5 {
+            assert!(response.contains(&format!(r#"{{"line":{}}}"#, n)));
+        }
+
+        server
+            .await
+            .expect("server should not panic")
+            .expect("server should shut down cleanly");
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+}
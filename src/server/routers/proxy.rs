@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use axum::extract::{Extension, Path};
+use axum::response::Response;
+use utoipa::IntoParams;
+
+use crate::config::JWT_SECRET;
+use crate::server::entities::schema::Name as SchemaName;
+use crate::server::entities::share::Name as ShareName;
+use crate::server::entities::table::Name as TableName;
+use crate::server::routers::SharedState;
+use crate::server::services::error::Error;
+use crate::server::services::table::Service as TableService;
+use crate::server::utilities::deltalake::Timeout as DeltalakeTimeout;
+use crate::server::utilities::deltalake::Utility as DeltalakeUtility;
+use crate::server::utilities::proxy::Utility as ProxyUtility;
+
+#[derive(Debug, serde::Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyGetParams {
+    token: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/proxy/{token}",
+    operation_id = "GetProxiedFile",
+    tag = "official",
+    params(ProxyGetParams),
+    responses(
+        (status = 200, description = "The file was successfully streamed.", body = String),
+        (status = 400, description = "The request is malformed.", body = ErrorMessage),
+        (status = 403, description = "The proxy token is invalid, expired, or the table is no longer shared.", body = ErrorMessage),
+        (status = 404, description = "The requested file does not exist.", body = ErrorMessage),
+        (status = 500, description = "The request is not handled correctly due to a server error.", body = ErrorMessage),
+        (status = 503, description = "The delta table could not be loaded in time.", body = ErrorMessage),
+    )
+)]
+#[tracing::instrument(skip(state, params))]
+pub async fn get(
+    Extension(state): Extension<SharedState>,
+    Path(params): Path<ProxyGetParams>,
+) -> Result<Response, Error> {
+    // NOTE: The proxy token is the credential, like the signature of a signed URL.
+    let claims = ProxyUtility::decode(&params.token, &JWT_SECRET)?;
+    let Ok(share) = ShareName::try_new(claims.share) else {
+        tracing::error!("proxy token's share data is malformed");
+        return Err(Error::ValidationFailed);
+    };
+    let Ok(schema) = SchemaName::try_new(claims.schema) else {
+        tracing::error!("proxy token's schema data is malformed");
+        return Err(Error::ValidationFailed);
+    };
+    let Ok(table) = TableName::try_new(claims.table) else {
+        tracing::error!("proxy token's table data is malformed");
+        return Err(Error::ValidationFailed);
+    };
+    let Ok(table) = TableService::query_by_fqn(&share, &schema, &table, &state.pg_pool).await
+    else {
+        tracing::error!(
+            "request is not handled correctly due to a server error while selecting table"
+        );
+        return Err(anyhow!("error occured while selecting table(s)").into());
+    };
+    let Some(table) = table else {
+        tracing::error!("proxied table is no longer shared");
+        return Err(Error::Forbidden);
+    };
+    let table = match DeltalakeUtility::with_timeout(DeltalakeUtility::open_table_version(
+        &table.location,
+        None,
+    ))
+    .await
+    {
+        Ok(table) => table,
+        Err(e) if e.is::<DeltalakeTimeout>() => {
+            tracing::error!("request timed out while loading delta table: {}", e);
+            return Err(Error::ServiceUnavailable);
+        }
+        Err(_) => {
+            tracing::error!(
+                "request is not handled correctly due to a server error while loading delta table"
+            );
+            return Err(anyhow!("error occured while selecting table(s)").into());
+        }
+    };
+    let response = ProxyUtility::response(table.object_store().as_ref(), &claims.path).await?;
+    tracing::info!("proxied file was successfully streamed");
+    Ok(response)
+}
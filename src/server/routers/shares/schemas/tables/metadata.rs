@@ -3,18 +3,21 @@ use axum::extract::{Extension, Path};
 use axum::http::header;
 use axum::http::header::{HeaderMap, HeaderValue};
 use axum::http::StatusCode;
-use axum::response::{IntoResponse, Response};
-use axum_extra::json_lines::JsonLines;
+use axum::response::Response;
 use utoipa::IntoParams;
 
 use crate::server::entities::schema::Name as SchemaName;
 use crate::server::entities::share::Name as ShareName;
 use crate::server::entities::table::Name as TableName;
 use crate::server::routers::SharedState;
+use crate::server::services::deltalake::ResponseFormat;
 use crate::server::services::deltalake::Service as DeltalakeService;
+use crate::server::services::deltalake::CAPABILITIES_HEADER_NAME;
 use crate::server::services::error::Error;
 use crate::server::services::table::Service as TableService;
+use crate::server::utilities::deltalake::Timeout as DeltalakeTimeout;
 use crate::server::utilities::deltalake::Utility as DeltalakeUtility;
+use crate::server::utilities::ndjson::Utility as NDJSONUtility;
 
 const HEADER_NAME: &str = "Delta-Table-Version";
 
@@ -39,13 +42,20 @@ pub struct SharesSchemasTablesMetadataGetParams {
         (status = 403, description = "The request is forbidden from being fulfilled.", body = ErrorMessage),
         (status = 404, description = "The requested resource does not exist.", body = ErrorMessage),
         (status = 500, description = "The request is not handled correctly due to a server error.", body = ErrorMessage),
+        (status = 503, description = "The delta table could not be loaded in time.", body = ErrorMessage),
     )
 )]
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, request_headers))]
 pub async fn get(
     Extension(state): Extension<SharedState>,
     Path(params): Path<SharesSchemasTablesMetadataGetParams>,
+    request_headers: HeaderMap,
 ) -> Result<Response, Error> {
+    let format = ResponseFormat::negotiate(
+        request_headers
+            .get(CAPABILITIES_HEADER_NAME)
+            .and_then(|value| value.to_str().ok()),
+    );
     let Ok(share) = ShareName::try_new(params.share) else {
         tracing::error!("requested share data is malformed");
         return Err(Error::ValidationFailed);
@@ -69,11 +79,23 @@ pub async fn get(
         tracing::error!("requested table does not exist");
         return Err(Error::NotFound);
     };
-    let Ok(table) = DeltalakeUtility::open_table(&table.location).await else {
-        tracing::error!(
-            "request is not handled correctly due to a server error while loading delta table"
-        );
-        return Err(anyhow!("error occured while selecting table(s)").into());
+    let table = match DeltalakeUtility::with_timeout(DeltalakeUtility::open_table_version(
+        &table.location,
+        None,
+    ))
+    .await
+    {
+        Ok(table) => table,
+        Err(e) if e.is::<DeltalakeTimeout>() => {
+            tracing::error!("request timed out while loading delta table: {}", e);
+            return Err(Error::ServiceUnavailable);
+        }
+        Err(_) => {
+            tracing::error!(
+                "request is not handled correctly due to a server error while loading delta table"
+            );
+            return Err(anyhow!("error occured while selecting table(s)").into());
+        }
     };
     let Ok(metadata) = table.get_metadata() else {
         tracing::error!("request is not handled correctly due to a server error while loading delta table metadata");
@@ -81,15 +103,178 @@ pub async fn get(
     };
     let mut headers = HeaderMap::new();
     headers.insert(HEADER_NAME, table.version().into());
+    headers.insert(
+        CAPABILITIES_HEADER_NAME,
+        HeaderValue::from_static(format.capabilities()),
+    );
     headers.insert(
         header::CONTENT_TYPE,
         HeaderValue::from_static("application/x-ndjson"),
     );
     tracing::info!("delta table metadata was successfully returned");
-    Ok((
+    Ok(NDJSONUtility::response(
         StatusCode::OK,
         headers,
-        JsonLines::new(DeltalakeService::metadata_from(metadata.to_owned())),
+        DeltalakeService::metadata_from(&table, metadata.to_owned(), format),
     )
-        .into_response())
+    .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get as get_route;
+    use axum::Router;
+    use serde_json::json;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::server::entities::account::Entity as AccountEntity;
+    use crate::server::entities::schema::Entity as SchemaEntity;
+    use crate::server::entities::share::Entity as ShareEntity;
+    use crate::server::entities::table::Entity as TableEntity;
+    use crate::server::repositories::account::Repository as AccountRepository;
+    use crate::server::repositories::schema::Repository as SchemaRepository;
+    use crate::server::repositories::share::Repository as ShareRepository;
+    use crate::server::repositories::table::Repository as TableRepository;
+    use crate::server::routers::State;
+
+    async fn register(location: &str, pg_pool: &PgPool) {
+        let account = AccountEntity::new(
+            testutils::rand::uuid(),
+            testutils::rand::string(10),
+            testutils::rand::email(),
+            testutils::rand::string(10),
+            testutils::rand::string(10),
+            testutils::rand::i64(1, 100000),
+        )
+        .expect("account should be valid");
+        AccountRepository::upsert(&account, pg_pool)
+            .await
+            .expect("account should be created");
+        let created_by = account.id().to_uuid().to_string();
+        let share = ShareEntity::new(testutils::rand::uuid(), "share".into(), created_by.clone())
+            .expect("share should be valid");
+        ShareRepository::upsert(&share, pg_pool)
+            .await
+            .expect("share should be created");
+        let schema = SchemaEntity::new(
+            testutils::rand::uuid(),
+            "schema".into(),
+            share.id().to_uuid().to_string(),
+            created_by.clone(),
+        )
+        .expect("schema should be valid");
+        SchemaRepository::upsert(&schema, pg_pool)
+            .await
+            .expect("schema should be created");
+        let table = TableEntity::new(
+            testutils::rand::uuid(),
+            "table".into(),
+            schema.id().to_uuid().to_string(),
+            location.into(),
+            created_by,
+        )
+        .expect("table should be valid");
+        TableRepository::upsert(&table, pg_pool)
+            .await
+            .expect("table should be created");
+    }
+
+    async fn fetch(app: Router, capabilities: Option<&str>) -> (String, Vec<serde_json::Value>) {
+        let mut request =
+            Request::builder().uri("/shares/share/schemas/schema/tables/table/metadata");
+        if let Some(capabilities) = capabilities {
+            request = request.header(CAPABILITIES_HEADER_NAME, capabilities);
+        }
+        let response = app
+            .oneshot(
+                request
+                    .body(Body::empty())
+                    .expect("request should be built"),
+            )
+            .await
+            .expect("request should be handled");
+        assert_eq!(response.status(), StatusCode::OK);
+        let format = response.headers()[CAPABILITIES_HEADER_NAME]
+            .to_str()
+            .expect("capabilities should be a string")
+            .to_owned();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should be readable");
+        let lines = std::str::from_utf8(&bytes)
+            .expect("body should be UTF-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("line should be JSON"))
+            .collect();
+        (format, lines)
+    }
+
+    #[sqlx::test]
+    async fn test_response_format(pg_pool: PgPool) {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        let log = dir.path().join("_delta_log");
+        std::fs::create_dir_all(&log).expect("delta log directory should be created");
+        let actions = [
+            json!({ "commitInfo": { "timestamp": 1000 } }),
+            json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+            json!({ "metaData": {
+                "id": "00000000-0000-0000-0000-000000000000",
+                "format": { "provider": "parquet", "options": {} },
+                "schemaString": r#"{"type":"struct","fields":[{"name":"id","type":"long","nullable":true,"metadata":{}}]}"#,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1000
+            } }),
+        ];
+        std::fs::write(
+            log.join(format!("{:020}.json", 0)),
+            actions
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+        .expect("delta log should be written");
+        register(dir.path().to_str().unwrap(), &pg_pool).await;
+        let app = Router::new()
+            .route(
+                "/shares/:share/schemas/:schema/tables/:table/metadata",
+                get_route(get),
+            )
+            .layer(Extension(Arc::new(State {
+                pg_pool,
+                gcp_service_account: None,
+                aws_credentials: None,
+                azure_credentials: None,
+            })));
+
+        let (format, lines) = fetch(app.clone(), None).await;
+        assert_eq!(format, "responseformat=parquet");
+        assert_eq!(lines[0], json!({ "protocol": { "minReaderVersion": 1 } }));
+        assert_eq!(
+            lines[1]["metaData"]["id"],
+            "00000000-0000-0000-0000-000000000000"
+        );
+
+        let (format, lines) = fetch(app, Some("responseformat=delta,parquet")).await;
+        assert_eq!(format, "responseformat=delta");
+        assert_eq!(
+            lines[0],
+            json!({ "protocol": { "deltaProtocol": { "minReaderVersion": 1, "minWriterVersion": 2 } } })
+        );
+        assert_eq!(
+            lines[1]["metaData"]["deltaMetadata"]["id"],
+            "00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(
+            lines[1]["metaData"]["deltaMetadata"]["partitionColumns"],
+            json!([])
+        );
+    }
 }
@@ -2,12 +2,15 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context};
+use axum::body::Body;
+use axum::extract::rejection::JsonRejection;
 use axum::extract::{Extension, Json, Path};
 use axum::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use axum_extra::json_lines::JsonLines;
-use tame_gcs::signing::ServiceAccount;
+use axum::BoxError;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
 use utoipa::{IntoParams, ToSchema};
 
 use crate::config;
@@ -15,27 +18,169 @@ use crate::server::entities::schema::Name as SchemaName;
 use crate::server::entities::share::Name as ShareName;
 use crate::server::entities::table::Name as TableName;
 use crate::server::routers::SharedState;
+use crate::server::services::deltalake::Budget as DeltalakeBudget;
+use crate::server::services::deltalake::PageCursor as DeltalakePageCursor;
+use crate::server::services::deltalake::ResponseFormat;
 use crate::server::services::deltalake::Service as DeltalakeService;
+use crate::server::services::deltalake::CAPABILITIES_HEADER_NAME;
 use crate::server::services::error::Error;
 use crate::server::services::table::Service as TableService;
+use crate::server::utilities::arrow::CONTENT_TYPE as ARROW_CONTENT_TYPE;
+use crate::server::utilities::deltalake::Timeout as DeltalakeTimeout;
 use crate::server::utilities::deltalake::Utility as DeltalakeUtility;
 use crate::server::utilities::json::PartitionFilter as JSONPartitionFilter;
 use crate::server::utilities::json::PredicateJson;
 use crate::server::utilities::json::Utility as JSONUtility;
+use crate::server::utilities::ndjson::Utility as NDJSONUtility;
+use crate::server::utilities::proxy::Utility as ProxyUtility;
 use crate::server::utilities::signed_url::{Platform, Signer, Utility as SignedUrlUtility};
 use crate::server::utilities::sql::PartitionFilter as SQLPartitionFilter;
 use crate::server::utilities::sql::Utility as SQLUtility;
 
 const HEADER_NAME: &str = "Delta-Table-Version";
+const FILES_SCANNED_HEADER_NAME: &str = "Delta-Sharing-Files-Scanned";
+const FILES_RETURNED_HEADER_NAME: &str = "Delta-Sharing-Files-Returned";
+const FILES_PRUNED_HEADER_NAME: &str = "Delta-Sharing-Files-Pruned-By-Predicate";
+const ARROW_FALLBACK_HEADER_NAME: &str = "Delta-Sharing-Arrow-Fallback";
+
+/// Format of the response to a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryFormat {
+    /// File actions referencing the data files, one JSON object per line.
+    ///
+    /// The actions are in the format negotiated by the `delta-sharing-capabilities` header.
+    #[default]
+    #[serde(alias = "ndjson")]
+    Parquet,
+    /// The rows of the table as an Arrow IPC stream.
+    Arrow,
+}
 
 #[derive(Debug, serde::Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SharesSchemasTablesQueryPostRequest {
     pub predicate_hints: Option<Vec<String>>,
     pub json_predicate_hints: Option<PredicateJson>,
+    /// Number of rows the client wants to read, which must be positive.
+    ///
+    /// This is a hint: files are returned whole, so the returned files may contain
+    /// slightly more rows than requested.
     pub limit_hint: Option<i32>,
     pub version: Option<i64>,
     pub timestamp: Option<String>,
+    pub starting_version: Option<i64>,
+    /// Whether metadata changes within the range of `starting_version` are returned.
+    pub include_historical_metadata: Option<bool>,
+    pub dry_run: Option<bool>,
+    /// Columns the client reads, file statistics are limited to these columns.
+    pub column_projection: Option<Vec<String>>,
+    /// Whether the response reports how many files were skipped by the predicate hints.
+    pub include_pruning_stats: Option<bool>,
+    /// Token of a response that was paginated to stay within budget, which resumes it.
+    ///
+    /// The remaining request must be resubmitted unchanged.
+    pub page_token: Option<String>,
+    /// Whether the rows of a small table are returned as Arrow instead of file actions.
+    ///
+    /// Tables that cannot be read as Arrow are answered with file actions, naming the reason
+    /// in the `Delta-Sharing-Arrow-Fallback` header.
+    pub response_format: Option<QueryFormat>,
+}
+
+impl SharesSchemasTablesQueryPostRequest {
+    // NOTE: A request may either specify a version or a timestamp, but not both.
+    fn try_from_json(payload: Result<Json<Self>, JsonRejection>) -> Result<Self, Error> {
+        let payload = match payload {
+            Ok(Json(payload)) => payload,
+            Err(JsonRejection::MissingJsonContentType(_)) => {
+                tracing::error!("requested content type is not application/json");
+                return Err(Error::UnsupportedMediaType);
+            }
+            Err(JsonRejection::BytesRejection(rejection))
+                if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE =>
+            {
+                tracing::error!("requested payload exceeds the body limit");
+                return Err(Error::PayloadTooLarge);
+            }
+            Err(rejection) => {
+                tracing::error!("requested payload is malformed: {}", rejection.body_text());
+                return Err(Error::InvalidRequest(rejection.body_text()));
+            }
+        };
+        if payload.version.is_some() && payload.timestamp.is_some() {
+            tracing::error!("requested version and timestamp are conflicting");
+            return Err(Error::InvalidRequest(
+                "Only one of `version` and `timestamp` may be specified".into(),
+            ));
+        }
+        if matches!(payload.limit_hint, Some(limit_hint) if limit_hint <= 0) {
+            tracing::error!("requested limit hint is not positive");
+            return Err(Error::ValidationFailed);
+        }
+        if payload.dry_run == Some(true) && payload.page_token.is_some() {
+            tracing::error!("requested dry run is not paginated");
+            return Err(Error::InvalidRequest(
+                "`dryRun` may not be combined with `pageToken`".into(),
+            ));
+        }
+        if payload.dry_run == Some(true) && payload.starting_version.is_some() {
+            tracing::error!("requested dry run is not supported for table changes");
+            return Err(Error::InvalidRequest(
+                "`dryRun` may not be combined with `startingVersion`".into(),
+            ));
+        }
+        if payload.response_format == Some(QueryFormat::Arrow)
+            && (payload.starting_version.is_some()
+                || payload.page_token.is_some()
+                || payload.dry_run == Some(true))
+        {
+            tracing::error!("requested arrow response is only supported for table snapshots");
+            return Err(Error::InvalidRequest(
+                "`responseFormat` arrow may not be combined with `startingVersion`, `pageToken` or `dryRun`".into(),
+            ));
+        }
+        Ok(payload)
+    }
+
+    // NOTE: Read-only tables share their snapshots, but not the changes between them.
+    fn check_read_only(starting_version: Option<i64>, read_only: bool) -> Result<(), Error> {
+        if read_only && starting_version.is_some() {
+            tracing::error!("requested table changes are not shared for read-only tables");
+            return Err(Error::InvalidRequest(
+                "`startingVersion` may not be specified for read-only tables".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Maximum size of a query request body in bytes.
+pub fn body_limit() -> anyhow::Result<usize> {
+    config::fetch_or("max_request_bytes", config::MAX_REQUEST_BYTES)
+}
+
+/// Maximum size of the data files of a table answered with Arrow in bytes.
+pub fn arrow_limit() -> anyhow::Result<usize> {
+    config::fetch_or("max_arrow_bytes", config::MAX_ARROW_BYTES)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TimeTravel {
+    Version(i64),
+    Timestamp(DateTime<Utc>),
+}
+
+impl TimeTravel {
+    // NOTE: version precedes over timestamp, the timestamp is ignored if a version is given.
+    //       Requests specifying both are already rejected when validating the payload.
+    fn from(version: Option<i64>, timestamp: Option<DateTime<Utc>>) -> Option<Self> {
+        match (version, timestamp) {
+            (Some(version), _) => Some(Self::Version(version)),
+            (None, Some(timestamp)) => Some(Self::Timestamp(timestamp)),
+            (None, None) => None,
+        }
+    }
 }
 
 #[derive(Debug, serde::Deserialize, IntoParams)]
@@ -54,20 +199,34 @@ pub struct SharesSchemasTablesQueryPostParams {
     request_body = SharesSchemasTablesQueryPostRequest,
     params(SharesSchemasTablesQueryPostParams),
     responses(
-        (status = 200, description = "The tables were successfully returned.", body = String),
+        (status = 200, description = "The tables were successfully returned, or summarized for a dry run.", body = String),
         (status = 400, description = "The request is malformed.", body = ErrorMessage),
         (status = 401, description = "The request is unauthenticated. The bearer token is missing or incorrect.", body = ErrorMessage),
         (status = 403, description = "The request is forbidden from being fulfilled.", body = ErrorMessage),
         (status = 404, description = "The requested resource does not exist.", body = ErrorMessage),
+        (status = 413, description = "The request body exceeds the configured size limit.", body = ErrorMessage),
+        (status = 415, description = "The request body is not application/json.", body = ErrorMessage),
         (status = 500, description = "The request is not handled correctly due to a server error.", body = ErrorMessage),
+        (status = 503, description = "The delta table could not be loaded in time.", body = ErrorMessage),
     )
 )]
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, request_headers))]
 pub async fn post(
     Extension(state): Extension<SharedState>,
     Path(params): Path<SharesSchemasTablesQueryPostParams>,
-    Json(payload): Json<SharesSchemasTablesQueryPostRequest>,
+    request_headers: HeaderMap,
+    payload: Result<Json<SharesSchemasTablesQueryPostRequest>, JsonRejection>,
 ) -> Result<Response, Error> {
+    let payload = SharesSchemasTablesQueryPostRequest::try_from_json(payload)?;
+    let Ok(budget) = DeltalakeBudget::from_config() else {
+        tracing::error!("request is not handled correctly due to an invalid response budget");
+        return Err(anyhow!("error occured while configuring response budget").into());
+    };
+    let format = ResponseFormat::negotiate(
+        request_headers
+            .get(CAPABILITIES_HEADER_NAME)
+            .and_then(|value| value.to_str().ok()),
+    );
     let predicate_hints = if let Some(predicate_hints) = payload.predicate_hints {
         let predicate_hints: Result<Vec<SQLPartitionFilter>, _> = predicate_hints
             .into_iter()
@@ -100,6 +259,20 @@ pub async fn post(
     } else {
         None
     };
+    let page_token = if let Some(page_token) = &payload.page_token {
+        let Ok(page_token) = DeltalakePageCursor::from_token(page_token) else {
+            tracing::error!("requested page token is malformed");
+            return Err(Error::ValidationFailed);
+        };
+        Some(page_token)
+    } else {
+        None
+    };
+    // NOTE: A paginated table query is resumed at the version of its first page.
+    let version = match page_token {
+        Some(page_token) if payload.starting_version.is_none() => Some(page_token.version),
+        _ => payload.version,
+    };
     let Ok(share) = ShareName::try_new(params.share) else {
         tracing::error!("requested share data is malformed");
         return Err(Error::ValidationFailed);
@@ -123,32 +296,74 @@ pub async fn post(
         tracing::error!("requested table does not exist");
         return Err(Error::NotFound);
     };
+    SharesSchemasTablesQueryPostRequest::check_read_only(
+        payload.starting_version,
+        table.read_only,
+    )?;
+    let table_name = table.name.clone();
     let Ok(platform) = Platform::from_str(&table.location) else {
         tracing::error!("requested cloud platform is not supported");
         return Err(anyhow!("error occured while identifying cloud platform").into());
     };
-    let Ok(mut table) = DeltalakeUtility::open_table(&table.location).await else {
-        tracing::error!(
-            "request is not handled correctly due to a server error while loading delta table"
-        );
-        return Err(anyhow!("error occured while selecting table(s)").into());
-    };
-    let mut is_time_traveled = false;
-    // NOTE: version precedes over timestamp
-    if let Some(timestamp) = timestamp {
-        let Ok(_) = table.load_with_datetime(timestamp).await else {
-            tracing::error!("request is not handled correctly due to a server error while time-traveling delta table");
+    let mut table = match DeltalakeUtility::with_timeout(DeltalakeUtility::open_table_version(
+        &table.location,
+        version,
+    ))
+    .await
+    {
+        Ok(table) => table,
+        Err(e) if e.is::<DeltalakeTimeout>() => {
+            tracing::error!("request timed out while loading delta table: {}", e);
+            return Err(Error::ServiceUnavailable);
+        }
+        Err(_) => {
+            tracing::error!(
+                "request is not handled correctly due to a server error while loading delta table"
+            );
             return Err(anyhow!("error occured while selecting table(s)").into());
-        };
-        is_time_traveled = true;
+        }
+    };
+    let is_time_traveled = match TimeTravel::from(payload.version, timestamp) {
+        Some(time_travel) => {
+            let loaded = match time_travel {
+                // the table is already opened at the requested version.
+                TimeTravel::Version(_) => Ok(()),
+                TimeTravel::Timestamp(timestamp) => {
+                    DeltalakeUtility::with_timeout(table.load_with_datetime(timestamp)).await
+                }
+            };
+            match loaded {
+                Ok(_) => {}
+                Err(e) if e.is::<DeltalakeTimeout>() => {
+                    tracing::error!("request timed out while time-traveling delta table: {}", e);
+                    return Err(Error::ServiceUnavailable);
+                }
+                Err(_) => {
+                    tracing::error!("request is not handled correctly due to a server error while time-traveling delta table");
+                    return Err(anyhow!("error occured while selecting table(s)").into());
+                }
+            }
+            true
+        }
+        None => false,
+    };
+    if let Some(starting_version) = &payload.starting_version {
+        if *starting_version < 0 || *starting_version > table.version() {
+            tracing::error!("requested starting version is out of range");
+            return Err(Error::ValidationFailed);
+        }
     }
-    // NOTE: version precedes over timestamp
-    if let Some(version) = &payload.version {
-        let Ok(_) = table.load_version(*version).await else {
-            tracing::error!("request is not handled correctly due to a server error while time-traveling delta table");
-            return Err(anyhow!("error occured while selecting table(s)").into());
+    if let Some(page_token) = &page_token {
+        let in_range = match payload.starting_version {
+            Some(starting_version) => {
+                (starting_version..=table.version()).contains(&page_token.version)
+            }
+            None => page_token.version == table.version(),
         };
-        is_time_traveled = true;
+        if !in_range {
+            tracing::error!("requested page token does not belong to the queried versions");
+            return Err(Error::ValidationFailed);
+        }
     }
     let metadata = {
         let Ok(metadata) = table.get_metadata() else {
@@ -157,7 +372,72 @@ pub async fn post(
         };
         metadata.to_owned()
     };
+    if payload.dry_run == Some(true) {
+        // NOTE: A dry run only reports what would be returned, so no URLs are signed.
+        let summary = DeltalakeService::summary_from(
+            &table,
+            predicate_hints,
+            json_predicate_hints,
+            payload.limit_hint,
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(HEADER_NAME, table.version().into());
+        tracing::info!("delta table summary was successfully returned");
+        return Ok((StatusCode::OK, headers, Json(summary)).into_response());
+    }
+    let arrow_fallback = if payload.response_format == Some(QueryFormat::Arrow) {
+        let Ok(arrow_limit) = arrow_limit() else {
+            tracing::error!("request is not handled correctly due to an invalid arrow limit");
+            return Err(anyhow!("error occured while configuring arrow limit").into());
+        };
+        let fallback = DeltalakeService::arrow_fallback(&table, &metadata, arrow_limit);
+        let Some(fallback) = fallback else {
+            let data = match DeltalakeService::arrow_from(&table) {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::error!("request is not handled correctly due to a server error while reading delta table as arrow: {}", e);
+                    return Err(anyhow!("error occured while reading table").into());
+                }
+            };
+            // NOTE: Errors after the first chunk cut the stream short, which readers detect
+            //       by the missing end of the IPC stream.
+            let data = data.map_err(|e| {
+                if e.is::<DeltalakeTimeout>() {
+                    tracing::error!("request timed out while reading delta table: {}", e);
+                } else {
+                    tracing::error!("error occured while streaming delta table as arrow: {}", e);
+                }
+                BoxError::from(e)
+            });
+            let mut headers = HeaderMap::new();
+            headers.insert(HEADER_NAME, table.version().into());
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(ARROW_CONTENT_TYPE));
+            tracing::info!("delta table is returned as arrow");
+            return Ok((StatusCode::OK, headers, Body::from_stream(data)).into_response());
+        };
+        tracing::info!(
+            "requested arrow response falls back to file actions: {}",
+            fallback.as_str()
+        );
+        Some(fallback)
+    } else {
+        None
+    };
+    if format == ResponseFormat::Parquet && DeltalakeService::requires_delta_format(&table) {
+        tracing::error!("requested table has deletion vectors but delta format was not requested");
+        return Err(Error::InvalidRequest(
+            "table has deletion vectors, request responseformat=delta in the delta-sharing-capabilities header".into(),
+        ));
+    }
     let url_signer: Box<dyn Signer> = match &platform {
+        // NOTE: Proxied files are streamed through the server, so their URLs are not signed
+        //       by the cloud platform and recipients never access the object store directly.
+        _ if config::fetch::<bool>("proxy_files") => ProxyUtility::signer(
+            share.to_string(),
+            schema.to_string(),
+            table_name,
+            Duration::from_secs(config::fetch::<u64>("signed_url_ttl")),
+        ),
         Platform::Aws => {
             if let Some(creds) = &state.aws_credentials {
                 SignedUrlUtility::aws_signer(
@@ -182,15 +462,15 @@ pub async fn post(
         }
         Platform::Gcp => {
             if state.gcp_service_account.is_some() {
-                let creds = ServiceAccount::load_json_file(
+                let creds = std::fs::read(
                     std::env::var("GOOGLE_APPLICATION_CREDENTIALS")
                         .context("failed to load GCP credentials")?,
                 )
                 .context("failed to load GCP credentials")?;
                 SignedUrlUtility::gcp_signer(
-                    creds,
+                    &creds,
                     Duration::from_secs(config::fetch::<u64>("signed_url_ttl")),
-                )
+                )?
             } else {
                 tracing::error!("No credentials found for GCP GCS");
                 return Err(anyhow!("Error occurred while signing URLs").into());
@@ -204,26 +484,286 @@ pub async fn post(
 
     let mut headers = HeaderMap::new();
     headers.insert(HEADER_NAME, table.version().into());
+    headers.insert(
+        CAPABILITIES_HEADER_NAME,
+        HeaderValue::from_static(format.capabilities()),
+    );
     headers.insert(
         CONTENT_TYPE,
         HeaderValue::from_static("application/x-ndjson"),
     );
-    tracing::info!("delta table was successfully returned");
-    Ok((
-        StatusCode::OK,
-        headers,
-        JsonLines::new(
-            DeltalakeService::files_from(
-                table,
-                metadata,
-                predicate_hints,
-                json_predicate_hints,
-                payload.limit_hint,
-                is_time_traveled,
-                &url_signer,
-            )
-            .await,
-        ),
+    if let Some(fallback) = arrow_fallback {
+        headers.insert(
+            ARROW_FALLBACK_HEADER_NAME,
+            HeaderValue::from_static(fallback.as_str()),
+        );
+    }
+    if let Some(starting_version) = payload.starting_version {
+        let Ok(changes) = DeltalakeService::changes_from(
+            table,
+            metadata,
+            starting_version,
+            payload.include_historical_metadata.unwrap_or(false),
+            page_token,
+            budget,
+            format,
+            &url_signer,
+        )
+        .await
+        else {
+            tracing::error!("request is not handled correctly due to a server error while loading delta table changes");
+            return Err(anyhow!("error occured while selecting table(s)").into());
+        };
+        tracing::info!("delta table changes were successfully returned");
+        return Ok(NDJSONUtility::response(StatusCode::OK, headers, changes).await);
+    }
+    let Ok((files, stats)) = DeltalakeService::files_from(
+        table,
+        metadata,
+        predicate_hints,
+        json_predicate_hints,
+        payload.limit_hint,
+        payload.column_projection.as_deref(),
+        is_time_traveled,
+        page_token,
+        budget,
+        format,
+        &url_signer,
     )
-        .into_response())
+    .await
+    else {
+        tracing::error!("request is not handled correctly due to a server error while loading delta table files");
+        return Err(anyhow!("error occured while selecting table(s)").into());
+    };
+    // NOTE: The stats are opt-in, so the default response is unchanged.
+    if payload.include_pruning_stats == Some(true) {
+        headers.insert(FILES_SCANNED_HEADER_NAME, stats.files_scanned.into());
+        headers.insert(FILES_RETURNED_HEADER_NAME, stats.files_returned.into());
+        headers.insert(
+            FILES_PRUNED_HEADER_NAME,
+            stats.files_pruned_by_predicate.into(),
+        );
+    }
+    tracing::info!("delta table was successfully returned");
+    Ok(NDJSONUtility::response(StatusCode::OK, headers, files).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::{DefaultBodyLimit, FromRequest};
+    use axum::http::Request;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn parse(
+        content_type: &str,
+        body: &'static str,
+    ) -> Result<SharesSchemasTablesQueryPostRequest, Error> {
+        let request = Request::builder()
+            .method("POST")
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .expect("request should be built");
+        let payload = Json::<SharesSchemasTablesQueryPostRequest>::from_request(request, &()).await;
+        SharesSchemasTablesQueryPostRequest::try_from_json(payload)
+    }
+
+    async fn accept(
+        payload: Result<Json<SharesSchemasTablesQueryPostRequest>, JsonRejection>,
+    ) -> Result<StatusCode, Error> {
+        SharesSchemasTablesQueryPostRequest::try_from_json(payload).map(|_| StatusCode::OK)
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large() {
+        let app = Router::new()
+            .route("/query", post(accept))
+            .layer(DefaultBodyLimit::max(64));
+        let hints = "x".repeat(128);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/query")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(format!(
+                r#"{{"jsonPredicateHints": "{}"}}"#,
+                hints
+            )))
+            .expect("request should be built");
+        let response = app
+            .oneshot(request)
+            .await
+            .expect("request should be handled");
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should be readable");
+        let body: serde_json::Value = serde_json::from_slice(&bytes).expect("body should be JSON");
+        assert_eq!(body["errorCode"], "INVALID_PARAMETER_VALUE");
+    }
+
+    #[test]
+    fn test_default_body_limit() {
+        assert_eq!(body_limit().unwrap(), config::MAX_REQUEST_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_media_type() {
+        let payload = parse("text/plain", r#"{"version": 1}"#).await;
+        assert!(matches!(payload, Err(Error::UnsupportedMediaType)));
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_version_and_timestamp() {
+        let payload = parse(
+            "application/json",
+            r#"{"version": 1, "timestamp": "2022-01-01T00:00:00Z"}"#,
+        )
+        .await;
+        let Err(Error::InvalidRequest(message)) = payload else {
+            panic!("conflicting version and timestamp should be rejected");
+        };
+        assert!(message.contains("version"));
+        assert!(message.contains("timestamp"));
+    }
+
+    #[tokio::test]
+    async fn test_valid_payload() {
+        let payload = parse("application/json", r#"{"version": 1, "limitHint": 10}"#)
+            .await
+            .expect("payload should be valid");
+        assert_eq!(payload.version, Some(1));
+        assert_eq!(payload.limit_hint, Some(10));
+        let payload = parse("application/json", r#"{"version": "latest"}"#).await;
+        assert!(matches!(payload, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_limit_hint() {
+        let payload = parse("application/json", r#"{"limitHint": 1}"#)
+            .await
+            .expect("payload should be valid");
+        assert_eq!(payload.limit_hint, Some(1));
+        for body in [r#"{"limitHint": 0}"#, r#"{"limitHint": -10}"#] {
+            let payload = parse("application/json", body).await;
+            assert!(matches!(payload, Err(Error::ValidationFailed)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arrow_response_format() {
+        let payload = parse("application/json", r#"{"responseFormat": "arrow"}"#)
+            .await
+            .expect("payload should be valid");
+        assert_eq!(payload.response_format, Some(QueryFormat::Arrow));
+        for body in [
+            r#"{"responseFormat": "arrow", "startingVersion": 1}"#,
+            r#"{"responseFormat": "arrow", "dryRun": true}"#,
+        ] {
+            let payload = parse("application/json", body).await;
+            assert!(matches!(payload, Err(Error::InvalidRequest(_))));
+        }
+        for body in [
+            r#"{"responseFormat": "parquet"}"#,
+            r#"{"responseFormat": "parquet", "startingVersion": 1}"#,
+            r#"{"responseFormat": "ndjson"}"#,
+        ] {
+            let payload = parse("application/json", body)
+                .await
+                .expect("payload should be valid");
+            assert_eq!(
+                payload.response_format.unwrap_or_default(),
+                QueryFormat::Parquet
+            );
+        }
+        let payload = parse("application/json", r#"{"responseFormat": "csv"}"#).await;
+        assert!(payload.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_table() {
+        let changes = parse("application/json", r#"{"startingVersion": 1}"#)
+            .await
+            .expect("payload should be valid");
+        let Err(Error::InvalidRequest(message)) =
+            SharesSchemasTablesQueryPostRequest::check_read_only(changes.starting_version, true)
+        else {
+            panic!("changes of a read-only table should be rejected");
+        };
+        assert!(message.contains("startingVersion"));
+        assert!(SharesSchemasTablesQueryPostRequest::check_read_only(
+            changes.starting_version,
+            false
+        )
+        .is_ok());
+        let snapshot = parse("application/json", r#"{"version": 1}"#)
+            .await
+            .expect("payload should be valid");
+        assert!(SharesSchemasTablesQueryPostRequest::check_read_only(
+            snapshot.starting_version,
+            true
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_column_projection() {
+        let payload = parse(
+            "application/json",
+            r#"{"columnProjection": ["id", "name"]}"#,
+        )
+        .await
+        .expect("payload should be valid");
+        assert_eq!(
+            payload.column_projection,
+            Some(vec!["id".to_string(), "name".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let payload = parse("application/json", r#"{"dryRun": true, "limitHint": 10}"#)
+            .await
+            .expect("payload should be valid");
+        assert_eq!(payload.dry_run, Some(true));
+        let payload = parse(
+            "application/json",
+            r#"{"dryRun": true, "startingVersion": 1}"#,
+        )
+        .await;
+        assert!(matches!(payload, Err(Error::InvalidRequest(_))));
+        let payload = parse(
+            "application/json",
+            r#"{"dryRun": false, "startingVersion": 1}"#,
+        )
+        .await;
+        assert!(payload.is_ok());
+        let payload = parse(
+            "application/json",
+            r#"{"dryRun": true, "pageToken": "1_0"}"#,
+        )
+        .await;
+        assert!(matches!(payload, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_time_travel_precedence() {
+        let timestamp = DeltalakeUtility::datetime_yyyy_mm_dd_hh_mm_ss("2022/01/01 00:00:00")
+            .expect("timestamp should be parsed");
+        assert_eq!(
+            TimeTravel::from(Some(3), Some(timestamp)),
+            Some(TimeTravel::Version(3))
+        );
+        assert_eq!(
+            TimeTravel::from(Some(3), None),
+            Some(TimeTravel::Version(3))
+        );
+        assert_eq!(
+            TimeTravel::from(None, Some(timestamp)),
+            Some(TimeTravel::Timestamp(timestamp))
+        );
+        assert_eq!(TimeTravel::from(None, None), None);
+    }
 }
@@ -11,6 +11,7 @@ use crate::server::entities::table::Name as TableName;
 use crate::server::routers::SharedState;
 use crate::server::services::error::Error;
 use crate::server::services::table::Service as TableService;
+use crate::server::utilities::deltalake::Timeout as DeltalakeTimeout;
 use crate::server::utilities::deltalake::Utility as DeltalakeUtility;
 
 const HEADER_NAME: &str = "Delta-Table-Version";
@@ -42,6 +43,7 @@ pub struct SharesSchemasTablesVersionGetQuery {
         (status = 403, description = "The request is forbidden from being fulfilled.", body = ErrorMessage),
         (status = 404, description = "The requested resource does not exist.", body = ErrorMessage),
         (status = 500, description = "The request is not handled correctly due to a server error.", body = ErrorMessage),
+        (status = 503, description = "The delta table could not be loaded in time.", body = ErrorMessage),
     )
 )]
 #[tracing::instrument(skip(state))]
@@ -84,17 +86,32 @@ pub async fn get(
         tracing::error!("requested table does not exist");
         return Err(Error::NotFound);
     };
-    let Ok(mut table) = DeltalakeUtility::open_table(&table.location).await else {
-        tracing::error!(
-            "request is not handled correctly due to a server error while loading delta table"
-        );
-        return Err(anyhow!("error occured while selecting table(s)").into());
-    };
-    if let Some(starting_timestamp) = starting_timestamp {
-        let Ok(_) = table.load_with_datetime(starting_timestamp).await else {
-            tracing::error!("request is not handled correctly due to a server error while time-traveling delta table");
-            return Err(anyhow!("error occured while selecting table(s)").into());
+    let mut table =
+        match DeltalakeUtility::with_timeout(DeltalakeUtility::open_table(&table.location)).await {
+            Ok(table) => table,
+            Err(e) if e.is::<DeltalakeTimeout>() => {
+                tracing::error!("request timed out while loading delta table: {}", e);
+                return Err(Error::ServiceUnavailable);
+            }
+            Err(_) => {
+                tracing::error!(
+                "request is not handled correctly due to a server error while loading delta table"
+            );
+                return Err(anyhow!("error occured while selecting table(s)").into());
+            }
         };
+    if let Some(starting_timestamp) = starting_timestamp {
+        match DeltalakeUtility::with_timeout(table.load_with_datetime(starting_timestamp)).await {
+            Ok(_) => {}
+            Err(e) if e.is::<DeltalakeTimeout>() => {
+                tracing::error!("request timed out while time-traveling delta table: {}", e);
+                return Err(Error::ServiceUnavailable);
+            }
+            Err(_) => {
+                tracing::error!("request is not handled correctly due to a server error while time-traveling delta table");
+                return Err(anyhow!("error occured while selecting table(s)").into());
+            }
+        }
     }
     let mut headers = HeaderMap::new();
     headers.insert(HEADER_NAME, table.version().into());
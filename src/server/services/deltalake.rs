@@ -1,16 +1,22 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use axum::body::Bytes;
 use axum::BoxError;
-use deltalake::protocol::Add;
+use deltalake::arrow::datatypes::Schema as ArrowSchema;
+use deltalake::protocol::{Action, Add, Protocol as ProtocolAction, Remove};
 use deltalake::schema::Schema;
-use deltalake::table::DeltaTableMetaData;
-use deltalake::DeltaTable;
-use futures_util::stream::Stream;
+use deltalake::table::{DeltaTableMetaData, PeekCommit};
+use deltalake::{DeltaTable, ObjectStore, Path as ObjectPath};
+use futures_util::stream::{Stream, StreamExt};
 use md5;
 use serde_json::json;
 use utoipa::ToSchema;
 
+use crate::config;
+use crate::server::utilities::arrow::Utility as ArrowUtility;
 use crate::server::utilities::deltalake::Utility as DeltalakeUtility;
 use crate::server::utilities::json::PartitionFilter as JSONPartitionFilter;
 use crate::server::utilities::json::Utility as JSONUtility;
@@ -42,6 +48,111 @@ impl Protocol {
     }
 }
 
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaProtocolDetail {
+    pub min_reader_version: i32,
+    pub min_writer_version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reader_features: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub writer_features: Option<Vec<String>>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaProtocolWrapper {
+    pub delta_protocol: DeltaProtocolDetail,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaProtocol {
+    pub protocol: DeltaProtocolWrapper,
+}
+
+impl DeltaProtocol {
+    fn from(table: &DeltaTable) -> Self {
+        Self {
+            protocol: DeltaProtocolWrapper {
+                delta_protocol: DeltaProtocolDetail {
+                    min_reader_version: table.get_min_reader_version(),
+                    min_writer_version: table.get_min_writer_version(),
+                    reader_features: table.get_reader_features().map(Self::features),
+                    writer_features: table.get_writer_features().map(Self::features),
+                },
+            },
+        }
+    }
+
+    fn at(protocol: &ProtocolAction) -> Self {
+        Self {
+            protocol: DeltaProtocolWrapper {
+                delta_protocol: DeltaProtocolDetail {
+                    min_reader_version: protocol.min_reader_version,
+                    min_writer_version: protocol.min_writer_version,
+                    reader_features: protocol.reader_features.as_ref().map(Self::features),
+                    writer_features: protocol.writer_features.as_ref().map(Self::features),
+                },
+            },
+        }
+    }
+
+    fn features<'a, F: serde::Serialize + 'a>(
+        features: impl IntoIterator<Item = &'a F>,
+    ) -> Vec<String> {
+        let mut features = features
+            .into_iter()
+            .filter_map(|f| match json!(f) {
+                serde_json::Value::String(f) => Some(f),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        features.sort();
+        features
+    }
+}
+
+/// Header in which clients list their capabilities, e.g. the response formats they accept.
+pub const CAPABILITIES_HEADER_NAME: &str = "delta-sharing-capabilities";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    #[default]
+    Parquet,
+    Delta,
+}
+
+impl ResponseFormat {
+    /// Negotiate the response format from the capabilities header of a request.
+    ///
+    /// Clients list the formats they accept in order of preference, e.g.
+    /// `responseformat=delta,parquet`. The first supported format is used, responses fall
+    /// back to the parquet format if no supported format is requested.
+    pub fn negotiate(capabilities: Option<&str>) -> Self {
+        capabilities
+            .into_iter()
+            .flat_map(|capabilities| capabilities.split(';'))
+            .filter_map(|capability| capability.split_once('='))
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case("responseformat"))
+            .flat_map(|(_, formats)| formats.split(','))
+            .find_map(|format| match format.trim().to_ascii_lowercase().as_str() {
+                "parquet" => Some(Self::Parquet),
+                "delta" => Some(Self::Delta),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Capabilities header announcing the format of a response.
+    pub fn capabilities(&self) -> &'static str {
+        match self {
+            ResponseFormat::Parquet => "responseformat=parquet",
+            ResponseFormat::Delta => "responseformat=delta",
+        }
+    }
+}
+
 #[derive(serde::Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Format {
@@ -75,7 +186,7 @@ pub struct Metadata {
 }
 
 impl Metadata {
-    fn from(metadata: DeltaTableMetaData) -> Self {
+    fn at(metadata: DeltaTableMetaData, version: Option<i64>) -> Self {
         Self {
             meta_data: MetadataDetail {
                 id: metadata.id,
@@ -87,7 +198,7 @@ impl Metadata {
                 schema_string: json!(metadata.schema).to_string(),
                 partition_columns: metadata.partition_columns,
                 configuration: metadata.configuration,
-                version: None,
+                version,
                 size: None,
                 num_files: None,
             },
@@ -95,6 +206,42 @@ impl Metadata {
     }
 }
 
+/// Metadata of a table in the delta format, wrapping the metaData action of the delta log.
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaMetadataDetail {
+    #[schema(value_type = Object)]
+    pub delta_metadata: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaMetadata {
+    pub meta_data: DeltaMetadataDetail,
+}
+
+impl DeltaMetadata {
+    fn at(metadata: DeltaTableMetaData, version: Option<i64>) -> Self {
+        Self {
+            meta_data: DeltaMetadataDetail {
+                delta_metadata: json!({
+                    "id": metadata.id,
+                    "name": metadata.name,
+                    "description": metadata.description,
+                    "format": { "provider": metadata.format.get_provider(), "options": {} },
+                    "schemaString": json!(metadata.schema).to_string(),
+                    "partitionColumns": metadata.partition_columns,
+                    "configuration": metadata.configuration,
+                    "createdTime": metadata.created_time,
+                }),
+                version,
+            },
+        }
+    }
+}
+
 #[derive(serde::Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDetail {
@@ -110,6 +257,56 @@ pub struct FileDetail {
     pub timestamp: Option<i64>,
 }
 
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+fn z85_decode(encoded: &str) -> Option<Vec<u8>> {
+    if encoded.len() % 5 != 0 {
+        return None;
+    }
+    encoded
+        .as_bytes()
+        .chunks(5)
+        .try_fold(Vec::new(), |mut bytes, chunk| {
+            let value = chunk.iter().try_fold(0u64, |value, c| {
+                let digit = Z85_ALPHABET.iter().position(|a| a == c)?;
+                Some(value * 85 + digit as u64)
+            })?;
+            bytes.extend_from_slice(&u32::try_from(value).ok()?.to_be_bytes());
+            Some(bytes)
+        })
+}
+
+/// Absolute location of the file of a deletion vector descriptor.
+///
+/// Relative deletion vectors are stored as `<prefix>/deletion_vector_<uuid>.bin` in the table
+/// directory, where the UUID is Z85 encoded in the last 20 characters of `pathOrInlineDv`.
+/// Inline deletion vectors have no file.
+fn deletion_vector_location(table_uri: &str, descriptor: &serde_json::Value) -> Option<String> {
+    let path = descriptor.get("pathOrInlineDv")?.as_str()?;
+    match descriptor.get("storageType")?.as_str()? {
+        "p" => Some(path.to_owned()),
+        "u" => {
+            let split = path.len().checked_sub(20)?;
+            if !path.is_char_boundary(split) {
+                return None;
+            }
+            let (prefix, encoded) = path.split_at(split);
+            let uuid = uuid::Uuid::from_slice(&z85_decode(encoded)?).ok()?;
+            let table_uri = table_uri.trim_end_matches('/');
+            if prefix.is_empty() {
+                Some(format!("{}/deletion_vector_{}.bin", table_uri, uuid))
+            } else {
+                Some(format!(
+                    "{}/{}/deletion_vector_{}.bin",
+                    table_uri, prefix, uuid
+                ))
+            }
+        }
+        _ => None,
+    }
+}
+
 #[derive(serde::Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
@@ -137,14 +334,399 @@ impl File {
         }
     }
 
-    async fn sign<S: Signer>(&mut self, url_signer: &S) {
-        self.file.url = url_signer.sign(&self.file.url).await.unwrap();
+    async fn sign<S: Signer>(&mut self, url_signer: &S) -> Result<()> {
+        self.file.url = url_signer
+            .sign(&self.file.url)
+            .await
+            .context("failed to sign data file URL")?;
+        Ok(())
+    }
+}
+
+/// File in the delta format, wrapping the add or remove action of the delta log.
+///
+/// The path of the action is replaced by the signed URL of the file.
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaFileDetail {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[schema(value_type = Object)]
+    pub delta_single_action: serde_json::Value,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaFile {
+    pub file: DeltaFileDetail,
+}
+
+impl DeltaFile {
+    fn from_add(add: Add, version: Option<i64>, timestamp: Option<i64>) -> Self {
+        Self {
+            file: DeltaFileDetail {
+                id: format!("{:x}", md5::compute(add.path.as_bytes())),
+                version,
+                timestamp,
+                delta_single_action: json!({ "add": add }),
+            },
+        }
+    }
+
+    fn from_remove(remove: Remove, version: i64, timestamp: i64) -> Self {
+        Self {
+            file: DeltaFileDetail {
+                id: format!("{:x}", md5::compute(remove.path.as_bytes())),
+                version: Some(version),
+                timestamp: Some(timestamp),
+                delta_single_action: json!({ "remove": remove }),
+            },
+        }
     }
+
+    // NOTE: Deletion vector files are signed like data files, so readers never resolve
+    //       their paths relative to the table location. Signers referencing relative paths,
+    //       like the proxy, get the path of the deletion vector within the table instead.
+    async fn sign<S: Signer>(&mut self, table_uri: &str, url_signer: &S) -> Result<()> {
+        for action in ["add", "remove"] {
+            let Some(action) = self.file.delta_single_action.get_mut(action) else {
+                continue;
+            };
+            if let Some(serde_json::Value::String(path)) = action.get_mut("path") {
+                *path = url_signer
+                    .sign(path)
+                    .await
+                    .context("failed to sign data file URL")?;
+            }
+            let Some(descriptor) = action.get_mut("deletionVector") else {
+                continue;
+            };
+            if let Some(location) = deletion_vector_location(table_uri, descriptor) {
+                let location = if url_signer.relative_paths() {
+                    location
+                        .strip_prefix(table_uri.trim_end_matches('/'))
+                        .and_then(|path| path.strip_prefix('/'))
+                        .map(str::to_owned)
+                        .context("deletion vector outside of the table cannot be referenced")?
+                } else {
+                    location
+                };
+                descriptor["storageType"] = json!("p");
+                let url = url_signer
+                    .sign(&location)
+                    .await
+                    .context("failed to sign deletion vector URL")?;
+                descriptor["pathOrInlineDv"] = json!(url);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddFileDetail {
+    pub id: String,
+    pub url: String,
+    pub partition_values: HashMap<String, String>,
+    pub size: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<String>,
+    pub version: i64,
+    pub timestamp: i64,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddFile {
+    pub add: AddFileDetail,
+}
+
+impl AddFile {
+    fn from(add: Add, version: i64, timestamp: i64) -> Self {
+        let mut partition_values: HashMap<String, String> = HashMap::new();
+        for (k, v) in add.partition_values.into_iter() {
+            if let Some(v) = v {
+                partition_values.insert(k, v);
+            }
+        }
+        Self {
+            add: AddFileDetail {
+                id: format!("{:x}", md5::compute(add.path.as_bytes())),
+                url: add.path,
+                partition_values,
+                size: add.size,
+                stats: add.stats,
+                version,
+                timestamp,
+            },
+        }
+    }
+
+    async fn sign<S: Signer>(&mut self, url_signer: &S) -> Result<()> {
+        self.add.url = url_signer
+            .sign(&self.add.url)
+            .await
+            .context("failed to sign data file URL")?;
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFileDetail {
+    pub id: String,
+    pub url: String,
+    pub partition_values: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    pub version: i64,
+    pub timestamp: i64,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFile {
+    pub remove: RemoveFileDetail,
+}
+
+impl RemoveFile {
+    fn from(remove: Remove, version: i64, timestamp: i64) -> Self {
+        let mut partition_values: HashMap<String, String> = HashMap::new();
+        for (k, v) in remove.partition_values.unwrap_or_default().into_iter() {
+            if let Some(v) = v {
+                partition_values.insert(k, v);
+            }
+        }
+        Self {
+            remove: RemoveFileDetail {
+                id: format!("{:x}", md5::compute(remove.path.as_bytes())),
+                url: remove.path,
+                partition_values,
+                size: remove.size,
+                version,
+                timestamp,
+            },
+        }
+    }
+
+    async fn sign<S: Signer>(&mut self, url_signer: &S) -> Result<()> {
+        self.remove.url = url_signer
+            .sign(&self.remove.url)
+            .await
+            .context("failed to sign data file URL")?;
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EndStreamActionDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EndStreamAction {
+    pub end_stream_action: EndStreamActionDetail,
+}
+
+/// Position of an action in a query response, from which a truncated response is resumed.
+///
+/// Actions are ordered by the version of the table they belong to and by their index within
+/// that version, i.e. within the snapshot for table queries and within the commit for changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PageCursor {
+    pub version: i64,
+    pub index: usize,
+}
+
+impl PageCursor {
+    pub fn new(version: i64, index: usize) -> Self {
+        Self { version, index }
+    }
+
+    pub fn to_token(self) -> String {
+        format!("{}_{}", self.version, self.index)
+    }
+
+    pub fn from_token(token: &str) -> Result<Self> {
+        let (version, index) = token
+            .split_once('_')
+            .context("page token should consist of a version and an index")?;
+        Ok(Self {
+            version: version
+                .parse()
+                .context("failed to parse page token version")?,
+            index: index.parse().context("failed to parse page token index")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    pub max_file_actions: usize,
+    pub max_bytes: usize,
+}
+
+impl Budget {
+    pub fn new(max_file_actions: usize, max_bytes: usize) -> Self {
+        Self {
+            max_file_actions,
+            max_bytes,
+        }
+    }
+
+    /// Budget configured by `max_file_actions` and `max_response_bytes`.
+    ///
+    /// Both limits must be positive, a page could not hold any action otherwise.
+    pub fn from_config() -> Result<Self> {
+        let max_file_actions = config::fetch_or("max_file_actions", config::MAX_FILE_ACTIONS)?;
+        let max_bytes = config::fetch_or("max_response_bytes", config::MAX_RESPONSE_BYTES)?;
+        for (flag, value) in [
+            ("max_file_actions", max_file_actions),
+            ("max_response_bytes", max_bytes),
+        ] {
+            if value == 0 {
+                return Err(anyhow!(
+                    r#"configuration flag "{}" ({}) must be positive"#,
+                    flag,
+                    config::env_var(flag)
+                ));
+            }
+        }
+        Ok(Self::new(max_file_actions, max_bytes))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub num_files: usize,
+    pub size_in_bytes: i64,
+    // NOTE: The number of records is only known if all selected files carry statistics.
+    pub num_records: Option<i64>,
+    pub version: i64,
+}
+
+/// Effectiveness of file skipping for a query, reported to clients on request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruningStats {
+    /// Number of files in the table snapshot.
+    pub files_scanned: usize,
+    /// Number of file actions in the response.
+    pub files_returned: usize,
+    /// Number of files skipped by the SQL or JSON predicate hints.
+    pub files_pruned_by_predicate: usize,
+}
+
+/// Reason why a query for Arrow is answered with file actions instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowFallback {
+    /// The data files of the table exceed the size budget for Arrow responses.
+    TooLarge,
+    /// The values of partition columns are not stored in the data files.
+    Partitioned,
+    /// Deleted rows would be returned, as deletion vectors are not applied.
+    DeletionVectors,
+}
+
+impl ArrowFallback {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArrowFallback::TooLarge => "too-large",
+            ArrowFallback::Partitioned => "partitioned",
+            ArrowFallback::DeletionVectors => "deletion-vectors",
+        }
+    }
+}
+
+enum Change {
+    Add(Add, i64, i64),
+    Remove(Remove, i64, i64),
+    Protocol(ProtocolAction),
+    Metadata(DeltaTableMetaData, i64),
 }
 
 pub struct Service;
 
 impl Service {
+    // NOTE: A truncated response ends with the token of the first omitted action, so resuming
+    //       from it neither duplicates nor skips actions. Each page carries at least one action,
+    //       even one exceeding the budget on its own, so paginated queries always make progress.
+    //       Lines are built, and their files signed, one at a time until the budget is exhausted,
+    //       so at most one line beyond the returned ones is ever built.
+    async fn apply_budget<F>(
+        lines: impl IntoIterator<Item = (PageCursor, F)>,
+        budget: Budget,
+    ) -> Result<Vec<Result<serde_json::Value, BoxError>>>
+    where
+        F: Future<Output = Result<serde_json::Value>>,
+    {
+        let mut bytes = 0;
+        let mut ret = Vec::new();
+        let mut next_page = None;
+        for (cursor, line) in lines {
+            if !ret.is_empty() && ret.len() >= budget.max_file_actions {
+                next_page = Some(cursor);
+                break;
+            }
+            let line = line.await?;
+            // NOTE: Each line is terminated by a newline in the ndjson response.
+            bytes += line.to_string().len() + 1;
+            if !ret.is_empty() && bytes > budget.max_bytes {
+                next_page = Some(cursor);
+                break;
+            }
+            ret.push(Ok(line));
+        }
+        if let Some(cursor) = next_page {
+            tracing::info!(
+                "response was paginated after {} file actions to stay within budget",
+                ret.len()
+            );
+            ret.push(Ok(json!(EndStreamAction {
+                end_stream_action: EndStreamActionDetail {
+                    next_page_token: Some(cursor.to_token()),
+                    error_message: None,
+                },
+            })));
+        }
+        Ok(ret)
+    }
+
+    pub fn protocol_from(table: &DeltaTable, format: ResponseFormat) -> serde_json::Value {
+        match format {
+            // NOTE: The parquet format carries the version of the Delta Sharing protocol itself.
+            ResponseFormat::Parquet => json!(Protocol::new()),
+            ResponseFormat::Delta => json!(DeltaProtocol::from(table)),
+        }
+    }
+
+    fn protocol_at(protocol: &ProtocolAction, format: ResponseFormat) -> serde_json::Value {
+        match format {
+            ResponseFormat::Parquet => json!(Protocol::new()),
+            ResponseFormat::Delta => json!(DeltaProtocol::at(protocol)),
+        }
+    }
+
+    fn metadata_at(
+        metadata: DeltaTableMetaData,
+        version: Option<i64>,
+        format: ResponseFormat,
+    ) -> serde_json::Value {
+        match format {
+            ResponseFormat::Parquet => json!(Metadata::at(metadata, version)),
+            ResponseFormat::Delta => json!(DeltaMetadata::at(metadata, version)),
+        }
+    }
+
     fn filter_with_limit_hint(files: Vec<Add>, limit_hint: Option<i32>) -> Vec<Add> {
         // NOTE: The server may try its best to filter files in a BEST EFFORT mode.
         let Some(limit_hint) = limit_hint else {
@@ -220,15 +802,158 @@ impl Service {
         files
     }
 
+    fn select_files(
+        table: &DeltaTable,
+        predicate_hints: Option<Vec<SQLPartitionFilter>>,
+        json_predicate_hints: Option<JSONPartitionFilter>,
+        limit_hint: Option<i32>,
+    ) -> (Vec<Add>, PruningStats) {
+        let files = table.get_state().files().to_owned();
+        let files_scanned = files.len();
+        let files = Self::filter_with_sql_hints(files, table.schema().cloned(), predicate_hints);
+        let files =
+            Self::filter_with_json_hints(files, table.schema().cloned(), json_predicate_hints);
+        let files_pruned_by_predicate = files_scanned - files.len();
+        let files = Self::filter_with_limit_hint(files, limit_hint);
+        let stats = PruningStats {
+            files_scanned,
+            files_returned: files.len(),
+            files_pruned_by_predicate,
+        };
+        (files, stats)
+    }
+
+    /// Limit the column statistics of a file to the projected columns.
+    ///
+    /// The number of records and statistics of nested fields of projected columns are kept.
+    /// Statistics that cannot be parsed are returned unchanged.
+    fn project_stats(
+        stats: Option<String>,
+        column_projection: Option<&[String]>,
+    ) -> Option<String> {
+        let (Some(stats), Some(columns)) = (stats.as_ref(), column_projection) else {
+            return stats;
+        };
+        let Ok(serde_json::Value::Object(mut parsed)) = serde_json::from_str(stats) else {
+            return Some(stats.to_owned());
+        };
+        for key in ["minValues", "maxValues", "nullCount"] {
+            if let Some(serde_json::Value::Object(values)) = parsed.get_mut(key) {
+                values.retain(|column, _| columns.contains(column));
+            }
+        }
+        Some(serde_json::Value::Object(parsed).to_string())
+    }
+
+    pub fn summary_from(
+        table: &DeltaTable,
+        predicate_hints: Option<Vec<SQLPartitionFilter>>,
+        json_predicate_hints: Option<JSONPartitionFilter>,
+        limit_hint: Option<i32>,
+    ) -> Summary {
+        let (files, _) =
+            Self::select_files(table, predicate_hints, json_predicate_hints, limit_hint);
+        Summary {
+            num_files: files.len(),
+            size_in_bytes: files.iter().map(|f| f.size).sum(),
+            num_records: files
+                .iter()
+                .map(|f| DeltalakeUtility::get_stats(f).ok().map(|s| s.num_records))
+                .sum(),
+            version: table.version(),
+        }
+    }
+
+    /// Whether `table` can only be read by clients which negotiated the delta response format.
+    ///
+    /// Deletion vectors cannot be expressed in parquet format file actions, so readers of
+    /// the parquet format would return deleted rows.
+    pub fn requires_delta_format(table: &DeltaTable) -> bool {
+        let reader_features = table
+            .get_reader_features()
+            .map(DeltaProtocol::features)
+            .unwrap_or_default();
+        reader_features.iter().any(|f| f == "deletionVectors")
+            || table
+                .get_state()
+                .files()
+                .iter()
+                .any(|f| f.deletion_vector.is_some())
+    }
+
+    /// Reason why the snapshot of `table` is not served as Arrow, if any.
+    pub fn arrow_fallback(
+        table: &DeltaTable,
+        metadata: &DeltaTableMetaData,
+        max_bytes: usize,
+    ) -> Option<ArrowFallback> {
+        let files = table.get_state().files();
+        if !metadata.partition_columns.is_empty() {
+            return Some(ArrowFallback::Partitioned);
+        }
+        if files.iter().any(|f| f.deletion_vector.is_some()) {
+            return Some(ArrowFallback::DeletionVectors);
+        }
+        let size = files.iter().map(|f| f.size).sum::<i64>();
+        if usize::try_from(size).map_or(true, |size| size > max_bytes) {
+            return Some(ArrowFallback::TooLarge);
+        }
+        None
+    }
+
+    /// Read all rows of the snapshot of `table` as an Arrow IPC stream.
+    ///
+    /// Predicate and limit hints are not applied, as they only allow skipping files. The
+    /// data files are read one at a time as the stream is consumed, each within the table
+    /// timeout.
+    pub fn arrow_from(table: &DeltaTable) -> Result<impl Stream<Item = Result<Bytes>> + Send> {
+        let schema = table
+            .get_schema()
+            .context("failed to load delta table schema")?;
+        let schema = ArrowSchema::try_from(schema)
+            .context("failed to convert delta table schema to arrow schema")?;
+        let paths = table
+            .get_state()
+            .files()
+            .iter()
+            .map(|file| {
+                ObjectPath::from_url_path(&file.path).context("data file path is malformed")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let store = table.object_store();
+        let files = futures::stream::iter(paths)
+            .then(move |path| {
+                let store = store.clone();
+                DeltalakeUtility::with_timeout(async move {
+                    store
+                        .get(&path)
+                        .await
+                        .context("failed to read data file")?
+                        .bytes()
+                        .await
+                        .context("failed to read data file")
+                })
+            })
+            .boxed();
+        ArrowUtility::ipc(Arc::new(schema), files)
+    }
+
     pub async fn files_from<S: Signer>(
         table: DeltaTable,
         metadata: DeltaTableMetaData,
         predicate_hints: Option<Vec<SQLPartitionFilter>>,
         json_predicate_hints: Option<JSONPartitionFilter>,
         limit_hint: Option<i32>,
+        column_projection: Option<&[String]>,
         is_time_traveled: bool,
+        page_token: Option<PageCursor>,
+        budget: Budget,
+        format: ResponseFormat,
         url_signer: &S,
-    ) -> impl Stream<Item = Result<serde_json::Value, BoxError>> {
+    ) -> Result<(
+        impl Stream<Item = Result<serde_json::Value, BoxError>>,
+        PruningStats,
+    )> {
         let version = if is_time_traveled {
             Some(table.version())
         } else {
@@ -239,38 +964,176 @@ impl Service {
         } else {
             None
         };
-        let files = Self::filter_with_sql_hints(
-            table.get_state().files().to_owned(),
-            table.schema().cloned(),
-            predicate_hints,
-        );
-        let files =
-            Self::filter_with_json_hints(files, table.schema().cloned(), json_predicate_hints);
-        let files = Self::filter_with_limit_hint(files, limit_hint);
-        let futures = files
+        let (files, mut stats) =
+            Self::select_files(&table, predicate_hints, json_predicate_hints, limit_hint);
+        // NOTE: The files of a snapshot are always selected in the same order, so a page token
+        //       refers to the same file as long as the table is queried at the same version.
+        let start = page_token.map_or(0, |cursor| cursor.index);
+        let snapshot_version = table.version();
+        let table_uri = table.table_uri();
+        let table_uri = table_uri.as_str();
+        let lines = files
             .into_iter()
-            .map(|f| async {
-                let mut file = File::from(f, version, timestamp);
-                file.sign(url_signer).await;
-                Ok::<serde_json::Value, BoxError>(json!(file))
-            })
-            .collect::<Vec<_>>();
-        let mut files = futures::future::join_all(futures).await;
+            .enumerate()
+            .skip(start)
+            .map(|(index, mut f)| {
+                f.stats = Self::project_stats(f.stats, column_projection);
+                let line = async move {
+                    let line = match format {
+                        ResponseFormat::Parquet => {
+                            let mut file = File::from(f, version, timestamp);
+                            file.sign(url_signer).await?;
+                            json!(file)
+                        }
+                        ResponseFormat::Delta => {
+                            let mut file = DeltaFile::from_add(f, version, timestamp);
+                            file.sign(table_uri, url_signer).await?;
+                            json!(file)
+                        }
+                    };
+                    Ok::<_, anyhow::Error>(line)
+                };
+                (PageCursor::new(snapshot_version, index), line)
+            });
+        // NOTE: A file that cannot be signed fails the whole response instead of a single line.
+        let mut files = Self::apply_budget(lines, budget).await?;
+        // NOTE: The budget may truncate the response, which ends with an end stream action then.
+        stats.files_returned = files
+            .iter()
+            .filter(|line| matches!(line, Ok(value) if value.get("file").is_some()))
+            .count();
 
         let mut ret = vec![
-            Ok(json!(Protocol::new())),
-            Ok(json!(Metadata::from(metadata))),
+            Ok(Self::protocol_from(&table, format)),
+            Ok(Self::metadata_at(metadata, None, format)),
         ];
         ret.append(&mut files);
-        futures_util::stream::iter(ret)
+        Ok((futures_util::stream::iter(ret), stats))
+    }
+
+    /// Changes of `table` from the action at `start` on.
+    ///
+    /// The delta log is read from the commit of `start`, so resuming a paginated response
+    /// does not read the commits of the previous pages again.
+    async fn changes_since(
+        table: &DeltaTable,
+        start: PageCursor,
+        include_historical_metadata: bool,
+    ) -> Result<Vec<(PageCursor, Change)>> {
+        let mut changes = Vec::new();
+        for current in start.version..=table.version() {
+            let PeekCommit::New(version, actions) = table
+                .peek_next_commit(current - 1)
+                .await
+                .context("failed to read delta log")?
+            else {
+                break;
+            };
+            // NOTE: The commit timestamp is taken from commitInfo and falls back to file timestamps.
+            let timestamp = actions.iter().find_map(|action| match action {
+                Action::commitInfo(info) => info.timestamp,
+                _ => None,
+            });
+            let mut index = 0;
+            for action in actions {
+                let change = match action {
+                    Action::add(add) => {
+                        let timestamp = timestamp.unwrap_or(add.modification_time);
+                        Change::Add(add, version, timestamp)
+                    }
+                    Action::remove(remove) => {
+                        let timestamp = timestamp.or(remove.deletion_timestamp).unwrap_or_default();
+                        Change::Remove(remove, version, timestamp)
+                    }
+                    Action::protocol(protocol) if include_historical_metadata => {
+                        Change::Protocol(protocol)
+                    }
+                    Action::metaData(metadata) if include_historical_metadata => {
+                        let metadata = DeltaTableMetaData::try_from(metadata)
+                            .context("failed to parse delta table metadata")?;
+                        Change::Metadata(metadata, version)
+                    }
+                    _ => continue,
+                };
+                let cursor = PageCursor::new(version, index);
+                if cursor >= start {
+                    changes.push((cursor, change));
+                }
+                index += 1;
+            }
+        }
+        Ok(changes)
+    }
+
+    pub async fn changes_from<S: Signer>(
+        table: DeltaTable,
+        metadata: DeltaTableMetaData,
+        starting_version: i64,
+        include_historical_metadata: bool,
+        page_token: Option<PageCursor>,
+        budget: Budget,
+        format: ResponseFormat,
+        url_signer: &S,
+    ) -> Result<impl Stream<Item = Result<serde_json::Value, BoxError>>> {
+        let start = PageCursor::new(starting_version, 0);
+        let start = page_token.map_or(start, |token| token.max(start));
+        let changes = Self::changes_since(&table, start, include_historical_metadata).await?;
+        let table = &table;
+        let table_uri = table.table_uri();
+        let table_uri = table_uri.as_str();
+        let lines = changes.into_iter().map(|(cursor, c)| {
+            let line = async move {
+                let line = match (c, format) {
+                    (Change::Add(add, version, timestamp), ResponseFormat::Parquet) => {
+                        let mut file = AddFile::from(add, version, timestamp);
+                        file.sign(url_signer).await?;
+                        json!(file)
+                    }
+                    (Change::Add(add, version, timestamp), ResponseFormat::Delta) => {
+                        let mut file = DeltaFile::from_add(add, Some(version), Some(timestamp));
+                        file.sign(table_uri, url_signer).await?;
+                        json!(file)
+                    }
+                    (Change::Remove(remove, version, timestamp), ResponseFormat::Parquet) => {
+                        let mut file = RemoveFile::from(remove, version, timestamp);
+                        file.sign(url_signer).await?;
+                        json!(file)
+                    }
+                    (Change::Remove(remove, version, timestamp), ResponseFormat::Delta) => {
+                        let mut file = DeltaFile::from_remove(remove, version, timestamp);
+                        file.sign(table_uri, url_signer).await?;
+                        json!(file)
+                    }
+                    // NOTE: Historical metadata is emitted in place, so readers can apply
+                    //       schema changes between the file actions of the range.
+                    (Change::Protocol(protocol), format) => Self::protocol_at(&protocol, format),
+                    (Change::Metadata(metadata, version), format) => {
+                        Self::metadata_at(metadata, Some(version), format)
+                    }
+                };
+                Ok::<_, anyhow::Error>(line)
+            };
+            (cursor, line)
+        });
+        // NOTE: A file that cannot be signed fails the whole response instead of a single line.
+        let mut files = Self::apply_budget(lines, budget).await?;
+
+        let mut ret = vec![
+            Ok(Self::protocol_from(table, format)),
+            Ok(Self::metadata_at(metadata, None, format)),
+        ];
+        ret.append(&mut files);
+        Ok(futures_util::stream::iter(ret))
     }
 
     pub fn metadata_from(
+        table: &DeltaTable,
         metadata: DeltaTableMetaData,
+        format: ResponseFormat,
     ) -> impl Stream<Item = Result<serde_json::Value, BoxError>> {
         let ret = vec![
-            Ok(json!(Protocol::new())),
-            Ok(json!(Metadata::from(metadata))),
+            Ok(Self::protocol_from(table, format)),
+            Ok(Self::metadata_at(metadata, None, format)),
         ];
         futures_util::stream::iter(ret)
     }
@@ -278,9 +1141,1153 @@ impl Service {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use deltalake::arrow::array::{Int64Array, StringArray};
+    use deltalake::arrow::datatypes::{DataType, Field};
+    use deltalake::arrow::ipc::reader::StreamReader;
+    use deltalake::arrow::record_batch::RecordBatch;
+    use deltalake::parquet::arrow::ArrowWriter;
+    use futures::StreamExt;
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::server::middlewares::jwt::Keys as JwtKeys;
+    use crate::server::utilities::proxy::{ProxySigner, Utility as ProxyUtility};
+
+    struct NoopSigner;
+
+    #[async_trait::async_trait]
+    impl Signer for NoopSigner {
+        async fn sign(&self, path: &str) -> Result<String> {
+            Ok(path.to_owned())
+        }
+    }
+
+    struct FailingSigner;
+
+    #[async_trait::async_trait]
+    impl Signer for FailingSigner {
+        async fn sign(&self, _: &str) -> Result<String> {
+            Err(anyhow!("failed to parse URL"))
+        }
+    }
+
+    fn commit(location: &Path, version: i64, actions: Vec<serde_json::Value>) {
+        let log = location.join("_delta_log");
+        std::fs::create_dir_all(&log).expect("delta log directory should be created");
+        let lines = actions
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(log.join(format!("{:020}.json", version)), lines)
+            .expect("delta log commit should be written");
+    }
+
+    fn add(path: &str, timestamp: i64) -> serde_json::Value {
+        json!({
+            "add": {
+                "path": path,
+                "partitionValues": {},
+                "size": 100,
+                "modificationTime": timestamp,
+                "dataChange": true
+            }
+        })
+    }
+
+    fn remove(path: &str, timestamp: i64) -> serde_json::Value {
+        json!({
+            "remove": {
+                "path": path,
+                "deletionTimestamp": timestamp,
+                "dataChange": true
+            }
+        })
+    }
+
+    fn commit_info(timestamp: i64) -> serde_json::Value {
+        json!({ "commitInfo": { "timestamp": timestamp } })
+    }
+
+    fn metadata(fields: &[(&str, &str)]) -> serde_json::Value {
+        let fields = fields
+            .iter()
+            .map(|(name, data_type)| {
+                json!({ "name": name, "type": data_type, "nullable": true, "metadata": {} })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "metaData": {
+                "id": "00000000-0000-0000-0000-000000000000",
+                "format": { "provider": "parquet", "options": {} },
+                "schemaString": json!({ "type": "struct", "fields": fields }).to_string(),
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1000
+            }
+        })
+    }
+
+    fn create_table(location: &Path) {
+        commit(
+            location,
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[("id", "long")]),
+                add("part-00000.parquet", 1000),
+            ],
+        );
+        commit(
+            location,
+            1,
+            vec![commit_info(2000), add("part-00001.parquet", 2000)],
+        );
+        commit(
+            location,
+            2,
+            vec![
+                commit_info(3000),
+                remove("part-00000.parquet", 3000),
+                add("part-00002.parquet", 3000),
+            ],
+        );
+    }
 
     #[tokio::test]
     async fn test() {
         println!("TEST DELTALAKE!!!");
     }
+
+    #[tokio::test]
+    async fn test_changes_from_starting_version() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        create_table(dir.path());
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        assert_eq!(table.version(), 2);
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let lines = Service::changes_from(
+            table,
+            metadata,
+            1,
+            false,
+            None,
+            Budget::new(10, 1024),
+            ResponseFormat::Parquet,
+            &NoopSigner,
+        )
+        .await
+        .expect("changes should be loaded")
+        .map(|line| line.expect("line should be serialized"))
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].get("protocol").is_some());
+        assert!(lines[1].get("metaData").is_some());
+        let changes = lines[2..]
+            .iter()
+            .map(|line| {
+                if let Some(add) = line.get("add") {
+                    (
+                        "add",
+                        add["url"].as_str().unwrap(),
+                        add["version"].as_i64().unwrap(),
+                    )
+                } else {
+                    let remove = &line["remove"];
+                    (
+                        "remove",
+                        remove["url"].as_str().unwrap(),
+                        remove["version"].as_i64().unwrap(),
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("add", "part-00001.parquet", 1),
+                ("remove", "part-00000.parquet", 2),
+                ("add", "part-00002.parquet", 2),
+            ]
+        );
+        assert_eq!(lines[2]["add"]["timestamp"].as_i64(), Some(2000));
+    }
+
+    #[tokio::test]
+    async fn test_changes_from_historical_metadata() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        create_table(dir.path());
+        commit(
+            dir.path(),
+            3,
+            vec![
+                commit_info(4000),
+                metadata(&[("id", "long"), ("name", "string")]),
+                add("part-00003.parquet", 4000),
+            ],
+        );
+        commit(
+            dir.path(),
+            4,
+            vec![
+                commit_info(5000),
+                json!({
+                    "protocol": {
+                        "minReaderVersion": 3,
+                        "minWriterVersion": 7,
+                        "readerFeatures": ["deletionVectors"],
+                        "writerFeatures": ["deletionVectors"]
+                    }
+                }),
+            ],
+        );
+        let location = dir.path().to_str().unwrap();
+        let changes = |starting_version: i64,
+                       include_historical_metadata: bool,
+                       format: ResponseFormat| async move {
+            let table = deltalake::open_table(location)
+                .await
+                .expect("delta table should be opened");
+            let metadata = table
+                .get_metadata()
+                .expect("delta table metadata should be loaded")
+                .to_owned();
+            Service::changes_from(
+                table,
+                metadata,
+                starting_version,
+                include_historical_metadata,
+                None,
+                Budget::new(20, 1024 * 1024),
+                format,
+                &NoopSigner,
+            )
+            .await
+            .expect("changes should be loaded")
+            .map(|line| line.expect("line should be serialized"))
+            .collect::<Vec<_>>()
+            .await
+        };
+        let kinds = |lines: &[serde_json::Value]| {
+            lines
+                .iter()
+                .map(|line| {
+                    line.as_object()
+                        .and_then(|line| line.keys().next().cloned())
+                        .expect("line should have an action")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let lines = changes(1, false, ResponseFormat::Parquet).await;
+        assert_eq!(
+            kinds(&lines),
+            vec!["protocol", "metaData", "add", "remove", "add", "add"]
+        );
+
+        let lines = changes(1, true, ResponseFormat::Parquet).await;
+        assert_eq!(
+            kinds(&lines),
+            vec!["protocol", "metaData", "add", "remove", "add", "metaData", "add", "protocol"]
+        );
+        assert_eq!(lines[5]["metaData"]["version"].as_i64(), Some(3));
+        let schema_string = lines[5]["metaData"]["schemaString"]
+            .as_str()
+            .expect("schema string should be a string");
+        assert!(schema_string.contains("\"string\""));
+        assert_eq!(lines[6]["add"]["version"].as_i64(), Some(3));
+
+        // historical protocols are emitted as committed, not as the current protocol.
+        let lines = changes(0, true, ResponseFormat::Delta).await;
+        let protocols = lines
+            .iter()
+            .filter_map(|line| line["protocol"].get("deltaProtocol"))
+            .map(|protocol| {
+                (
+                    protocol["minReaderVersion"].as_i64().unwrap(),
+                    protocol["minWriterVersion"].as_i64().unwrap(),
+                    protocol.get("readerFeatures").cloned(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            protocols,
+            vec![
+                (3, 7, Some(json!(["deletionVectors"]))),
+                (1, 2, None),
+                (3, 7, Some(json!(["deletionVectors"]))),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summary_from() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        create_table(dir.path());
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        let summary = Service::summary_from(&table, None, None, None);
+        assert_eq!(
+            summary,
+            Summary {
+                num_files: 2,
+                size_in_bytes: 200,
+                num_records: None,
+                version: 2,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&summary).expect("summary should be serialized"),
+            json!({
+                "numFiles": 2,
+                "sizeInBytes": 200,
+                "numRecords": null,
+                "version": 2
+            })
+        );
+
+        let with_stats = |path: &str, num_records: i64| {
+            let mut action = add(path, 1000);
+            action["add"]["stats"] = json!(json!({
+                "numRecords": num_records,
+                "minValues": {},
+                "maxValues": {},
+                "nullCount": {}
+            })
+            .to_string());
+            action
+        };
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[("id", "long")]),
+                with_stats("part-00000.parquet", 5),
+                with_stats("part-00001.parquet", 7),
+            ],
+        );
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        let summary = Service::summary_from(&table, None, None, None);
+        assert_eq!(summary.num_files, 2);
+        assert_eq!(summary.num_records, Some(12));
+        assert_eq!(summary.version, 0);
+
+        // NOTE: The limit hint applies to the summary as it does to the file actions.
+        let summary = Service::summary_from(&table, None, None, Some(1));
+        assert_eq!(summary.num_files, 1);
+        assert_eq!(summary.size_in_bytes, 100);
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let stats = json!({
+            "numRecords": 5,
+            "minValues": { "id": 1, "name": "a", "address": { "city": "x" } },
+            "maxValues": { "id": 5, "name": "e", "address": { "city": "z" } },
+            "nullCount": { "id": 0, "name": 1, "address": { "city": 0 } }
+        })
+        .to_string();
+        let columns = vec!["id".to_string(), "address".to_string()];
+        let projected = Service::project_stats(Some(stats.clone()), Some(&columns))
+            .expect("stats should be kept");
+        let projected: serde_json::Value =
+            serde_json::from_str(&projected).expect("stats should be JSON");
+        assert_eq!(
+            projected,
+            json!({
+                "numRecords": 5,
+                "minValues": { "id": 1, "address": { "city": "x" } },
+                "maxValues": { "id": 5, "address": { "city": "z" } },
+                "nullCount": { "id": 0, "address": { "city": 0 } }
+            })
+        );
+
+        assert_eq!(
+            Service::project_stats(Some(stats.clone()), None),
+            Some(stats)
+        );
+        assert_eq!(Service::project_stats(None, Some(&columns)), None);
+        assert_eq!(
+            Service::project_stats(Some("not json".into()), Some(&columns)),
+            Some("not json".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_from_column_projection() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        let mut action = add("part-00000.parquet", 1000);
+        action["add"]["stats"] = json!(json!({
+            "numRecords": 5,
+            "minValues": { "id": 1, "name": "a" },
+            "maxValues": { "id": 5, "name": "e" },
+            "nullCount": { "id": 0, "name": 0 }
+        })
+        .to_string());
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[("id", "long"), ("name", "string")]),
+                action,
+            ],
+        );
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let columns = vec!["id".to_string()];
+        let lines = Service::files_from(
+            table,
+            metadata,
+            None,
+            None,
+            None,
+            Some(&columns),
+            false,
+            None,
+            Budget::new(10, 1024 * 1024),
+            ResponseFormat::Parquet,
+            &NoopSigner,
+        )
+        .await
+        .expect("files should be loaded")
+        .0
+        .map(|line| line.expect("line should be serialized"))
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(lines.len(), 3);
+        let stats: serde_json::Value = serde_json::from_str(
+            lines[2]["file"]["stats"]
+                .as_str()
+                .expect("file should have stats"),
+        )
+        .expect("stats should be JSON");
+        assert_eq!(stats["numRecords"], 5);
+        assert_eq!(stats["minValues"], json!({ "id": 1 }));
+        assert_eq!(stats["maxValues"], json!({ "id": 5 }));
+        assert_eq!(stats["nullCount"], json!({ "id": 0 }));
+    }
+
+    #[tokio::test]
+    async fn test_arrow_from() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .expect("record batch should be created");
+        let file = std::fs::File::create(dir.path().join("part-00000.parquet"))
+            .expect("data file should be created");
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None)
+            .expect("parquet writer should be created");
+        writer
+            .write(&batch)
+            .expect("record batch should be written");
+        writer.close().expect("data file should be finished");
+        let size = std::fs::metadata(dir.path().join("part-00000.parquet"))
+            .expect("data file should exist")
+            .len();
+        let mut action = add("part-00000.parquet", 1000);
+        action["add"]["size"] = json!(size);
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[("id", "long"), ("name", "string")]),
+                action,
+            ],
+        );
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        let mut metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+
+        assert_eq!(
+            Service::arrow_fallback(&table, &metadata, 1024 * 1024),
+            None
+        );
+        let data = Service::arrow_from(&table)
+            .expect("delta table should be read as arrow")
+            .map(|chunk| chunk.expect("arrow stream should be encoded"))
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+        let reader =
+            StreamReader::try_new(data.as_slice(), None).expect("arrow stream should be read");
+        assert_eq!(reader.schema(), schema);
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .expect("record batches should be read");
+        assert_eq!(batches, vec![batch]);
+
+        assert_eq!(
+            Service::arrow_fallback(&table, &metadata, size as usize - 1),
+            Some(ArrowFallback::TooLarge)
+        );
+        metadata.partition_columns = vec!["name".into()];
+        assert_eq!(
+            Service::arrow_fallback(&table, &metadata, 1024 * 1024),
+            Some(ArrowFallback::Partitioned)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_from_pruning_stats() {
+        let with_range = |path: &str, min: i64, max: i64| {
+            let mut action = add(path, 1000);
+            action["add"]["stats"] = json!(json!({
+                "numRecords": max - min + 1,
+                "minValues": { "id": min },
+                "maxValues": { "id": max },
+                "nullCount": { "id": 0 }
+            })
+            .to_string());
+            action
+        };
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[("id", "long")]),
+                with_range("part-00000.parquet", 1, 5),
+                with_range("part-00001.parquet", 6, 10),
+                with_range("part-00002.parquet", 11, 15),
+            ],
+        );
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let predicate = SQLUtility::parse("id > 7".into()).expect("predicate should be parsed");
+        let (lines, stats) = Service::files_from(
+            table,
+            metadata,
+            Some(vec![predicate]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            Budget::new(10, 1024 * 1024),
+            ResponseFormat::Parquet,
+            &NoopSigner,
+        )
+        .await
+        .expect("files should be loaded");
+        let lines = lines.collect::<Vec<_>>().await;
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            stats,
+            PruningStats {
+                files_scanned: 3,
+                files_returned: 2,
+                files_pruned_by_predicate: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_from_deletion_vectors() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        let deletion_vector = json!({
+            "storageType": "u",
+            "pathOrInlineDv": "ab^-aqEH.-t@S}K{vb[*k^",
+            "offset": 4,
+            "sizeInBytes": 40,
+            "cardinality": 6
+        });
+        let mut action = add("part-00000.parquet", 1000);
+        action["add"]["deletionVector"] = deletion_vector.clone();
+        let mut metadata_action = metadata(&[("id", "long")]);
+        metadata_action["metaData"]["configuration"] =
+            json!({ "delta.enableDeletionVectors": "true" });
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({
+                    "protocol": {
+                        "minReaderVersion": 3,
+                        "minWriterVersion": 7,
+                        "readerFeatures": ["deletionVectors"],
+                        "writerFeatures": ["deletionVectors"]
+                    }
+                }),
+                metadata_action,
+                action,
+                add("part-00001.parquet", 1000),
+            ],
+        );
+        let location = dir.path().to_str().unwrap();
+        let lines = |format: ResponseFormat| async move {
+            let table = deltalake::open_table(location)
+                .await
+                .expect("delta table should be opened");
+            assert!(Service::requires_delta_format(&table));
+            let metadata = table
+                .get_metadata()
+                .expect("delta table metadata should be loaded")
+                .to_owned();
+            Service::files_from(
+                table,
+                metadata,
+                None,
+                None,
+                None,
+                None,
+                false,
+                None,
+                Budget::new(10, 1024 * 1024),
+                format,
+                &NoopSigner,
+            )
+            .await
+            .expect("files should be loaded")
+            .0
+            .map(|line| line.expect("line should be serialized"))
+            .collect::<Vec<_>>()
+            .await
+        };
+
+        // parquet format file actions cannot carry deletion vectors.
+        let parquet = lines(ResponseFormat::Parquet).await;
+        assert_eq!(parquet.len(), 4);
+        assert!(parquet[2..]
+            .iter()
+            .all(|line| line["file"].get("deletionVector").is_none()));
+
+        let delta = lines(ResponseFormat::Delta).await;
+        assert_eq!(delta.len(), 4);
+        let files = delta[2..]
+            .iter()
+            .map(|line| {
+                let action = &line["file"]["deltaSingleAction"]["add"];
+                (
+                    action["path"].as_str().unwrap(),
+                    action.get("deletionVector"),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let descriptor = files["part-00000.parquet"].expect("deletion vector should be kept");
+        assert_eq!(descriptor["storageType"], "p");
+        assert!(descriptor["pathOrInlineDv"]
+            .as_str()
+            .unwrap()
+            .ends_with("/ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"));
+        assert_eq!(descriptor["offset"], deletion_vector["offset"]);
+        assert_eq!(descriptor["cardinality"], deletion_vector["cardinality"]);
+        assert_eq!(files["part-00001.parquet"], None);
+        assert_eq!(
+            deletion_vector_location("/tmp/table/", &deletion_vector),
+            Some("/tmp/table/ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_from_proxied_deletion_vectors() {
+        static KEYS: Lazy<JwtKeys> = Lazy::new(|| JwtKeys::new(b"secret"));
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        let mut action = add("part-00000.parquet", 1000);
+        action["add"]["deletionVector"] = json!({
+            "storageType": "u",
+            "pathOrInlineDv": "ab^-aqEH.-t@S}K{vb[*k^",
+            "offset": 1,
+            "sizeInBytes": 4,
+            "cardinality": 1
+        });
+        let mut metadata_action = metadata(&[("id", "long")]);
+        metadata_action["metaData"]["configuration"] =
+            json!({ "delta.enableDeletionVectors": "true" });
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({
+                    "protocol": {
+                        "minReaderVersion": 3,
+                        "minWriterVersion": 7,
+                        "readerFeatures": ["deletionVectors"],
+                        "writerFeatures": ["deletionVectors"]
+                    }
+                }),
+                metadata_action,
+                action,
+            ],
+        );
+        std::fs::create_dir_all(dir.path().join("ab")).expect("directory should be created");
+        std::fs::write(
+            dir.path()
+                .join("ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"),
+            b"DV01",
+        )
+        .expect("deletion vector should be written");
+        let location = dir.path().to_str().unwrap();
+        let table = deltalake::open_table(location)
+            .await
+            .expect("delta table should be opened");
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let signer = ProxySigner {
+            share: "share1".into(),
+            schema: "schema1".into(),
+            table: "table1".into(),
+            endpoint: "http://localhost:8080".into(),
+            expiration: std::time::Duration::from_secs(300),
+            keys: &KEYS,
+        };
+        let lines = Service::files_from(
+            table,
+            metadata,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+            Budget::new(10, 1024 * 1024),
+            ResponseFormat::Delta,
+            &signer,
+        )
+        .await
+        .expect("files should be loaded")
+        .0
+        .map(|line| line.expect("line should be serialized"))
+        .collect::<Vec<_>>()
+        .await;
+        let descriptor = &lines[2]["file"]["deltaSingleAction"]["add"]["deletionVector"];
+        let token = descriptor["pathOrInlineDv"]
+            .as_str()
+            .and_then(|url| url.strip_prefix("http://localhost:8080/proxy/"))
+            .expect("deletion vector should reference the proxy");
+        let claims = ProxyUtility::decode(token, &KEYS).expect("proxy token should be valid");
+        assert_eq!(
+            claims.path,
+            "ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"
+        );
+
+        // the deletion vector is read from the object store of the table by the proxy.
+        let table = deltalake::open_table(location)
+            .await
+            .expect("delta table should be opened");
+        let response = ProxyUtility::response(table.object_store().as_ref(), &claims.path)
+            .await
+            .expect("deletion vector should be proxied");
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should be readable");
+        assert_eq!(body.as_ref(), b"DV01");
+    }
+
+    #[tokio::test]
+    async fn test_sign_failure() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        create_table(dir.path());
+        let location = dir.path().to_str().unwrap();
+        for format in [ResponseFormat::Parquet, ResponseFormat::Delta] {
+            let table = deltalake::open_table(location)
+                .await
+                .expect("delta table should be opened");
+            let metadata = table
+                .get_metadata()
+                .expect("delta table metadata should be loaded")
+                .to_owned();
+            let files = Service::files_from(
+                table,
+                metadata.clone(),
+                None,
+                None,
+                None,
+                None,
+                false,
+                None,
+                Budget::new(10, 1024 * 1024),
+                format,
+                &FailingSigner,
+            )
+            .await;
+            assert!(files.is_err());
+
+            let table = deltalake::open_table(location)
+                .await
+                .expect("delta table should be opened");
+            let changes = Service::changes_from(
+                table,
+                metadata,
+                1,
+                false,
+                None,
+                Budget::new(10, 1024 * 1024),
+                format,
+                &FailingSigner,
+            )
+            .await;
+            assert!(changes.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_budget() {
+        let builds = &std::sync::atomic::AtomicUsize::new(0);
+        let lines = || {
+            builds.store(0, std::sync::atomic::Ordering::SeqCst);
+            (0..10000).map(|i| {
+                let line = async move {
+                    builds.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let line = json!({ "file": { "url": format!("part-{:05}.parquet", i) } });
+                    Ok::<_, anyhow::Error>(line)
+                };
+                (PageCursor::new(3, i), line)
+            })
+        };
+        let built = || builds.load(std::sync::atomic::Ordering::SeqCst);
+        let size = json!({ "file": { "url": "part-00000.parquet" } })
+            .to_string()
+            .len()
+            + 1;
+        let serialized = |files: Result<Vec<Result<serde_json::Value, BoxError>>>| {
+            files
+                .expect("lines should be built")
+                .into_iter()
+                .map(|line| line.expect("line should be serialized"))
+                .collect::<Vec<_>>()
+        };
+
+        // only the lines within the budget are built.
+        let files = serialized(Service::apply_budget(lines(), Budget::new(100, usize::MAX)).await);
+        assert_eq!(files.len(), 101);
+        assert!(files[..100].iter().all(|line| line.get("file").is_some()));
+        assert_eq!(
+            files[100],
+            json!({ "endStreamAction": { "nextPageToken": "3_100" } })
+        );
+        assert_eq!(built(), 100);
+
+        let files =
+            serialized(Service::apply_budget(lines(), Budget::new(usize::MAX, size * 5)).await);
+        assert_eq!(files.len(), 6);
+        assert_eq!(files[5]["endStreamAction"]["nextPageToken"], "3_5");
+        assert_eq!(built(), 6);
+
+        let files =
+            serialized(Service::apply_budget(lines().take(3), Budget::new(3, size * 3)).await);
+        assert_eq!(files.len(), 3);
+
+        // an action exceeding the budget on its own is returned on a page of its own.
+        let files = serialized(Service::apply_budget(lines(), Budget::new(100, size - 1)).await);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["file"]["url"], "part-00000.parquet");
+        assert_eq!(files[1]["endStreamAction"]["nextPageToken"], "3_1");
+        let files =
+            serialized(Service::apply_budget(lines().skip(1), Budget::new(100, size - 1)).await);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[1]["endStreamAction"]["nextPageToken"], "3_2");
+
+        let files = serialized(Service::apply_budget(lines(), Budget::new(0, usize::MAX)).await);
+        assert_eq!(files.len(), 2);
+        assert_eq!(built(), 1);
+
+        // a line that cannot be built fails the page.
+        let failing = lines().map(|(cursor, line)| {
+            let line = async move {
+                line.await?;
+                Err::<serde_json::Value, _>(anyhow!("failed to sign data file URL"))
+            };
+            (cursor, line)
+        });
+        assert!(Service::apply_budget(failing, Budget::new(100, usize::MAX))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_page_cursor() {
+        let cursor = PageCursor::new(12, 34);
+        assert_eq!(PageCursor::from_token(&cursor.to_token()).unwrap(), cursor);
+        assert!(PageCursor::new(12, 35) > cursor);
+        assert!(PageCursor::new(13, 0) > cursor);
+        for token in ["", "12", "12_", "x_34", "12_-1"] {
+            assert!(PageCursor::from_token(token).is_err(), "{}", token);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_from_pages() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+                metadata(&[]),
+            ],
+        );
+        for version in 1..=40 {
+            let timestamp = 1000 + version * 1000;
+            commit(
+                dir.path(),
+                version,
+                vec![
+                    commit_info(timestamp),
+                    add(&format!("part-{:05}-a.parquet", version), timestamp),
+                    add(&format!("part-{:05}-b.parquet", version), timestamp),
+                ],
+            );
+        }
+        let location = dir.path().to_str().unwrap();
+        let page = |page_token: Option<PageCursor>, budget: Budget| async move {
+            let table = deltalake::open_table(location)
+                .await
+                .expect("delta table should be opened");
+            let metadata = table
+                .get_metadata()
+                .expect("delta table metadata should be loaded")
+                .to_owned();
+            Service::changes_from(
+                table,
+                metadata,
+                1,
+                false,
+                page_token,
+                budget,
+                ResponseFormat::Parquet,
+                &NoopSigner,
+            )
+            .await
+            .expect("changes should be loaded")
+            .map(|line| line.expect("line should be serialized"))
+            .collect::<Vec<_>>()
+            .await
+        };
+        let urls = |lines: &[serde_json::Value]| {
+            lines
+                .iter()
+                .filter_map(|line| line["add"]["url"].as_str().map(str::to_owned))
+                .collect::<Vec<_>>()
+        };
+
+        let all = page(None, Budget::new(1000, usize::MAX)).await;
+        assert_eq!(urls(&all).len(), 80);
+        assert!(all.iter().all(|line| line.get("endStreamAction").is_none()));
+
+        // NOTE: The budget ends the first page within the commit of version 13.
+        let first = page(None, Budget::new(25, usize::MAX)).await;
+        assert_eq!(urls(&first).len(), 25);
+        let token = first
+            .last()
+            .and_then(|line| line["endStreamAction"]["nextPageToken"].as_str())
+            .expect("first page should end with a page token");
+        assert_eq!(token, "13_1");
+        let token = PageCursor::from_token(token).expect("page token should be parsed");
+        let second = page(Some(token), Budget::new(1000, usize::MAX)).await;
+        assert!(second[0].get("protocol").is_some());
+        assert!(second[1].get("metaData").is_some());
+        assert!(second
+            .iter()
+            .all(|line| line.get("endStreamAction").is_none()));
+
+        let mut pages = urls(&first);
+        pages.extend(urls(&second));
+        assert_eq!(pages, urls(&all));
+
+        // the commits of previous pages are not read again.
+        let table = deltalake::open_table(location)
+            .await
+            .expect("delta table should be opened");
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        for version in 1..13 {
+            std::fs::remove_file(
+                dir.path()
+                    .join("_delta_log")
+                    .join(format!("{:020}.json", version)),
+            )
+            .expect("delta log commit should be removed");
+        }
+        let resumed = Service::changes_from(
+            table,
+            metadata,
+            1,
+            false,
+            Some(token),
+            Budget::new(1000, usize::MAX),
+            ResponseFormat::Parquet,
+            &NoopSigner,
+        )
+        .await
+        .expect("changes should be loaded")
+        .map(|line| line.expect("line should be serialized"))
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(urls(&resumed), urls(&second));
+    }
+
+    #[test]
+    fn test_response_format_negotiate() {
+        assert_eq!(ResponseFormat::negotiate(None), ResponseFormat::Parquet);
+        assert_eq!(
+            ResponseFormat::negotiate(Some("responseformat=delta")),
+            ResponseFormat::Delta
+        );
+        assert_eq!(
+            ResponseFormat::negotiate(Some(
+                "readerfeatures=deletionvectors;responseFormat=Delta,parquet"
+            )),
+            ResponseFormat::Delta
+        );
+        assert_eq!(
+            ResponseFormat::negotiate(Some("responseformat=iceberg,parquet,delta")),
+            ResponseFormat::Parquet
+        );
+        assert_eq!(
+            ResponseFormat::negotiate(Some("responseformat=iceberg")),
+            ResponseFormat::Parquet
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_from_table_features() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        commit(
+            dir.path(),
+            0,
+            vec![
+                commit_info(1000),
+                json!({
+                    "protocol": {
+                        "minReaderVersion": 3,
+                        "minWriterVersion": 7,
+                        "readerFeatures": ["deletionVectors", "columnMapping"],
+                        "writerFeatures": ["deletionVectors", "columnMapping", "appendOnly"]
+                    }
+                }),
+                metadata(&[("id", "long")]),
+                add("part-00000.parquet", 1000),
+            ],
+        );
+        let table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+        assert_eq!(
+            Service::protocol_from(&table, ResponseFormat::Delta),
+            json!({
+                "protocol": {
+                    "deltaProtocol": {
+                        "minReaderVersion": 3,
+                        "minWriterVersion": 7,
+                        "readerFeatures": ["columnMapping", "deletionVectors"],
+                        "writerFeatures": ["appendOnly", "columnMapping", "deletionVectors"]
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            Service::protocol_from(&table, ResponseFormat::Parquet),
+            json!({ "protocol": { "minReaderVersion": 1 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_from_time_traveled_version() {
+        let dir = testutils::io::tempdir().expect("temporary directory should be created");
+        create_table(dir.path());
+        let mut metadata_action = metadata(&[("id", "long"), ("name", "string")]);
+        metadata_action["metaData"]["partitionColumns"] = json!(["name"]);
+        commit(dir.path(), 3, vec![commit_info(4000), metadata_action]);
+        let mut table = deltalake::open_table(dir.path().to_str().unwrap())
+            .await
+            .expect("delta table should be opened");
+
+        let columns = |line: &serde_json::Value| -> Vec<String> {
+            let schema_string = line["metaData"]["schemaString"]
+                .as_str()
+                .expect("schema string should be a string");
+            assert!(!schema_string.is_empty());
+            let schema: serde_json::Value =
+                serde_json::from_str(schema_string).expect("schema string should be JSON");
+            schema["fields"]
+                .as_array()
+                .expect("schema should have fields")
+                .iter()
+                .map(|field| field["name"].as_str().unwrap().to_owned())
+                .collect()
+        };
+
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let lines = Service::metadata_from(&table, metadata, ResponseFormat::Parquet)
+            .map(|line| line.expect("line should be serialized"))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1]["metaData"]["id"],
+            "00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(lines[1]["metaData"]["partitionColumns"], json!(["name"]));
+        assert_eq!(columns(&lines[1]), vec!["id", "name"]);
+
+        table
+            .load_version(2)
+            .await
+            .expect("delta table version should be loaded");
+        let metadata = table
+            .get_metadata()
+            .expect("delta table metadata should be loaded")
+            .to_owned();
+        let lines = Service::metadata_from(&table, metadata, ResponseFormat::Parquet)
+            .map(|line| line.expect("line should be serialized"))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(lines[1]["metaData"]["partitionColumns"], json!([]));
+        assert_eq!(columns(&lines[1]), vec!["id"]);
+    }
 }
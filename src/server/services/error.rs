@@ -20,6 +20,10 @@ pub enum Error {
     Conflict,
     EnvironmentVariableMissing,
     NotImplemented,
+    ServiceUnavailable,
+    UnsupportedMediaType,
+    PayloadTooLarge,
+    InvalidRequest(String),
 }
 
 impl std::fmt::Debug for Error {
@@ -53,54 +57,215 @@ impl std::fmt::Debug for Error {
             Error::NotImplemented => {
                 f.field(&"Not implemented");
             }
+            Error::ServiceUnavailable => {
+                f.field(&"Service unavailable");
+            }
+            Error::UnsupportedMediaType => {
+                f.field(&"Unsupported media type");
+            }
+            Error::PayloadTooLarge => {
+                f.field(&"Payload too large");
+            }
+            Error::InvalidRequest(message) => {
+                f.field(&"Invalid request").field(message);
+            }
         };
         f.finish()
     }
 }
 
-#[allow(clippy::recursive_format_impl)]
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::InternalServerProblem(ref e) => {
-                write!(f, "{}: {}", self, e)
-            }
-            _ => f.write_str(&self.to_string()),
+            Error::InternalServerProblem(e) => write!(f, "Something went wrong: {}", e),
+            Error::BadRequest => f.write_str("Bad request"),
+            Error::Unauthorized => f.write_str("Unauthorized"),
+            Error::Forbidden => f.write_str("Forbidden"),
+            Error::NotFound => f.write_str("Not found"),
+            Error::ValidationFailed => f.write_str("Validation failed"),
+            Error::Conflict => f.write_str("Confliction occured"),
+            Error::EnvironmentVariableMissing => f.write_str("Missing environment variable"),
+            Error::NotImplemented => f.write_str("Not implemented"),
+            Error::ServiceUnavailable => f.write_str("Service unavailable"),
+            Error::UnsupportedMediaType => f.write_str("Unsupported media type"),
+            Error::PayloadTooLarge => f.write_str("Payload too large"),
+            Error::InvalidRequest(message) => write!(f, "Invalid request: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InternalServerProblem(e) => Some(e.as_ref()),
+            _ => None,
         }
     }
 }
 
 impl From<anyhow::Error> for Error {
+    // NOTE: Errors wrapped in anyhow keep their kind, so they are mapped to the right status.
     fn from(e: anyhow::Error) -> Self {
-        Error::InternalServerProblem(e)
+        match e.downcast::<Error>() {
+            Ok(error) => error,
+            Err(e) => Error::InternalServerProblem(e),
+        }
     }
 }
 
+/// Error codes returned in the `errorCode` field, as defined by the Delta Sharing protocol.
+pub mod code {
+    pub const INVALID_PARAMETER_VALUE: &str = "INVALID_PARAMETER_VALUE";
+    pub const RESOURCE_DOES_NOT_EXIST: &str = "RESOURCE_DOES_NOT_EXIST";
+    pub const RESOURCE_ALREADY_EXISTS: &str = "RESOURCE_ALREADY_EXISTS";
+    pub const PERMISSION_DENIED: &str = "PERMISSION_DENIED";
+    pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
+    pub const NOT_IMPLEMENTED: &str = "NOT_IMPLEMENTED";
+    pub const TEMPORARILY_UNAVAILABLE: &str = "TEMPORARILY_UNAVAILABLE";
+    pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
+        let (status, error_code, message) = match self {
             Error::InternalServerProblem(e) => {
                 tracing::error!("stacktrace: {}", e.backtrace());
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    code::INTERNAL_ERROR,
+                    "Internal server error".into(),
+                )
             }
-            Error::BadRequest => (StatusCode::BAD_REQUEST, "Bad request"),
-            Error::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
-            Error::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
-            Error::NotFound => (StatusCode::NOT_FOUND, "Not found"),
-            Error::ValidationFailed => (StatusCode::BAD_REQUEST, "Bad request"),
-            Error::Conflict => (StatusCode::CONFLICT, "Conflict"),
-            Error::EnvironmentVariableMissing => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-            }
-            Error::NotImplemented => (StatusCode::NOT_IMPLEMENTED, "Not implemented"),
+            Error::BadRequest => (
+                StatusCode::BAD_REQUEST,
+                code::INVALID_PARAMETER_VALUE,
+                "Bad request".into(),
+            ),
+            Error::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                code::UNAUTHENTICATED,
+                "Unauthorized".into(),
+            ),
+            Error::Forbidden => (
+                StatusCode::FORBIDDEN,
+                code::PERMISSION_DENIED,
+                "Forbidden".into(),
+            ),
+            Error::NotFound => (
+                StatusCode::NOT_FOUND,
+                code::RESOURCE_DOES_NOT_EXIST,
+                "Not found".into(),
+            ),
+            Error::ValidationFailed => (
+                StatusCode::BAD_REQUEST,
+                code::INVALID_PARAMETER_VALUE,
+                "Bad request".into(),
+            ),
+            Error::Conflict => (
+                StatusCode::CONFLICT,
+                code::RESOURCE_ALREADY_EXISTS,
+                "Conflict".into(),
+            ),
+            Error::EnvironmentVariableMissing => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                code::INTERNAL_ERROR,
+                "Internal server error".into(),
+            ),
+            Error::NotImplemented => (
+                StatusCode::NOT_IMPLEMENTED,
+                code::NOT_IMPLEMENTED,
+                "Not implemented".into(),
+            ),
+            Error::ServiceUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                code::TEMPORARILY_UNAVAILABLE,
+                "Service unavailable".into(),
+            ),
+            Error::UnsupportedMediaType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                code::INVALID_PARAMETER_VALUE,
+                "Unsupported media type".into(),
+            ),
+            Error::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                code::INVALID_PARAMETER_VALUE,
+                "Request body is too large".into(),
+            ),
+            Error::InvalidRequest(message) => (
+                StatusCode::BAD_REQUEST,
+                code::INVALID_PARAMETER_VALUE,
+                message,
+            ),
         };
         (
             status,
             Json(ErrorMessage {
-                error_code: status.as_str().into(),
-                message: message.into(),
+                error_code: error_code.into(),
+                message,
             }),
         )
             .into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body(error: Error) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should be readable");
+        (
+            status,
+            serde_json::from_slice(&bytes).expect("body should be JSON"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_not_found_error_code() {
+        let (status, body) = body(Error::NotFound).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["errorCode"], "RESOURCE_DOES_NOT_EXIST");
+    }
+
+    #[tokio::test]
+    async fn test_bad_request_error_code() {
+        let (status, body) = body(Error::InvalidRequest("invalid version".into())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["errorCode"], "INVALID_PARAMETER_VALUE");
+        assert_eq!(body["message"], "invalid version");
+    }
+
+    #[tokio::test]
+    async fn test_wrapped_error_keeps_kind() {
+        let wrapped = anyhow::Error::from(Error::Forbidden).context("failed to authorize");
+        let (status, body) = body(Error::from(wrapped)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["errorCode"], "PERMISSION_DENIED");
+
+        let (status, body) = body(anyhow::anyhow!("failed to connect").into()).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["errorCode"], "INTERNAL_ERROR");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::Forbidden.to_string(), "Forbidden");
+        assert_eq!(
+            Error::InternalServerProblem(anyhow::anyhow!("boom")).to_string(),
+            "Something went wrong: boom"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_hides_details() {
+        let error = Error::InternalServerProblem(anyhow::anyhow!("connection string leaked"));
+        let (status, body) = body(error).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["errorCode"], "INTERNAL_ERROR");
+        assert_eq!(body["message"], "Internal server error");
+    }
+}
@@ -15,6 +15,7 @@ pub struct Table {
     pub id: String,
     pub name: String,
     pub location: String,
+    pub read_only: bool,
 }
 
 impl Table {
@@ -23,6 +24,7 @@ impl Table {
             id: entity.id().to_string(),
             name: entity.name().to_string(),
             location: entity.location().to_string(),
+            read_only: entity.read_only(),
         }
     }
 }
@@ -51,7 +53,8 @@ impl Service {
             r#"SELECT
                    id::text,
                    name,
-                   location
+                   location,
+                   read_only
                FROM "table""#,
         );
         if let Some(name) = after {
@@ -89,7 +92,8 @@ impl Service {
             r#"SELECT
                    id::text,
                    name,
-                   location
+                   location,
+                   read_only
                FROM "table"
                WHERE name = $1"#,
         )
@@ -117,7 +121,8 @@ impl Service {
             r#"SELECT
                    "table".id::text AS id,
                    "table".name AS name,
-                   "table".location AS location
+                   "table".location AS location,
+                   "table".read_only AS read_only
                FROM "table"
                LEFT JOIN "schema" ON "schema".id = "table".schema_id
                LEFT JOIN share ON share.id = "schema".share_id
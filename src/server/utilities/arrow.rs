@@ -0,0 +1,241 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use deltalake::arrow::array::{new_null_array, ArrayRef};
+use deltalake::arrow::compute::cast;
+use deltalake::arrow::datatypes::SchemaRef;
+use deltalake::arrow::ipc::writer::StreamWriter;
+use deltalake::arrow::record_batch::RecordBatch;
+use deltalake::parquet::arrow::arrow_reader::{
+    ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder,
+};
+use futures::stream::{BoxStream, Stream, StreamExt};
+
+pub const CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+
+/// Buffer the IPC writer encodes into, drained after every record batch.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    fn take(&self) -> Bytes {
+        Bytes::from(std::mem::take(&mut *self.0.lock().unwrap()))
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Encoder {
+    schema: SchemaRef,
+    writer: StreamWriter<Buffer>,
+    buffer: Buffer,
+}
+
+impl Encoder {
+    fn try_new(schema: SchemaRef) -> Result<Self> {
+        let buffer = Buffer::default();
+        let writer = StreamWriter::try_new(buffer.clone(), &schema)
+            .context("failed to create arrow stream")?;
+        Ok(Self {
+            schema,
+            writer,
+            buffer,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes> {
+        self.writer
+            .write(&Utility::align(batch, &self.schema)?)
+            .context("failed to write record batch")?;
+        Ok(self.buffer.take())
+    }
+
+    fn finish(mut self) -> Result<Bytes> {
+        self.writer
+            .finish()
+            .context("failed to finish arrow stream")?;
+        Ok(self.buffer.take())
+    }
+}
+
+struct IpcState {
+    files: BoxStream<'static, Result<Bytes>>,
+    encoder: Option<Encoder>,
+    reader: Option<ParquetRecordBatchReader>,
+}
+
+impl IpcState {
+    // NOTE: The stream ends after the first error, as the IPC stream cannot be continued.
+    async fn next(&mut self) -> Option<Result<Bytes>> {
+        let mut encoder = self.encoder.take()?;
+        loop {
+            if let Some(reader) = &mut self.reader {
+                match reader.next() {
+                    Some(batch) => {
+                        let chunk = batch
+                            .context("failed to read record batch")
+                            .and_then(|batch| encoder.write(&batch));
+                        if chunk.is_ok() {
+                            self.encoder = Some(encoder);
+                        }
+                        return Some(chunk);
+                    }
+                    None => self.reader = None,
+                }
+            }
+            match self.files.next().await {
+                Some(file) => {
+                    let reader = file.and_then(|file| {
+                        ParquetRecordBatchReaderBuilder::try_new(file)
+                            .context("failed to read parquet file")?
+                            .build()
+                            .context("failed to read parquet file")
+                    });
+                    match reader {
+                        Ok(reader) => self.reader = Some(reader),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                None => return Some(encoder.finish()),
+            }
+        }
+    }
+}
+
+pub struct Utility;
+
+impl Utility {
+    // NOTE: Data files written before a schema change lack columns added later, which are
+    //       filled with nulls like readers of the delta table do.
+    fn align(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| match batch.column_by_name(field.name()) {
+                Some(column) if column.data_type() == field.data_type() => Ok(column.clone()),
+                Some(column) => cast(column, field.data_type())
+                    .with_context(|| format!("failed to cast column {}", field.name())),
+                None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        RecordBatch::try_new(schema.clone(), columns)
+            .context("failed to align record batch to table schema")
+    }
+
+    /// Encode the rows of the parquet `files` as an Arrow IPC stream with the given `schema`.
+    ///
+    /// The stream is encoded one record batch at a time, so only a single data file is held
+    /// in memory. Each chunk carries the messages of one record batch, the first one starts
+    /// with the schema and the last one ends the IPC stream.
+    pub fn ipc(
+        schema: SchemaRef,
+        files: BoxStream<'static, Result<Bytes>>,
+    ) -> Result<impl Stream<Item = Result<Bytes>> + Send> {
+        let state = IpcState {
+            files,
+            encoder: Some(Encoder::try_new(schema)?),
+            reader: None,
+        };
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            let chunk = state.next().await?;
+            Some((chunk, state))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use deltalake::arrow::array::{Int64Array, StringArray};
+    use deltalake::arrow::datatypes::{DataType, Field, Schema};
+    use deltalake::arrow::ipc::reader::StreamReader;
+    use deltalake::parquet::arrow::ArrowWriter;
+
+    use super::*;
+
+    fn parquet(batch: &RecordBatch) -> Bytes {
+        let mut data = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), None)
+            .expect("parquet writer should be created");
+        writer.write(batch).expect("batch should be written");
+        writer.close().expect("parquet file should be finished");
+        Bytes::from(data)
+    }
+
+    async fn ipc(schema: SchemaRef, files: Vec<Bytes>) -> Vec<u8> {
+        let files = futures::stream::iter(files.into_iter().map(Ok)).boxed();
+        let chunks = Utility::ipc(schema, files)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        chunks
+            .into_iter()
+            .map(|chunk| chunk.expect("chunk should be encoded"))
+            .flat_map(|chunk| chunk.to_vec())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ipc() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let old = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)])),
+            vec![Arc::new(Int64Array::from(vec![1, 2]))],
+        )
+        .unwrap();
+        let new = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![3])),
+                Arc::new(StringArray::from(vec!["c"])),
+            ],
+        )
+        .unwrap();
+
+        let data = ipc(schema.clone(), vec![parquet(&old), parquet(&new)]).await;
+        let reader = StreamReader::try_new(data.as_slice(), None).unwrap();
+        assert_eq!(reader.schema(), schema);
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 2);
+        // the file written before the column was added has no names.
+        assert_eq!(batches[0].column(1).null_count(), 2);
+        assert_eq!(batches[1], new);
+
+        let data = ipc(schema.clone(), vec![]).await;
+        let reader = StreamReader::try_new(data.as_slice(), None).unwrap();
+        assert_eq!(reader.schema(), schema);
+        assert_eq!(reader.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_ipc_error_ends_stream() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)]));
+        let files = futures::stream::iter(vec![
+            Ok(Bytes::from_static(b"not parquet")),
+            Ok(Bytes::from_static(b"not parquet")),
+        ])
+        .boxed();
+        let chunks = Utility::ipc(schema, files)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].is_err());
+    }
+}
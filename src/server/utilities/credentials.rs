@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::server::utilities::location::StorageLocation;
+
+/// Storage options used to access the object store of a table location.
+///
+/// The values usually contain secrets like access keys or SAS tokens, so they are
+/// redacted when formatted and never serialized.
+#[derive(Clone, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Credentials(HashMap<String, String>);
+
+impl Credentials {
+    pub fn new(options: HashMap<String, String>) -> Self {
+        Self(options)
+    }
+
+    pub fn options(&self) -> &HashMap<String, String> {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.keys().map(|key| (key, "<redacted>")))
+            .finish()
+    }
+}
+
+/// Registry of storage credentials keyed by URL scheme and bucket.
+///
+/// Credentials registered for `<scheme>://<bucket>` take precedence over those
+/// registered for the whole `<scheme>`.
+#[derive(Debug, Clone, Default)]
+pub struct Provider {
+    credentials: HashMap<String, Credentials>,
+}
+
+impl Provider {
+    pub fn new(credentials: HashMap<String, Credentials>) -> Self {
+        Self { credentials }
+    }
+
+    pub fn lookup(&self, location: &str) -> Option<&Credentials> {
+        let location = StorageLocation::from_str(location).ok()?;
+        let scheme = location.scheme();
+        location
+            .bucket()
+            .and_then(|bucket| self.credentials.get(&format!("{}://{}", scheme, bucket)))
+            .or_else(|| self.credentials.get(scheme))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(key: &str, value: &str) -> Credentials {
+        Credentials::new(HashMap::from([(key.to_string(), value.to_string())]))
+    }
+
+    fn provider() -> Provider {
+        Provider::new(HashMap::from([
+            (
+                "s3://bucketA".to_string(),
+                credentials("aws_access_key_id", "keyA"),
+            ),
+            (
+                "s3://bucketB".to_string(),
+                credentials("aws_access_key_id", "keyB"),
+            ),
+            (
+                "abfss".to_string(),
+                credentials("azure_storage_sas_key", "sas"),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_lookup_by_bucket() {
+        let provider = provider();
+        assert_eq!(
+            provider.lookup("s3://bucketA/path/to/table"),
+            Some(&credentials("aws_access_key_id", "keyA"))
+        );
+        assert_eq!(
+            provider.lookup("s3://bucketB/path/to/table"),
+            Some(&credentials("aws_access_key_id", "keyB"))
+        );
+        assert_eq!(provider.lookup("s3://bucketC/path/to/table"), None);
+    }
+
+    #[test]
+    fn test_lookup_by_scheme() {
+        let provider = provider();
+        assert_eq!(
+            provider.lookup("abfss://container@account.dfs.core.windows.net/table"),
+            Some(&credentials("azure_storage_sas_key", "sas"))
+        );
+        assert_eq!(provider.lookup("gs://bucket/table"), None);
+        assert_eq!(provider.lookup("not a url"), None);
+    }
+
+    #[test]
+    fn test_lookup_by_container() {
+        let provider = Provider::new(HashMap::from([(
+            "abfss://container".to_string(),
+            credentials("azure_storage_account_key", "key"),
+        )]));
+        assert_eq!(
+            provider.lookup("abfss://container@account.dfs.core.windows.net/table"),
+            Some(&credentials("azure_storage_account_key", "key"))
+        );
+        assert_eq!(
+            provider.lookup("abfss://other@account.dfs.core.windows.net/table"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let formatted = format!("{:?}", provider());
+        assert!(formatted.contains("aws_access_key_id"));
+        assert!(!formatted.contains("keyA"));
+        assert!(!formatted.contains("sas\""));
+    }
+}
@@ -1,17 +1,30 @@
 use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use deltalake::schema::SchemaDataType;
 use deltalake::{open_table_with_storage_options, DeltaTable};
+use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
 use utoipa::ToSchema;
 
 use crate::config;
 
 pub type File = deltalake::protocol::Add;
 
+// NOTE: The configuration of the table cache and the open limit is validated on startup.
+static TABLE_CACHE: Lazy<TableCache<DeltaTable>> =
+    Lazy::new(|| TableCache::from_config().expect("table cache configuration should be valid"));
+
+static TABLE_OPENS: Lazy<OpenLimit> =
+    Lazy::new(|| OpenLimit::from_config().expect("table open limit configuration should be valid"));
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct Interval<T>
 where
@@ -167,9 +180,218 @@ pub struct Stats {
     pub null_count: HashMap<String, i64>,
 }
 
+#[derive(Debug)]
+pub struct Timeout(Duration);
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "loading delta table timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+type CacheKey = (String, Option<i64>);
+
+struct CacheEntry<T> {
+    value: T,
+    loaded_at: Instant,
+    last_used: u64,
+}
+
+struct CacheEntries<T> {
+    tick: u64,
+    entries: HashMap<CacheKey, CacheEntry<T>>,
+}
+
+/// Least recently used cache of opened tables, keyed by location and version.
+///
+/// Tables opened without a version refer to the latest version of the table, so they are
+/// refreshed with the commits made since they were loaded whenever they are reused. Entries
+/// expire after the TTL and are loaded from scratch again. A capacity of zero disables caching.
+pub struct TableCache<T> {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<CacheEntries<T>>,
+    loads: AtomicUsize,
+}
+
+impl<T: Clone> TableCache<T> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::new(CacheEntries {
+                tick: 0,
+                entries: HashMap::new(),
+            }),
+            loads: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn from_config() -> Result<Self> {
+        let capacity = config::fetch_or("table_cache_capacity", config::TABLE_CACHE_CAPACITY)?;
+        let ttl = config::fetch_or("table_cache_ttl", config::TABLE_CACHE_TTL)?;
+        Ok(Self::new(capacity, Duration::from_secs(ttl)))
+    }
+
+    /// Number of times a table was loaded because it was not cached.
+    pub fn loads(&self) -> usize {
+        self.loads.load(Ordering::Relaxed)
+    }
+
+    /// Get the table at `location` and `version`, calling `load` if it is not cached.
+    pub async fn get_or_load<F, Fut>(
+        &self,
+        location: &str,
+        version: Option<i64>,
+        load: F,
+    ) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.get_or_refresh(location, version, load, |value| async move { Ok(value) })
+            .await
+    }
+
+    /// Get the table at `location` and `version` like [`TableCache::get_or_load`], passing
+    /// cached tables without a version through `refresh` to pick up new commits.
+    pub async fn get_or_refresh<F, Fut, R, RFut>(
+        &self,
+        location: &str,
+        version: Option<i64>,
+        load: F,
+        refresh: R,
+    ) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+        R: FnOnce(T) -> RFut,
+        RFut: Future<Output = Result<T>>,
+    {
+        let key = (location.to_string(), version);
+        if let Some((value, loaded_at)) = self.lookup(&key) {
+            if version.is_some() {
+                return Ok(value);
+            }
+            let value = refresh(value).await?;
+            // NOTE: Refreshed tables keep their load time, so they are still loaded from
+            //       scratch once the TTL has passed.
+            self.insert(key, value.clone(), loaded_at);
+            return Ok(value);
+        }
+        self.loads.fetch_add(1, Ordering::Relaxed);
+        let value = load().await?;
+        self.insert(key, value.clone(), Instant::now());
+        Ok(value)
+    }
+
+    fn lookup(&self, key: &CacheKey) -> Option<(T, Instant)> {
+        let mut cache = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.tick += 1;
+        let tick = cache.tick;
+        let entry = cache.entries.get_mut(key)?;
+        if entry.loaded_at.elapsed() >= self.ttl {
+            cache.entries.remove(key);
+            return None;
+        }
+        entry.last_used = tick;
+        Some((entry.value.clone(), entry.loaded_at))
+    }
+
+    fn insert(&self, key: CacheKey, value: T, loaded_at: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut cache = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.tick += 1;
+        let entry = CacheEntry {
+            value,
+            loaded_at,
+            last_used: cache.tick,
+        };
+        cache.entries.insert(key, entry);
+        while cache.entries.len() > self.capacity {
+            let Some(oldest) = cache
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            cache.entries.remove(&oldest);
+        }
+    }
+}
+
+/// Limit of tables being opened concurrently.
+///
+/// Opens beyond the limit wait for a running open to finish. Waiting counts towards the
+/// timeout of loading the table, so opens time out while the limit stays saturated.
+pub struct OpenLimit {
+    permits: Semaphore,
+}
+
+impl OpenLimit {
+    pub fn new(permits: usize) -> Self {
+        Self {
+            permits: Semaphore::new(permits),
+        }
+    }
+
+    pub fn from_config() -> Result<Self> {
+        let permits = config::fetch_or("max_table_opens", config::MAX_TABLE_OPENS)?;
+        if permits == 0 {
+            return Err(anyhow!(
+                r#"configuration flag "{}" ({}) must be positive"#,
+                "max_table_opens",
+                config::env_var("max_table_opens")
+            ));
+        }
+        Ok(Self::new(permits))
+    }
+
+    /// Run `open` as soon as fewer than the limit of opens are running.
+    pub async fn run<T>(&self, open: impl Future<Output = Result<T>>) -> Result<T> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .context("failed to wait for opening delta table")?;
+        open.await
+    }
+}
+
 pub struct Utility;
 
 impl Utility {
+    pub fn timeout() -> Result<Duration> {
+        let timeout = config::fetch_or("table_timeout", config::TABLE_TIMEOUT)?;
+        Ok(Duration::from_secs(timeout))
+    }
+
+    pub async fn with_timeout<T, E>(future: impl Future<Output = Result<T, E>>) -> Result<T>
+    where
+        E: Into<anyhow::Error>,
+    {
+        Self::with_timeout_after(Self::timeout()?, future).await
+    }
+
+    pub async fn with_timeout_after<T, E>(
+        duration: Duration,
+        future: impl Future<Output = Result<T, E>>,
+    ) -> Result<T>
+    where
+        E: Into<anyhow::Error>,
+    {
+        match tokio::time::timeout(duration, future).await {
+            Ok(result) => result.map_err(Into::into),
+            Err(_) => Err(Timeout(duration).into()),
+        }
+    }
+
     pub async fn open_table(location: &str) -> Result<DeltaTable> {
         let google_service_account_path = format!(
             "{}",
@@ -182,19 +404,58 @@ impl Utility {
         );
         let aws_profile = std::env::var("AWS_PROFILE").unwrap_or(config::AWS_PROFILE.into());
         let aws_region = std::env::var("AWS_REGION").unwrap_or(config::AWS_REGION.into());
-        open_table_with_storage_options(
-            location,
-            HashMap::from([
-                (
-                    String::from("google_service_account_path"),
-                    google_service_account_path,
-                ),
-                (String::from("profile"), aws_profile),
-                (String::from("region"), aws_region),
-            ]),
-        )
-        .await
-        .context("failed to open delta table")
+        let mut options = HashMap::from([
+            (
+                String::from("google_service_account_path"),
+                google_service_account_path,
+            ),
+            (String::from("profile"), aws_profile),
+            (String::from("region"), aws_region),
+        ]);
+        if let Some(credentials) = config::STORAGE_CREDENTIALS.lookup(location) {
+            options.extend(credentials.options().clone());
+        }
+        TABLE_OPENS
+            .run(async {
+                open_table_with_storage_options(location, options)
+                    .await
+                    .context("failed to open delta table")
+            })
+            .await
+    }
+
+    /// Open the table at `location`, loaded at `version` or the latest version if none is given.
+    ///
+    /// Recently opened tables are reused from a cache instead of reading the delta log again.
+    /// Reused tables without a version only read the commits made since they were cached.
+    pub async fn open_table_version(location: &str, version: Option<i64>) -> Result<DeltaTable> {
+        TABLE_CACHE
+            .get_or_refresh(
+                location,
+                version,
+                || async {
+                    let mut table = Self::open_table(location).await?;
+                    if let Some(version) = version {
+                        table
+                            .load_version(version)
+                            .await
+                            .context("failed to load delta table version")?;
+                    }
+                    Ok(table)
+                },
+                |mut table| async move {
+                    TABLE_OPENS
+                        .run(async move {
+                            table
+                                .update()
+                                .await
+                                .context("failed to update delta table")?;
+                            Ok(table)
+                        })
+                        .await
+                },
+            )
+            .await
     }
 
     pub fn get_stats(file: &File) -> Result<Stats> {
@@ -219,6 +480,173 @@ impl Utility {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_with_timeout_after_hanging() {
+        let result = Utility::with_timeout_after(
+            Duration::from_millis(10),
+            std::future::pending::<Result<DeltaTable>>(),
+        )
+        .await;
+        let Err(e) = result else {
+            panic!("hanging future should time out");
+        };
+        assert!(e.is::<Timeout>());
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_after_ready() {
+        let result = Utility::with_timeout_after(Duration::from_secs(1), async {
+            Ok::<_, anyhow::Error>(1)
+        })
+        .await;
+        assert_eq!(result.ok(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_table_cache_reuses_version() {
+        let cache = TableCache::new(4, Duration::from_secs(60));
+        let table = cache
+            .get_or_load("s3://bucket/table", Some(1), || async { Ok(1) })
+            .await
+            .unwrap();
+        assert_eq!(table, 1);
+        let table = cache
+            .get_or_load("s3://bucket/table", Some(1), || async { Ok(2) })
+            .await
+            .unwrap();
+        assert_eq!(table, 1);
+        assert_eq!(cache.loads(), 1);
+
+        let table = cache
+            .get_or_load("s3://bucket/table", Some(2), || async { Ok(2) })
+            .await
+            .unwrap();
+        assert_eq!(table, 2);
+        let table = cache
+            .get_or_load("s3://bucket/table", None, || async { Ok(3) })
+            .await
+            .unwrap();
+        assert_eq!(table, 3);
+        assert_eq!(cache.loads(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_table_cache_evicts_least_recently_used() {
+        let cache = TableCache::new(2, Duration::from_secs(60));
+        for version in [1, 2, 1, 3] {
+            cache
+                .get_or_load("s3://bucket/table", Some(version), || async { Ok(version) })
+                .await
+                .unwrap();
+        }
+        assert_eq!(cache.loads(), 3);
+        // version 2 was used least recently, so it was evicted when version 3 was loaded.
+        cache
+            .get_or_load("s3://bucket/table", Some(1), || async { Ok(1) })
+            .await
+            .unwrap();
+        assert_eq!(cache.loads(), 3);
+        cache
+            .get_or_load("s3://bucket/table", Some(2), || async { Ok(2) })
+            .await
+            .unwrap();
+        assert_eq!(cache.loads(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_table_cache_expires_and_skips_failures() {
+        let cache = TableCache::new(2, Duration::ZERO);
+        for _ in 0..2 {
+            cache
+                .get_or_load("s3://bucket/table", None, || async { Ok(1) })
+                .await
+                .unwrap();
+        }
+        assert_eq!(cache.loads(), 2);
+
+        let cache = TableCache::<i64>::new(2, Duration::from_secs(60));
+        let result = cache
+            .get_or_load("s3://bucket/table", None, || async {
+                Err(anyhow!("failed to open delta table"))
+            })
+            .await;
+        assert!(result.is_err());
+        cache
+            .get_or_load("s3://bucket/table", None, || async { Ok(1) })
+            .await
+            .unwrap();
+        assert_eq!(cache.loads(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_table_cache_refreshes_latest_version() {
+        let cache = TableCache::new(4, Duration::from_secs(60));
+        for (expected, version) in [(1, None), (2, None), (3, None), (1, Some(1)), (1, Some(1))] {
+            let table = cache
+                .get_or_refresh(
+                    "s3://bucket/table",
+                    version,
+                    || async { Ok(1) },
+                    |table| async move { Ok(table + 1) },
+                )
+                .await
+                .unwrap();
+            assert_eq!(table, expected);
+        }
+        assert_eq!(cache.loads(), 2);
+
+        // failed refreshes are returned as errors.
+        let result = cache
+            .get_or_refresh(
+                "s3://bucket/table",
+                None,
+                || async { Ok(1) },
+                |_| async { Err(anyhow!("failed to update delta table")) },
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_limit() {
+        let limit = OpenLimit::new(3);
+        let (running, observed) = (&AtomicUsize::new(0), &AtomicUsize::new(0));
+        let opens = (0..20).map(|n| {
+            limit.run(async move {
+                let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                observed.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(n)
+            })
+        });
+        let tables = futures::future::join_all(opens).await;
+        assert!(tables.iter().all(Result::is_ok));
+        assert_eq!(observed.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_open_limit_times_out_when_saturated() {
+        let limit = OpenLimit::new(1);
+        let hanging = limit.run(std::future::pending::<Result<()>>());
+        let waiting =
+            Utility::with_timeout_after(Duration::from_millis(10), limit.run(async { Ok(()) }));
+        // NOTE: The hanging open is polled first, so it holds the only permit.
+        tokio::select! {
+            biased;
+            _ = hanging => unreachable!("hanging open should not finish"),
+            result = waiting => assert!(result.unwrap_err().is::<Timeout>()),
+        }
+    }
+
+    #[test]
+    fn test_default_timeout() {
+        assert_eq!(
+            Utility::timeout().unwrap(),
+            Duration::from_secs(config::TABLE_TIMEOUT)
+        );
+    }
+
     #[test]
     fn test_i64_interval() {
         let min = testutils::rand::i64(-10, 10);
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use url::Url;
+
+/// Storage location of a delta table, parsed from its URL.
+///
+/// Azure locations of the form `<scheme>://<container>@<account>.<host>/<path>` are split
+/// into container and account, other cloud locations into bucket and path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageLocation {
+    raw: String,
+    scheme: String,
+    bucket: Option<String>,
+    account: Option<String>,
+    path: String,
+}
+
+impl StorageLocation {
+    /// Returns the location as it was given.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the URL scheme, e.g. `s3` or `abfss`.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns the bucket, or the container for Azure locations.
+    pub fn bucket(&self) -> Option<&str> {
+        self.bucket.as_deref()
+    }
+
+    /// Returns the storage account of Azure locations.
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// Returns the path within the bucket, without a leading slash.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn is_azure(scheme: &str) -> bool {
+        matches!(scheme, "abfs" | "abfss" | "wasb" | "wasbs")
+    }
+
+    fn is_cloud(scheme: &str) -> bool {
+        Self::is_azure(scheme) || matches!(scheme, "s3" | "s3a" | "gs")
+    }
+}
+
+impl FromStr for StorageLocation {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let url = Url::parse(input).context("failed to parse storage location")?;
+        let scheme = url.scheme().to_string();
+        let host = url.host_str().filter(|host| !host.is_empty());
+        if Self::is_cloud(&scheme) && host.is_none() {
+            return Err(anyhow!("storage location '{}' has no bucket", input));
+        }
+        let (bucket, account) = if Self::is_azure(&scheme) {
+            let container = url.username();
+            if container.is_empty() {
+                return Err(anyhow!("storage location '{}' has no container", input));
+            }
+            let account = host.and_then(|host| host.split('.').next());
+            (Some(container.to_string()), account.map(String::from))
+        } else {
+            (host.map(String::from), None)
+        };
+        Ok(Self {
+            raw: input.to_string(),
+            scheme,
+            bucket,
+            account,
+            path: url.path().trim_start_matches('/').to_string(),
+        })
+    }
+}
+
+impl fmt::Display for StorageLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(location: &str) -> StorageLocation {
+        StorageLocation::from_str(location).expect("location should be parsed")
+    }
+
+    #[test]
+    fn test_parse_s3() {
+        for scheme in ["s3", "s3a"] {
+            let location = parse(&format!("{}://delta-sharing-test/covid", scheme));
+            assert_eq!(location.scheme(), scheme);
+            assert_eq!(location.bucket(), Some("delta-sharing-test"));
+            assert_eq!(location.account(), None);
+            assert_eq!(location.path(), "covid");
+        }
+    }
+
+    #[test]
+    fn test_parse_gcs() {
+        let location = parse("gs://delta-sharing-test/path/to/covid");
+        assert_eq!(location.scheme(), "gs");
+        assert_eq!(location.bucket(), Some("delta-sharing-test"));
+        assert_eq!(location.path(), "path/to/covid");
+        assert_eq!(location.as_str(), "gs://delta-sharing-test/path/to/covid");
+    }
+
+    #[test]
+    fn test_parse_azure() {
+        let location = parse("abfss://mycontainer@mystorageaccount.dfs.core.windows.net/myblob");
+        assert_eq!(location.scheme(), "abfss");
+        assert_eq!(location.bucket(), Some("mycontainer"));
+        assert_eq!(location.account(), Some("mystorageaccount"));
+        assert_eq!(location.path(), "myblob");
+
+        let location = parse("wasbs://mycontainer@mystorageaccount.blob.core.windows.net/a/b");
+        assert_eq!(location.scheme(), "wasbs");
+        assert_eq!(location.bucket(), Some("mycontainer"));
+        assert_eq!(location.account(), Some("mystorageaccount"));
+        assert_eq!(location.path(), "a/b");
+    }
+
+    #[test]
+    fn test_parse_local() {
+        let location = parse("file:///tmp/delta/covid");
+        assert_eq!(location.scheme(), "file");
+        assert_eq!(location.bucket(), None);
+        assert_eq!(location.path(), "tmp/delta/covid");
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(StorageLocation::from_str("not a url").is_err());
+        assert!(StorageLocation::from_str("s3:///covid").is_err());
+        assert!(
+            StorageLocation::from_str("abfss://mystorageaccount.dfs.core.windows.net/x").is_err()
+        );
+    }
+}
@@ -1,6 +1,11 @@
+pub mod arrow;
 pub mod bootstrap;
+pub mod credentials;
 pub mod deltalake;
 pub mod json;
+pub mod location;
+pub mod ndjson;
 pub mod postgres;
+pub mod proxy;
 pub mod signed_url;
 pub mod sql;
@@ -0,0 +1,131 @@
+use axum::body::{Body, Bytes};
+use axum::http::header::{HeaderMap, CONTENT_LENGTH};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::BoxError;
+use futures_util::stream::{self, Stream, StreamExt};
+
+// NOTE: Responses up to this size are buffered, so they can be sent with a Content-Length.
+pub const MAX_BUFFERED_BYTES: usize = 64 * 1024;
+
+pub struct Utility;
+
+impl Utility {
+    fn line(value: &serde_json::Value) -> Vec<u8> {
+        let mut line = value.to_string().into_bytes();
+        line.push(b'\n');
+        line
+    }
+
+    /// Respond with the ndjson `lines`, framed by their size.
+    ///
+    /// Responses of at most [`MAX_BUFFERED_BYTES`] are sent with a `Content-Length`, larger
+    /// responses are streamed with chunked transfer encoding. An error ends the stream.
+    pub async fn response<S>(status: StatusCode, mut headers: HeaderMap, lines: S) -> Response
+    where
+        S: Stream<Item = Result<serde_json::Value, BoxError>> + Send + 'static,
+    {
+        let mut lines = Box::pin(lines);
+        let mut buffered = Vec::new();
+        let pending = loop {
+            match lines.next().await {
+                Some(Ok(value)) => {
+                    buffered.extend(Self::line(&value));
+                    if buffered.len() > MAX_BUFFERED_BYTES {
+                        break None;
+                    }
+                }
+                // NOTE: The error is streamed after the buffered lines to end the response.
+                Some(Err(e)) => break Some(e),
+                None => {
+                    headers.insert(CONTENT_LENGTH, buffered.len().into());
+                    return (status, headers, Body::from(buffered)).into_response();
+                }
+            }
+        };
+        let head = stream::once(async move { Ok::<Bytes, BoxError>(Bytes::from(buffered)) });
+        let body = match pending {
+            Some(e) => Body::from_stream(head.chain(stream::once(async move { Err(e) }))),
+            None => Body::from_stream(
+                head.chain(lines.map(|line| line.map(|value| Bytes::from(Self::line(&value))))),
+            ),
+        };
+        (status, headers, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::{HeaderValue, CONTENT_TYPE};
+    use axum::routing::get;
+    use axum::Router;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+
+    fn lines(count: usize) -> impl Stream<Item = Result<serde_json::Value, BoxError>> {
+        stream::iter((0..count).map(|n| Ok(json!({ "line": n }))))
+    }
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-ndjson"),
+        );
+        headers
+    }
+
+    async fn raw_response(count: usize) -> String {
+        let app = Router::new().route(
+            "/lines",
+            get(move || Utility::response(StatusCode::OK, headers(), lines(count))),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should be bound");
+        let addr = listener
+            .local_addr()
+            .expect("listener should have an address");
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut stream = TcpStream::connect(addr)
+            .await
+            .expect("server should accept connections");
+        stream
+            .write_all(b"GET /lines HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("request should be sent");
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .await
+            .expect("response should be read");
+        String::from_utf8(response)
+            .expect("response should be UTF-8")
+            .to_lowercase()
+    }
+
+    #[tokio::test]
+    async fn test_small_response_has_content_length() {
+        let response = raw_response(2).await;
+        let body = "{\"line\":0}\n{\"line\":1}\n";
+        assert!(response.contains(&format!("content-length: {}\r\n", body.len())));
+        assert!(!response.contains("transfer-encoding"));
+        assert!(response.contains("content-type: application/x-ndjson"));
+        assert!(response.ends_with(body));
+    }
+
+    #[tokio::test]
+    async fn test_large_response_is_chunked() {
+        let count = MAX_BUFFERED_BYTES / 10 + 1000;
+        let response = raw_response(count).await;
+        assert!(response.contains("transfer-encoding: chunked\r\n"));
+        assert!(!response.contains("content-length"));
+        for n in [0, count / 2, count - 1] {
+            assert!(response.contains(&format!("{{\"line\":{}}}", n)));
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use axum::body::Body;
+use axum::http::header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, encode, Header, Validation};
+use object_store::path::Path;
+use object_store::ObjectStore;
+
+use crate::config;
+use crate::config::JWT_SECRET;
+use crate::server::middlewares::jwt::Keys as JwtKeys;
+use crate::server::services::error::Error;
+use crate::server::utilities::signed_url::Signer;
+
+/// Audience of proxy tokens.
+///
+/// Proxy tokens are signed with the same secret as profile tokens, so the audience keeps
+/// either kind from being accepted in place of the other: the proxy requires it, and the
+/// bearer token validation rejects any token carrying an audience.
+pub const AUDIENCE: &str = "delta-sharing-proxy";
+
+/// Claims of a proxy token, which grants reading a single file of a shared table.
+///
+/// The table is identified by its fully qualified name, so access ends as soon as the
+/// table is no longer shared, even if the token has not expired yet.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Claims {
+    pub share: String,
+    pub schema: String,
+    pub table: String,
+    /// Path of the file relative to the table location, as referenced in the delta log.
+    pub path: String,
+    pub aud: String,
+    pub exp: i64,
+}
+
+/// Signer referencing files by a proxy endpoint of the server instead of the object store.
+///
+/// Recipients that cannot reach the object store fetch the files from `/proxy/<token>`,
+/// which streams the file contents through the server.
+pub struct ProxySigner {
+    pub share: String,
+    pub schema: String,
+    pub table: String,
+    pub endpoint: String,
+    pub expiration: Duration,
+    pub keys: &'static JwtKeys,
+}
+
+#[async_trait::async_trait]
+impl Signer for ProxySigner {
+    async fn sign(&self, path: &str) -> Result<String> {
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("failed to create current system time")?
+            + self.expiration;
+        let claims = Claims {
+            share: self.share.clone(),
+            schema: self.schema.clone(),
+            table: self.table.clone(),
+            path: path.to_owned(),
+            aud: AUDIENCE.to_owned(),
+            exp: i64::try_from(expires_at.as_secs())
+                .context("failed to convert expiration seconds to i64")?,
+        };
+        let token = encode(&Header::default(), &claims, &self.keys.encoding)
+            .context("failed to create proxy token")?;
+        Ok(format!(
+            "{}/proxy/{}",
+            self.endpoint.trim_end_matches('/'),
+            token
+        ))
+    }
+
+    // NOTE: Proxied files are read from the object store of the table, which is rooted at the
+    //       table location.
+    fn relative_paths(&self) -> bool {
+        true
+    }
+}
+
+pub struct Utility;
+
+impl Utility {
+    /// Signer for the files of a table, referencing the proxy endpoint of this server.
+    pub fn signer(
+        share: String,
+        schema: String,
+        table: String,
+        expiration: Duration,
+    ) -> Box<dyn Signer> {
+        Box::new(ProxySigner {
+            share,
+            schema,
+            table,
+            endpoint: config::fetch::<String>("server_addr"),
+            expiration,
+            keys: &JWT_SECRET,
+        })
+    }
+
+    /// Validate a proxy token, rejecting expired and forged tokens as forbidden.
+    ///
+    /// Tokens without the proxy [`AUDIENCE`], like profile tokens, are rejected alike.
+    pub fn decode(token: &str, keys: &JwtKeys) -> Result<Claims, Error> {
+        // NOTE: Proxy tokens expire exactly at their expiration, like signed URLs.
+        let mut validation = Validation::default();
+        validation.leeway = 0;
+        validation.set_audience(&[AUDIENCE]);
+        validation.set_required_spec_claims(&["exp", "aud"]);
+        match decode::<Claims>(token, &keys.decoding, &validation) {
+            Ok(token) => Ok(token.claims),
+            Err(e) if *e.kind() == ErrorKind::ExpiredSignature => {
+                tracing::error!("proxy token has expired");
+                Err(Error::Forbidden)
+            }
+            Err(_) => {
+                tracing::error!("proxy token cannot be decoded");
+                Err(Error::Forbidden)
+            }
+        }
+    }
+
+    /// Stream the file at `path` relative to the root of `store`.
+    pub async fn response(store: &dyn ObjectStore, path: &str) -> Result<Response, Error> {
+        let Ok(path) = Path::from_url_path(path) else {
+            tracing::error!("proxied file path is malformed");
+            return Err(Error::ValidationFailed);
+        };
+        let file = match store.get(&path).await {
+            Ok(file) => file,
+            Err(object_store::Error::NotFound { .. }) => {
+                tracing::error!("proxied file does not exist");
+                return Err(Error::NotFound);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "request is not handled correctly due to a server error while reading proxied file: {}",
+                    e
+                );
+                return Err(anyhow::anyhow!("error occured while reading file").into());
+            }
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        headers.insert(CONTENT_LENGTH, file.meta.size.into());
+        Ok((
+            StatusCode::OK,
+            headers,
+            Body::from_stream(file.into_stream()),
+        )
+            .into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Bytes;
+    use axum::extract::Path as UrlPath;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use object_store::memory::InMemory;
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    static KEYS: Lazy<JwtKeys> = Lazy::new(|| JwtKeys::new(b"secret"));
+
+    fn signer(expiration: Duration) -> ProxySigner {
+        ProxySigner {
+            share: "share1".into(),
+            schema: "schema1".into(),
+            table: "table1".into(),
+            endpoint: "http://localhost:8080/".into(),
+            expiration,
+            keys: &KEYS,
+        }
+    }
+
+    fn router(store: Arc<InMemory>) -> Router {
+        Router::new().route(
+            "/proxy/:token",
+            get(move |UrlPath(token): UrlPath<String>| async move {
+                let claims = Utility::decode(&token, &KEYS)?;
+                Utility::response(store.as_ref(), &claims.path).await
+            }),
+        )
+    }
+
+    async fn fetch(store: Arc<InMemory>, url: &str) -> Response {
+        let path = url
+            .strip_prefix("http://localhost:8080")
+            .expect("proxy URL should reference the endpoint");
+        router(store)
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_proxy_file() {
+        let store = Arc::new(InMemory::new());
+        let content = Bytes::from_static(b"PAR1 parquet content PAR1");
+        store
+            .put(
+                &Path::from("date=2024-01-01/part 1.parquet"),
+                content.clone(),
+            )
+            .await
+            .unwrap();
+
+        let url = signer(Duration::from_secs(300))
+            .sign("date=2024-01-01/part%201.parquet")
+            .await
+            .unwrap();
+        assert!(url.starts_with("http://localhost:8080/proxy/"));
+        let response = fetch(store, &url).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[CONTENT_LENGTH],
+            content.len().to_string()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, content);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_token_expired() {
+        let store = Arc::new(InMemory::new());
+        let url = signer(Duration::ZERO).sign("part-0.parquet").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let response = fetch(store.clone(), &url).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // tokens signed with another secret are rejected alike.
+        let token = encode(
+            &Header::default(),
+            &Claims {
+                share: "share1".into(),
+                schema: "schema1".into(),
+                table: "table1".into(),
+                path: "part-0.parquet".into(),
+                aud: AUDIENCE.into(),
+                exp: 4_102_444_800,
+            },
+            &JwtKeys::new(b"other").encoding,
+        )
+        .unwrap();
+        let response = fetch(store, &format!("http://localhost:8080/proxy/{}", token)).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_token_audience() {
+        let store = Arc::new(InMemory::new());
+        store
+            .put(&Path::from("part-0.parquet"), Bytes::from_static(b"PAR1"))
+            .await
+            .unwrap();
+
+        // tokens signed with the same secret but without the proxy audience are rejected.
+        for aud in [None, Some("delta-sharing")] {
+            let mut claims = serde_json::json!({
+                "share": "share1",
+                "schema": "schema1",
+                "table": "table1",
+                "path": "part-0.parquet",
+                "exp": 4_102_444_800_i64,
+            });
+            if let Some(aud) = aud {
+                claims["aud"] = aud.into();
+            }
+            let token = encode(&Header::default(), &claims, &KEYS.encoding).unwrap();
+            let response = fetch(
+                store.clone(),
+                &format!("http://localhost:8080/proxy/{}", token),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        // proxy tokens fail the validation of bearer tokens.
+        let url = signer(Duration::from_secs(300))
+            .sign("part-0.parquet")
+            .await
+            .unwrap();
+        let token = url
+            .strip_prefix("http://localhost:8080/proxy/")
+            .expect("proxy URL should reference the endpoint");
+        let err = decode::<serde_json::Value>(token, &KEYS.decoding, &Validation::default())
+            .expect_err("proxy token should not be accepted as bearer token");
+        assert_eq!(*err.kind(), ErrorKind::InvalidAudience);
+    }
+}
@@ -1,10 +1,15 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use object_store::azure::MicrosoftAzureBuilder;
 use object_store::path::Path;
 use object_store::signer::Signer as ObjectStoreSigner;
+use once_cell::sync::Lazy;
 use rusoto_core::Region;
 use rusoto_credential::AwsCredentials as AWS;
 use rusoto_s3::util::{PreSignedRequest, PreSignedRequestOption};
@@ -14,9 +19,17 @@ use tame_gcs::signing::ServiceAccount as GCP;
 use tame_gcs::{BucketName, ObjectName};
 use url::Url;
 
+use crate::config;
 use crate::server::{routers::AzureCredential, AzureLocation};
 
-#[derive(Debug, PartialEq, Eq)]
+const MAX_CACHED_URLS: usize = 100_000;
+
+// NOTE: The configuration of the signed URL cache is validated on startup.
+static SIGNED_URL_CACHE: Lazy<Arc<SignedUrlCache>> = Lazy::new(|| {
+    Arc::new(SignedUrlCache::from_config().expect("signed URL cache configuration should be valid"))
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Platform {
     Aws,
     Gcp,
@@ -41,6 +54,12 @@ impl FromStr for Platform {
 #[async_trait::async_trait]
 pub trait Signer: Send + Sync {
     async fn sign(&self, path: &str) -> Result<String>;
+
+    /// Whether files are referenced by their path relative to the table root instead of their
+    /// absolute location, like the proxy does.
+    fn relative_paths(&self) -> bool {
+        false
+    }
 }
 
 #[async_trait::async_trait]
@@ -48,6 +67,138 @@ impl<S: Signer + ?Sized> Signer for Box<S> {
     async fn sign(&self, path: &str) -> Result<String> {
         self.as_ref().sign(path).await
     }
+
+    fn relative_paths(&self) -> bool {
+        self.as_ref().relative_paths()
+    }
+}
+
+/// Identity of the credentials signing URLs on a cloud platform.
+///
+/// The credentials are only kept as a digest, so cached URLs are never shared between
+/// signers with different or rotated credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignerId {
+    platform: Platform,
+    credentials: u64,
+}
+
+impl SignerId {
+    pub fn new(platform: Platform, credentials: &impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        credentials.hash(&mut hasher);
+        Self {
+            platform,
+            credentials: hasher.finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    signer: SignerId,
+    path: String,
+    expiration: Duration,
+}
+
+#[derive(Default)]
+struct CacheEntries {
+    urls: HashMap<CacheKey, (String, Instant, u64)>,
+    expirations: BTreeMap<(Instant, u64), CacheKey>,
+    inserted: u64,
+}
+
+/// Cache of signed URLs keyed by signer, path and expiration, shared across requests.
+///
+/// Signed URLs are reused until they expire within the refresh window, so recipients
+/// always receive URLs that stay valid for at least the refresh window. Once `capacity`
+/// URLs are cached, expired URLs and then the URLs expiring first are evicted one by one.
+pub struct SignedUrlCache {
+    refresh_window: Duration,
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+impl SignedUrlCache {
+    pub fn new(refresh_window: Duration, capacity: usize) -> Self {
+        Self {
+            refresh_window,
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+        }
+    }
+
+    pub fn from_config() -> Result<Self> {
+        let refresh_window = config::fetch_or(
+            "signed_url_refresh_window",
+            config::SIGNED_URL_REFRESH_WINDOW,
+        )?;
+        Ok(Self::new(
+            Duration::from_secs(refresh_window),
+            MAX_CACHED_URLS,
+        ))
+    }
+
+    fn get(&self, key: &CacheKey, now: Instant) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (url, expires_at, _) = entries.urls.get(key)?;
+        (expires_at.saturating_duration_since(now) > self.refresh_window).then(|| url.clone())
+    }
+
+    fn insert(&self, key: CacheKey, url: String, expires_at: Instant, now: Instant) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((_, previous, inserted)) = entries.urls.remove(&key) {
+            entries.expirations.remove(&(previous, inserted));
+        }
+        while let Some((&(first, _), _)) = entries.expirations.first_key_value() {
+            if first > now && entries.urls.len() < self.capacity {
+                break;
+            }
+            if let Some((_, evicted)) = entries.expirations.pop_first() {
+                entries.urls.remove(&evicted);
+            }
+        }
+        if self.capacity == 0 {
+            return;
+        }
+        let inserted = entries.inserted;
+        entries.inserted += 1;
+        entries
+            .expirations
+            .insert((expires_at, inserted), key.clone());
+        entries.urls.insert(key, (url, expires_at, inserted));
+    }
+}
+
+/// Signer reusing URLs signed by the inner signer from a [`SignedUrlCache`].
+pub struct CachingSigner<S> {
+    pub inner: S,
+    pub signer: SignerId,
+    pub expiration: Duration,
+    pub cache: Arc<SignedUrlCache>,
+}
+
+#[async_trait::async_trait]
+impl<S: Signer> Signer for CachingSigner<S> {
+    async fn sign(&self, path: &str) -> Result<String> {
+        let signed_at = Instant::now();
+        let key = CacheKey {
+            signer: self.signer,
+            path: path.to_owned(),
+            expiration: self.expiration,
+        };
+        if let Some(url) = self.cache.get(&key, signed_at) {
+            return Ok(url);
+        }
+        let url = self.inner.sign(path).await?;
+        self.cache
+            .insert(key, url.clone(), signed_at + self.expiration, signed_at);
+        Ok(url)
+    }
+
+    fn relative_paths(&self) -> bool {
+        self.inner.relative_paths()
+    }
 }
 
 pub struct AwsSigner {
@@ -156,16 +307,46 @@ impl Signer for GcpSigner {
 pub struct Utility;
 
 impl Utility {
+    fn cached<S: Signer + 'static>(
+        inner: S,
+        signer: SignerId,
+        expiration: Duration,
+    ) -> Box<dyn Signer> {
+        Box::new(CachingSigner {
+            inner,
+            signer,
+            expiration,
+            cache: SIGNED_URL_CACHE.clone(),
+        })
+    }
+
     pub fn aws_signer(aws: AWS, expiration: Duration) -> Box<dyn Signer> {
-        Box::new(AwsSigner { aws, expiration })
+        let signer = SignerId::new(
+            Platform::Aws,
+            &(
+                aws.aws_access_key_id(),
+                aws.aws_secret_access_key(),
+                aws.token(),
+            ),
+        );
+        Self::cached(AwsSigner { aws, expiration }, signer, expiration)
     }
 
     pub fn azure_signer(azure: AzureLocation, expiration: Duration) -> Box<dyn Signer> {
-        Box::new(AzureSigner { azure, expiration })
+        let AzureCredential::AccessKey(access_key) = &azure.credential;
+        let signer = SignerId::new(Platform::Azure, &(&azure.account, access_key));
+        Self::cached(AzureSigner { azure, expiration }, signer, expiration)
     }
 
-    pub fn gcp_signer(gcp: GCP, expiration: Duration) -> Box<dyn Signer> {
-        Box::new(GcpSigner { gcp, expiration })
+    /// Signer for the service account in the JSON key file `credentials`.
+    pub fn gcp_signer(credentials: &[u8], expiration: Duration) -> Result<Box<dyn Signer>> {
+        let gcp = GCP::load_json(credentials).context("failed to load GCP credentials")?;
+        let signer = SignerId::new(Platform::Gcp, &credentials);
+        Ok(Self::cached(
+            GcpSigner { gcp, expiration },
+            signer,
+            expiration,
+        ))
     }
 }
 
@@ -178,6 +359,131 @@ mod tests {
     use std::str::FromStr;
     use tame_gcs::signing::ServiceAccount;
 
+    /// Signer appending the number of signatures made so far to the path.
+    #[derive(Default)]
+    struct CountingSigner(std::sync::atomic::AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl Signer for CountingSigner {
+        async fn sign(&self, path: &str) -> Result<String> {
+            let count = self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(format!("{}?signature={}", path, count))
+        }
+    }
+
+    fn key(signer: SignerId, path: &str, expiration: Duration) -> CacheKey {
+        CacheKey {
+            signer,
+            path: path.into(),
+            expiration,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caching_signer_reuses_urls() {
+        let aws = SignerId::new(Platform::Aws, &"keyA");
+        let signer = CachingSigner {
+            inner: CountingSigner::default(),
+            signer: aws,
+            expiration: Duration::from_secs(300),
+            cache: Arc::new(SignedUrlCache::new(Duration::from_secs(60), 100)),
+        };
+        let first = signer.sign("s3://bucket/a.parquet").await.unwrap();
+        let second = signer.sign("s3://bucket/a.parquet").await.unwrap();
+        assert_eq!(first, second);
+        let other = signer.sign("s3://bucket/b.parquet").await.unwrap();
+        assert_ne!(first, other);
+
+        // URLs signed for a different expiration are not reused.
+        let cache = signer.cache.clone();
+        let signer = CachingSigner {
+            inner: CountingSigner::default(),
+            signer: aws,
+            expiration: Duration::from_secs(600),
+            cache: cache.clone(),
+        };
+        assert_eq!(
+            signer.sign("s3://bucket/a.parquet").await.unwrap(),
+            "s3://bucket/a.parquet?signature=0"
+        );
+
+        // nor are URLs signed with other credentials.
+        for other in [
+            SignerId::new(Platform::Aws, &"keyB"),
+            SignerId::new(Platform::Gcp, &"keyA"),
+        ] {
+            let signer = CachingSigner {
+                inner: CountingSigner::default(),
+                signer: other,
+                expiration: Duration::from_secs(300),
+                cache: cache.clone(),
+            };
+            assert_eq!(
+                signer.sign("s3://bucket/a.parquet").await.unwrap(),
+                "s3://bucket/a.parquet?signature=0"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caching_signer_refreshes_near_expiry() {
+        let cache = SignedUrlCache::new(Duration::from_secs(60), 100);
+        let aws = SignerId::new(Platform::Aws, &"keyA");
+        let now = Instant::now();
+        let expiration = Duration::from_secs(300);
+        let a = key(aws, "s3://bucket/a.parquet", expiration);
+        cache.insert(a.clone(), "signed".into(), now + expiration, now);
+        assert_eq!(
+            cache.get(&a, now + Duration::from_secs(200)),
+            Some("signed".into())
+        );
+        assert_eq!(cache.get(&a, now + Duration::from_secs(250)), None);
+
+        // URLs expiring within the refresh window are signed again.
+        let signer = CachingSigner {
+            inner: CountingSigner::default(),
+            signer: aws,
+            expiration: Duration::from_secs(30),
+            cache: Arc::new(cache),
+        };
+        let first = signer.sign("s3://bucket/a.parquet").await.unwrap();
+        let second = signer.sign("s3://bucket/a.parquet").await.unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_signed_url_cache_evicts_first_expiring() {
+        let cache = SignedUrlCache::new(Duration::ZERO, 2);
+        let aws = SignerId::new(Platform::Aws, &"keyA");
+        let now = Instant::now();
+        let expiration = Duration::from_secs(300);
+        let (a, b, c) = (
+            key(aws, "s3://bucket/a.parquet", expiration),
+            key(aws, "s3://bucket/b.parquet", expiration),
+            key(aws, "s3://bucket/c.parquet", expiration),
+        );
+        cache.insert(b.clone(), "b".into(), now + Duration::from_secs(200), now);
+        cache.insert(a.clone(), "a".into(), now + Duration::from_secs(100), now);
+
+        // only the URL expiring first is evicted once the cache is full.
+        cache.insert(c.clone(), "c".into(), now + Duration::from_secs(300), now);
+        assert_eq!(cache.get(&a, now), None);
+        assert_eq!(cache.get(&b, now), Some("b".into()));
+        assert_eq!(cache.get(&c, now), Some("c".into()));
+
+        // re-signed URLs replace their previous entry.
+        cache.insert(b.clone(), "b2".into(), now + Duration::from_secs(400), now);
+        assert_eq!(cache.get(&b, now), Some("b2".into()));
+        assert_eq!(cache.get(&c, now), Some("c".into()));
+
+        // expired URLs are evicted even before the cache is full.
+        let later = now + Duration::from_secs(350);
+        cache.insert(a.clone(), "a2".into(), later + expiration, later);
+        assert_eq!(cache.get(&a, later), Some("a2".into()));
+        assert_eq!(cache.get(&b, later), Some("b2".into()));
+        assert_eq!(cache.entries.lock().unwrap().urls.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_aws_sign_local() {
         let creds = AwsCredentials::new("test", "test", None, None);
@@ -5,7 +5,12 @@ use deltalake::schema::Schema;
 
 use crate::server::utilities::deltalake::{Stats, ValueType};
 
-static KEYWORDS: &[char] = &[' ', '=', '\'', '\"', '>', '<'];
+static KEYWORDS: &[char] = &[' ', '=', '\'', '\"', '>', '<', '!'];
+
+// NOTE: Predicate hints are provided by clients, so pathological inputs are rejected up front.
+const MAX_EXPRESSION_LENGTH: usize = 4096;
+
+const MAX_NESTING_DEPTH: usize = 32;
 
 #[derive(Debug, PartialEq, Eq)]
 enum Token {
@@ -75,6 +80,14 @@ impl Token {
                         tokens.push_back(Token::LT);
                     }
                 }
+                '!' => {
+                    if iter.peek() == Some(&'=') {
+                        iter.next();
+                        tokens.push_back(Token::NE);
+                    } else {
+                        return Err(anyhow!("could not recognize a character: {}", c));
+                    }
+                }
                 c if !KEYWORDS.contains(&c) => {
                     let tail: String = iter
                         .by_ref()
@@ -226,12 +239,44 @@ impl Utility {
                 let Ok(ref value) = value.parse::<T>() else {
                     return true;
                 };
-                min != value && value != max
+                // NOTE: Only files whose values all equal the given value can be skipped.
+                !(min == value && value == max)
+            }
+        }
+    }
+
+    fn limit(code: &str) -> Result<()> {
+        let mut depth: usize = 0;
+        let mut quote: Option<char> = None;
+        for c in code.chars() {
+            match (quote, c) {
+                (Some(q), c) if q == c => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '\"') => quote = Some(c),
+                (None, '(') => {
+                    depth += 1;
+                    if depth > MAX_NESTING_DEPTH {
+                        return Err(anyhow!(
+                            "SQL expression exceeds maximum nesting depth of {}",
+                            MAX_NESTING_DEPTH
+                        ));
+                    }
+                }
+                (None, ')') => depth = depth.saturating_sub(1),
+                _ => {}
             }
         }
+        if code.len() > MAX_EXPRESSION_LENGTH {
+            return Err(anyhow!(
+                "SQL expression exceeds maximum length of {} bytes",
+                MAX_EXPRESSION_LENGTH
+            ));
+        }
+        Ok(())
     }
 
     pub fn parse(code: String) -> Result<PartitionFilter> {
+        Self::limit(&code).context("SQL expression is too complex")?;
         let mut tokens = Token::lex(code).context("failed to lex given string")?;
         let column = Self::column(&mut tokens)
             .context("first entry of SQL expression should be column name")?;
@@ -641,4 +686,89 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_parse_bang_not_equal() {
+        let predicate = Utility::parse("id != 5".into()).expect("expression should be parsed");
+        assert_eq!(
+            predicate,
+            PartitionFilter {
+                column: "id".into(),
+                predicate: Predicate::NotEqual("5".into())
+            }
+        );
+        let predicate = Utility::parse("id!='5'".into()).expect("expression should be parsed");
+        assert_eq!(predicate.predicate, Predicate::NotEqual("5".into()));
+        assert!(Utility::parse("id ! 5".into()).is_err());
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        for (operator, predicate) in [
+            ("=", Predicate::Equal("5".into())),
+            (">", Predicate::GreaterThan("5".into())),
+            ("<", Predicate::LessThan("5".into())),
+            (">=", Predicate::GreaterEqual("5".into())),
+            ("<=", Predicate::LessEqual("5".into())),
+            ("<>", Predicate::NotEqual("5".into())),
+            ("!=", Predicate::NotEqual("5".into())),
+        ] {
+            let filter =
+                Utility::parse(format!("id {} 5", operator)).expect("expression should be parsed");
+            assert_eq!(filter.predicate, predicate, "operator {}", operator);
+        }
+    }
+
+    #[test]
+    fn test_check_boundaries() {
+        // a file with values in [5, 10] and no nulls.
+        let check = |predicate: Predicate| Utility::check(&predicate, &5_i64, &10_i64, &0);
+        assert!(check(Predicate::Equal("5".into())));
+        assert!(check(Predicate::Equal("10".into())));
+        assert!(!check(Predicate::Equal("4".into())));
+        assert!(!check(Predicate::Equal("11".into())));
+
+        assert!(check(Predicate::GreaterThan("9".into())));
+        assert!(!check(Predicate::GreaterThan("10".into())));
+        assert!(check(Predicate::GreaterEqual("10".into())));
+        assert!(!check(Predicate::GreaterEqual("11".into())));
+
+        assert!(check(Predicate::LessThan("6".into())));
+        assert!(!check(Predicate::LessThan("5".into())));
+        assert!(check(Predicate::LessEqual("5".into())));
+        assert!(!check(Predicate::LessEqual("4".into())));
+
+        assert!(check(Predicate::NotEqual("5".into())));
+        assert!(check(Predicate::NotEqual("10".into())));
+        assert!(check(Predicate::NotEqual("7".into())));
+        assert!(!Utility::check(
+            &Predicate::NotEqual("5".into()),
+            &5_i64,
+            &5_i64,
+            &0
+        ));
+
+        // values that cannot be compared never skip files.
+        assert!(check(Predicate::GreaterThan("abc".into())));
+    }
+
+    #[test]
+    fn test_parse_nested_expression() {
+        let expr = format!("{}a = 1{}", "(".repeat(10000), ")".repeat(10000));
+        let err = Utility::parse(expr).expect_err("deeply nested expression should be rejected");
+        assert!(format!("{:#}", err).contains("nesting depth"));
+    }
+
+    #[test]
+    fn test_parse_long_expression() {
+        let expr = format!("a = '{}'", "x".repeat(MAX_EXPRESSION_LENGTH));
+        let err = Utility::parse(expr).expect_err("long expression should be rejected");
+        assert!(format!("{:#}", err).contains("maximum length"));
+        let expr = format!("a = '{}'", "(".repeat(MAX_NESTING_DEPTH + 1));
+        let predicate = Utility::parse(expr).expect("quoted parentheses should not be nested");
+        assert_eq!(
+            predicate.predicate,
+            Predicate::Equal("(".repeat(MAX_NESTING_DEPTH + 1))
+        );
+    }
 }
@@ -7,6 +7,7 @@ use std::io::Result;
 use std::io::Write;
 use std::path::Path;
 use tempfile::NamedTempFile;
+use tempfile::TempDir;
 
 pub fn read_lines(path: &Path) -> Result<Lines<BufReader<File>>> {
     let file = File::open(&path)?;
@@ -19,6 +20,10 @@ pub fn tempfile(content: &str) -> Result<NamedTempFile> {
     Ok(file)
 }
 
+pub fn tempdir() -> Result<TempDir> {
+    TempDir::new()
+}
+
 pub fn persist<'a>(content: &'a str, path: &'a Path) -> Result<&'a Path> {
     let mut file = File::create(&path)?;
     file.write_all(content.as_bytes())?;